@@ -30,7 +30,9 @@ fn main() {
         .setup(move |app| {
             // Spawn the axum server in background
             let _handle = tauri::async_runtime::spawn(async move {
-                if let Err(e) = hive_lib::webui::start_server_async(port).await {
+                if let Err(e) = hive_lib::webui::start_server_async(
+                    hive_lib::webui::ServerOptions::local(port),
+                ).await {
                     eprintln!("Server error: {}", e);
                 }
             });
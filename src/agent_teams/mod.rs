@@ -163,6 +163,30 @@ pub fn preseed_tasks(
     team_name: &str,
     tasks: &[StructuredTask],
     drone_dir: &Path,
+) -> Result<Vec<AgentTeamTask>> {
+    seed_work_tasks(team_name, tasks, drone_dir, None)
+}
+
+/// Pre-seed a single task appended to `tasks` after the drone started,
+/// leaving the files of the other tasks untouched.
+pub fn preseed_added_task(
+    team_name: &str,
+    tasks: &[StructuredTask],
+    task_number: usize,
+    drone_dir: &Path,
+) -> Result<Option<AgentTeamTask>> {
+    Ok(
+        seed_work_tasks(team_name, tasks, drone_dir, Some(task_number))?
+            .into_iter()
+            .next(),
+    )
+}
+
+fn seed_work_tasks(
+    team_name: &str,
+    tasks: &[StructuredTask],
+    drone_dir: &Path,
+    only: Option<usize>,
 ) -> Result<Vec<AgentTeamTask>> {
     // Filter to Work tasks only
     let work_tasks: Vec<&StructuredTask> = tasks
@@ -194,6 +218,9 @@ pub fn preseed_tasks(
     let mut seeded = Vec::new();
 
     for (idx, task) in work_tasks.iter().enumerate() {
+        if only.is_some_and(|n| n != task.number) {
+            continue;
+        }
         let id = (idx + 1).to_string();
         let task_path = tasks_dir.join(format!("{id}.json"));
 
@@ -10,32 +10,24 @@ fn test_preseed_tasks_filters_work_only() {
         StructuredTask {
             number: 1,
             title: "Setup".to_string(),
-            body: String::new(),
             task_type: TaskType::Setup,
-            model: None,
-            parallel: false,
-            files: Vec::new(),
-            depends_on: Vec::new(),
+            ..Default::default()
         },
         StructuredTask {
             number: 2,
             title: "Implement feature".to_string(),
             body: "Do the work".to_string(),
-            task_type: TaskType::Work,
             model: Some("sonnet".to_string()),
             parallel: true,
             files: vec!["src/main.rs".to_string()],
-            depends_on: Vec::new(),
+            ..Default::default()
         },
         StructuredTask {
             number: 3,
             title: "Create PR".to_string(),
-            body: String::new(),
             task_type: TaskType::Pr,
-            model: None,
-            parallel: false,
-            files: Vec::new(),
             depends_on: vec![2],
+            ..Default::default()
         },
     ];
 
@@ -78,22 +70,13 @@ fn test_preseed_tasks_maps_depends_on() {
         StructuredTask {
             number: 2,
             title: "Task A".to_string(),
-            body: String::new(),
-            task_type: TaskType::Work,
-            model: None,
-            parallel: false,
-            files: Vec::new(),
-            depends_on: Vec::new(),
+            ..Default::default()
         },
         StructuredTask {
             number: 3,
             title: "Task B".to_string(),
-            body: String::new(),
-            task_type: TaskType::Work,
-            model: None,
-            parallel: false,
-            files: Vec::new(),
             depends_on: vec![2],
+            ..Default::default()
         },
     ];
 
@@ -108,6 +91,50 @@ fn test_preseed_tasks_maps_depends_on() {
     let _ = cleanup_team(&team_name);
 }
 
+#[test]
+fn test_preseed_added_task_leaves_others_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let drone_dir = dir.path().join("drone");
+    fs::create_dir_all(&drone_dir).unwrap();
+
+    let work = |number: usize, title: &str, depends_on: Vec<usize>| StructuredTask {
+        number,
+        title: title.to_string(),
+        depends_on,
+        ..Default::default()
+    };
+    let mut tasks = vec![work(2, "Task A", Vec::new())];
+
+    let team_name = format!("test-added-{}", std::process::id());
+    preseed_tasks(&team_name, &tasks, &drone_dir).unwrap();
+    let first_path = team_tasks_dir(&team_name).join("1.json");
+    fs::write(
+        &first_path,
+        fs::read_to_string(&first_path)
+            .unwrap()
+            .replace("\"pending\"", "\"in_progress\""),
+    )
+    .unwrap();
+
+    tasks.push(work(3, "Task B", vec![2]));
+    let added = preseed_added_task(&team_name, &tasks, 3, &drone_dir)
+        .unwrap()
+        .unwrap();
+    assert_eq!(added.id, "2");
+    assert_eq!(added.subject, "US-002: Task B");
+    assert_eq!(added.blocked_by, vec!["1"]);
+
+    // The running task keeps its state and is not announced twice
+    assert!(fs::read_to_string(&first_path)
+        .unwrap()
+        .contains("in_progress"));
+    let events = fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert_eq!(events.matches("TaskCreate").count(), 2);
+    assert!(events.contains("Task B"));
+
+    let _ = cleanup_team(&team_name);
+}
+
 #[test]
 fn test_agent_team_task_serialization() {
     let task = AgentTeamTask {
@@ -4,7 +4,9 @@ pub mod native_team;
 use anyhow::Result;
 use std::path::PathBuf;
 
-use crate::types::StructuredTask;
+use crate::types::{
+    BudgetLimits, QualityGateSpec, RetryPolicy, SandboxConfig, StructuredTask, TaskLimits,
+};
 
 /// Configuration for spawning a drone process.
 #[derive(Clone)]
@@ -32,6 +34,25 @@ pub struct SpawnConfig {
     pub mode: String,
     /// Detected project languages (e.g., ["rust", "node"])
     pub project_languages: Vec<String>,
+    /// Spending caps enforced by the native team coordinator
+    pub budget: BudgetLimits,
+    /// Quality gates declared by the plan or `.hive/config.json`
+    pub quality_gates: Vec<QualityGateSpec>,
+    /// How many follow-up tasks workers may add with `ProposeTask`
+    pub max_proposed_tasks: usize,
+    /// Triage tasks that used up their retries instead of failing the drone
+    pub triage: bool,
+    /// Retry policy for tasks whose plan bullets leave a setting unset
+    pub retry: RetryPolicy,
+    /// Timeout and turn budget for tasks whose plan bullets leave one unset
+    pub task_limits: TaskLimits,
+    /// Process sandbox for Bash calls and quality gates
+    pub sandbox: SandboxConfig,
+    /// The repository's shared git directory, writable in the sandbox.
+    /// Looked up by the coordinator when it starts.
+    pub git_common_dir: Option<PathBuf>,
+    /// Give each worker a persistent shell for its Bash calls
+    pub persistent_shell: bool,
 }
 
 /// Handle returned by a backend after spawning a drone.
@@ -0,0 +1,110 @@
+//! Approval gates: work parked until a reviewer approves or rejects it.
+//!
+//! A work task with `- approval: required` waits after its worker finishes,
+//! before its branch is merged. An `approval: required` PR task holds the
+//! drone before the PR phase. Pending approvals are written to
+//! `<drone_dir>/approvals.json` for the CLI and dashboard; decisions arrive
+//! through the control socket.
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const APPROVALS_FILE: &str = "approvals.json";
+
+/// Diffs beyond this many bytes are cut; reviewers can inspect the branch.
+const MAX_DIFF_LEN: usize = 200_000;
+
+/// Work waiting for a reviewer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingApproval {
+    /// Plan task number; `None` for the gate before the PR phase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<usize>,
+    pub title: String,
+    /// Unified diff of the changes under review
+    pub diff: String,
+    /// Worker notes (or verification status) shown next to the diff
+    #[serde(default)]
+    pub notes: String,
+    pub requested: String,
+}
+
+impl PendingApproval {
+    pub fn new(task: Option<usize>, title: String, diff: String, notes: String) -> Self {
+        Self {
+            task,
+            title,
+            diff: truncate_diff(diff),
+            notes,
+            requested: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// A reviewer's answer, queued by the control socket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalDecision {
+    pub task: Option<usize>,
+    pub approved: bool,
+    pub comment: Option<String>,
+}
+
+/// Replace the drone's pending approvals; an empty list removes the file.
+pub fn save(drone_dir: &Path, pending: &[PendingApproval]) -> Result<()> {
+    let path = drone_dir.join(APPROVALS_FILE);
+    if pending.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return Ok(());
+    }
+    std::fs::write(path, serde_json::to_string_pretty(pending)?)?;
+    Ok(())
+}
+
+pub fn load(drone_dir: &Path) -> Vec<PendingApproval> {
+    std::fs::read_to_string(drone_dir.join(APPROVALS_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn truncate_diff(diff: String) -> String {
+    if diff.len() <= MAX_DIFF_LEN {
+        return diff;
+    }
+    let mut end = MAX_DIFF_LEN;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n... diff truncated ...\n", &diff[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_pending_approvals() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pending = vec![
+            PendingApproval::new(Some(2), "Rotate keys".into(), "diff".into(), String::new()),
+            PendingApproval::new(None, "Pull request".into(), "diff".into(), String::new()),
+        ];
+        save(tmp.path(), &pending).unwrap();
+        assert_eq!(load(tmp.path()), pending);
+
+        // Resolving the last approval removes the file
+        save(tmp.path(), &[]).unwrap();
+        assert!(load(tmp.path()).is_empty());
+        assert!(!tmp.path().join(APPROVALS_FILE).exists());
+    }
+
+    #[test]
+    fn test_long_diffs_are_truncated() {
+        let approval =
+            PendingApproval::new(None, "PR".into(), "é".repeat(MAX_DIFF_LEN), String::new());
+        assert!(approval.diff.len() < MAX_DIFF_LEN + 32);
+        assert!(approval.diff.ends_with("... diff truncated ...\n"));
+    }
+}
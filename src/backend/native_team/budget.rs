@@ -0,0 +1,170 @@
+//! Spend tracking and budget enforcement for native-team drones.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::commands::common::cost::parse_cost_from_ndjson;
+use crate::types::BudgetLimits;
+
+/// USD and tokens spent so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Spend {
+    pub usd: f64,
+    pub tokens: u64,
+}
+
+/// Running spend totals for a drone and each of its tasks.
+#[derive(Debug, Default)]
+pub struct SpendLedger {
+    totals: Mutex<LedgerTotals>,
+}
+
+#[derive(Debug, Default)]
+struct LedgerTotals {
+    drone: Spend,
+    tasks: HashMap<String, Spend>,
+}
+
+impl SpendLedger {
+    /// Seed the ledger from an existing cost.ndjson, so a resumed drone
+    /// counts what earlier runs already spent.
+    pub fn load(cost_path: &Path) -> Self {
+        let summary = parse_cost_from_ndjson(cost_path);
+        let totals = LedgerTotals {
+            drone: Spend {
+                usd: summary.total_cost_usd,
+                tokens: summary.total_tokens(),
+            },
+            tasks: summary
+                .by_task
+                .iter()
+                .map(|(id, c)| {
+                    let spend = Spend {
+                        usd: c.cost_usd,
+                        tokens: c.total_tokens(),
+                    };
+                    (id.clone(), spend)
+                })
+                .collect(),
+        };
+        Self {
+            totals: Mutex::new(totals),
+        }
+    }
+
+    pub fn record(&self, task_id: Option<&str>, usd: f64, tokens: u64) {
+        let Ok(mut totals) = self.totals.lock() else {
+            return;
+        };
+        totals.drone.usd += usd;
+        totals.drone.tokens += tokens;
+        if let Some(id) = task_id {
+            let task = totals.tasks.entry(id.to_string()).or_default();
+            task.usd += usd;
+            task.tokens += tokens;
+        }
+    }
+
+    pub fn drone(&self) -> Spend {
+        self.totals.lock().map(|t| t.drone).unwrap_or_default()
+    }
+
+    pub fn task(&self, task_id: &str) -> Spend {
+        self.totals
+            .lock()
+            .ok()
+            .and_then(|t| t.tasks.get(task_id).copied())
+            .unwrap_or_default()
+    }
+}
+
+/// A cap that has been reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetBreach {
+    /// `None` for the drone-wide budget
+    pub task_id: Option<String>,
+    /// The cap that was hit, e.g. "$5.00" or "2000000 tokens"
+    pub limit: String,
+    pub spent: Spend,
+}
+
+/// Check the drone-wide caps.
+pub fn check_drone(limits: &BudgetLimits, ledger: &SpendLedger) -> Option<BudgetBreach> {
+    let spent = ledger.drone();
+    reached(limits.drone_usd, limits.drone_tokens, spent).map(|limit| BudgetBreach {
+        task_id: None,
+        limit,
+        spent,
+    })
+}
+
+/// Check the per-task caps for one task.
+pub fn check_task(
+    limits: &BudgetLimits,
+    ledger: &SpendLedger,
+    task_id: &str,
+) -> Option<BudgetBreach> {
+    let spent = ledger.task(task_id);
+    reached(limits.task_usd, limits.task_tokens, spent).map(|limit| BudgetBreach {
+        task_id: Some(task_id.to_string()),
+        limit,
+        spent,
+    })
+}
+
+fn reached(usd: Option<f64>, tokens: Option<u64>, spent: Spend) -> Option<String> {
+    if let Some(cap) = usd.filter(|cap| spent.usd >= *cap) {
+        return Some(format!("${cap:.2}"));
+    }
+    tokens
+        .filter(|cap| spent.tokens >= *cap)
+        .map(|cap| format!("{cap} tokens"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drone_and_task_caps() {
+        let ledger = SpendLedger::default();
+        let limits = BudgetLimits {
+            drone_usd: Some(1.0),
+            task_tokens: Some(1000),
+            ..Default::default()
+        };
+
+        ledger.record(Some("1"), 0.4, 600);
+        ledger.record(Some("2"), 0.4, 600);
+        assert_eq!(check_drone(&limits, &ledger), None);
+        assert_eq!(check_task(&limits, &ledger, "1"), None);
+
+        ledger.record(Some("1"), 0.2, 400);
+        let task = check_task(&limits, &ledger, "1").unwrap();
+        assert_eq!(task.task_id.as_deref(), Some("1"));
+        assert_eq!(task.limit, "1000 tokens");
+        assert_eq!(check_task(&limits, &ledger, "2"), None);
+
+        let drone = check_drone(&limits, &ledger).unwrap();
+        assert_eq!(drone.task_id, None);
+        assert_eq!(drone.limit, "$1.00");
+        assert_eq!(drone.spent.tokens, 1600);
+    }
+
+    #[test]
+    fn test_ledger_seeded_from_cost_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("cost.ndjson");
+        std::fs::write(
+            &path,
+            r#"{"worker":"w","task":"3","input_tokens":10,"output_tokens":5,"cache_read":0,"cache_create":0,"cost_usd":2.5}"#,
+        )
+        .unwrap();
+
+        let ledger = SpendLedger::load(&path);
+        assert_eq!(ledger.drone().tokens, 15);
+        assert_eq!(ledger.task("3").usd, 2.5);
+        assert_eq!(ledger.task("4"), Spend::default());
+    }
+}
@@ -0,0 +1,301 @@
+//! Local control socket for a running coordinator.
+//!
+//! Each native-team coordinator listens on `.hive/drones/<name>/control.sock`.
+//! A client writes one JSON request terminated by a newline and reads one
+//! JSON response back, e.g. `{"cmd":"abort_task","task":3}` answered by
+//! `{"ok":true}`. `hive stop`, the web monitor and the MCP server use it.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::webui::chat::handlers::agentic::MessageInbox;
+
+const SOCKET_FILE: &str = "control.sock";
+
+/// How long a request may wait for the coordinator to pick it up. The
+/// coordinator answers between scheduling ticks, but not while it is
+/// running setup commands or the verify and PR phases.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A command for a running coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Phase, dispatch state and the state of every task
+    Status,
+    /// Stop every worker and end the drone
+    AbortDrone,
+    /// Stop one task's worker; the task fails without retry
+    AbortTask {
+        task: usize,
+    },
+    /// Put a failed task back in the queue
+    RetryTask {
+        task: usize,
+    },
+    SetMaxAgents {
+        max_agents: usize,
+    },
+    /// Stop starting new tasks; running workers carry on
+    Pause,
+    Resume,
+    /// Stop workers after their current API turn, save their conversations
+    /// and end the run; `hive resume` continues them
+    PauseDrone,
+    /// Accept work parked at an approval gate: a task's changes, or the PR
+    /// phase when `task` is omitted
+    Approve {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    /// Send parked work back with `comment` as feedback; without a comment
+    /// the task fails, or the drone stops before opening a PR
+    Reject {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    /// Deliver a message to a running worker after its next tool call
+    InjectMessage {
+        task: usize,
+        message: String,
+    },
+}
+
+/// The coordinator's answer to a `ControlRequest`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub data: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub fn ok(data: serde_json::Value) -> Self {
+        Self {
+            ok: true,
+            data,
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: serde_json::Value::Null,
+            error: Some(message.into()),
+        }
+    }
+}
+
+/// A request waiting for the coordinator's answer.
+pub struct ControlCall {
+    pub request: ControlRequest,
+    pub reply: oneshot::Sender<ControlResponse>,
+}
+
+pub fn socket_path(drone_dir: &Path) -> PathBuf {
+    drone_dir.join(SOCKET_FILE)
+}
+
+/// Listen on `path` and forward each request to `calls`.
+///
+/// `abort_drone` is applied here through `abort_flag` rather than queued,
+/// so a drone can be stopped in any phase.
+pub fn serve(
+    path: &Path,
+    calls: mpsc::Sender<ControlCall>,
+    abort_flag: Arc<AtomicBool>,
+) -> Result<JoinHandle<()>> {
+    // A previous coordinator that died without cleaning up leaves its socket behind
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+
+    Ok(tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let calls = calls.clone();
+            let abort_flag = abort_flag.clone();
+            tokio::spawn(async move {
+                let _ = handle_connection(stream, calls, abort_flag).await;
+            });
+        }
+    }))
+}
+
+async fn handle_connection(
+    stream: UnixStream,
+    calls: mpsc::Sender<ControlCall>,
+    abort_flag: Arc<AtomicBool>,
+) -> Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(read).read_line(&mut line).await?;
+
+    let response = match serde_json::from_str::<ControlRequest>(&line) {
+        Err(e) => ControlResponse::error(format!("Invalid request: {e}")),
+        Ok(ControlRequest::AbortDrone) => {
+            abort_flag.store(true, Ordering::Relaxed);
+            ControlResponse::ok(serde_json::Value::Null)
+        }
+        Ok(request) => forward(request, &calls).await,
+    };
+
+    let mut out = serde_json::to_string(&response)?;
+    out.push('\n');
+    write.write_all(out.as_bytes()).await?;
+    Ok(())
+}
+
+async fn forward(request: ControlRequest, calls: &mpsc::Sender<ControlCall>) -> ControlResponse {
+    let (reply, answer) = oneshot::channel();
+    if calls.send(ControlCall { request, reply }).await.is_err() {
+        return ControlResponse::error("Coordinator is shutting down");
+    }
+    match tokio::time::timeout(REPLY_TIMEOUT, answer).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(_)) => ControlResponse::error("Coordinator is shutting down"),
+        Err(_) => ControlResponse::error(
+            "Coordinator did not answer in time (it only takes commands while dispatching tasks)",
+        ),
+    }
+}
+
+/// Send one request to the coordinator of the drone in `drone_dir`.
+pub fn send(drone_dir: &Path, request: &ControlRequest) -> Result<ControlResponse> {
+    let path = socket_path(drone_dir);
+    let mut stream = std::os::unix::net::UnixStream::connect(&path)
+        .with_context(|| format!("No coordinator is listening on {}", path.display()))?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT + Duration::from_secs(5)))?;
+
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut answer = String::new();
+    BufReader::new(stream)
+        .read_line(&mut answer)
+        .context("Failed to read control response")?;
+    serde_json::from_str(&answer).context("Invalid control response")
+}
+
+/// Messages queued for a running worker by `inject_message`.
+#[derive(Debug, Default)]
+pub struct WorkerInbox {
+    messages: Mutex<Vec<String>>,
+}
+
+impl WorkerInbox {
+    pub fn push(&self, message: String) {
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.push(message);
+        }
+    }
+}
+
+impl MessageInbox for WorkerInbox {
+    fn take(&self) -> Vec<String> {
+        self.messages
+            .lock()
+            .map(|mut m| std::mem::take(&mut *m))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_wire_format() {
+        let request: ControlRequest =
+            serde_json::from_str(r#"{"cmd":"inject_message","task":2,"message":"use v2"}"#)
+                .unwrap();
+        assert_eq!(
+            request,
+            ControlRequest::InjectMessage {
+                task: 2,
+                message: "use v2".to_string()
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&ControlRequest::SetMaxAgents { max_agents: 2 }).unwrap(),
+            r#"{"cmd":"set_max_agents","max_agents":2}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ControlRequest::PauseDrone).unwrap(),
+            r#"{"cmd":"pause_drone"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"cmd":"approve"}"#).unwrap(),
+            ControlRequest::Approve {
+                task: None,
+                comment: None
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&ControlResponse::ok(serde_json::Value::Null)).unwrap(),
+            r#"{"ok":true}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_requests_round_trip_through_socket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (tx, mut rx) = mpsc::channel::<ControlCall>(4);
+        let abort_flag = Arc::new(AtomicBool::new(false));
+        let server = serve(&socket_path(tmp.path()), tx, abort_flag.clone()).unwrap();
+        tokio::spawn(async move {
+            while let Some(call) = rx.recv().await {
+                let answer = match call.request {
+                    ControlRequest::Status => {
+                        ControlResponse::ok(serde_json::json!({"paused": false}))
+                    }
+                    other => ControlResponse::error(format!("unsupported: {other:?}")),
+                };
+                let _ = call.reply.send(answer);
+            }
+        });
+
+        let dir = tmp.path().to_path_buf();
+        let (status, pause, abort) = tokio::task::spawn_blocking(move || {
+            (
+                send(&dir, &ControlRequest::Status).unwrap(),
+                send(&dir, &ControlRequest::Pause).unwrap(),
+                send(&dir, &ControlRequest::AbortDrone).unwrap(),
+            )
+        })
+        .await
+        .unwrap();
+
+        assert!(status.ok);
+        assert_eq!(status.data["paused"], false);
+        assert!(!pause.ok);
+        assert_eq!(pause.error.as_deref(), Some("unsupported: Pause"));
+        assert!(abort.ok);
+        assert!(abort_flag.load(Ordering::Relaxed));
+        server.abort();
+    }
+
+    #[test]
+    fn test_send_without_coordinator_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = send(tmp.path(), &ControlRequest::Status).unwrap_err();
+        assert!(err.to_string().contains("No coordinator"), "{err}");
+    }
+}
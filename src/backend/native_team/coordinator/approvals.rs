@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+
+use super::super::approvals::{self, ApprovalDecision, PendingApproval};
+use super::super::control::ControlResponse;
+use super::super::paused_workers::{self, PausedWorker};
+use super::super::worker_notes::{self, WorkerNote};
+use super::super::worktrees;
+use super::{Phase, TeamCoordinator};
+use crate::types::TaskType;
+
+/// The drone worktree's HEAD when the drone first started; the PR approval
+/// diff is taken against it.
+const BASE_COMMIT_FILE: &str = ".base_commit";
+
+impl TeamCoordinator {
+    fn drone_dir(&self) -> PathBuf {
+        PathBuf::from(".hive/drones").join(&self.config.drone_name)
+    }
+
+    /// Drop approvals left by a previous run and remember the commit the
+    /// drone's work started from.
+    pub(super) async fn init_approvals(&mut self) {
+        let drone_dir = self.drone_dir();
+        let _ = approvals::save(&drone_dir, &[]);
+        let base_file = drone_dir.join(BASE_COMMIT_FILE);
+        if !base_file.exists() {
+            if let Ok(head) = worktrees::head_commit(&self.config.working_dir).await {
+                let _ = std::fs::write(base_file, head);
+            }
+        }
+    }
+
+    pub(super) fn task_needs_approval(&self, task_number: usize) -> bool {
+        self.scheduler
+            .get_task(task_number)
+            .is_some_and(|t| t.approval)
+    }
+
+    pub(super) fn pr_needs_approval(&self) -> bool {
+        self.config
+            .structured_tasks
+            .iter()
+            .any(|t| t.task_type == TaskType::Pr && t.approval)
+    }
+
+    /// Park a finished task for review instead of merging it. The worker's
+    /// changes are committed on its branch so the diff is stable.
+    pub(super) async fn request_task_approval(&mut self, task_number: usize) {
+        let task_id = self.task_number_to_id(task_number);
+        let title = self
+            .scheduler
+            .get_task(task_number)
+            .map(|t| t.title.clone())
+            .unwrap_or_default();
+        let drone_worktree = self.config.working_dir.clone();
+        let diff = match self.worktrees.get(&task_number) {
+            Some(wt) => {
+                let message = format!("hive: task {task_number} — {title}");
+                match worktrees::commit_all(wt, &message).await {
+                    Ok(_) => worktrees::branch_diff(&drone_worktree, wt).await,
+                    Err(e) => Err(e),
+                }
+            }
+            // The worker ran in the shared checkout
+            None => worktrees::diff_since(&drone_worktree, "HEAD").await,
+        }
+        .unwrap_or_else(|e| format!("Could not compute the diff: {e:#}"));
+        let notes = worker_notes::read_task_note(&self.drone_dir(), task_number)
+            .map(|note| format_note(&note))
+            .unwrap_or_default();
+
+        eprintln!("[hive] Task {task_id} is awaiting approval");
+        self.scheduler.mark_awaiting_approval(task_number);
+        self.emitter
+            .update_task_file(&task_id, "in_progress", None, Some("Awaiting approval"));
+        self.emitter.emit_approval_requested(Some(&task_id), &title);
+        self.add_pending_approval(PendingApproval::new(Some(task_number), title, diff, notes));
+        if self.phase != Phase::AwaitingApproval {
+            self.transition_phase(Phase::AwaitingApproval);
+        }
+    }
+
+    /// Queue a reviewer's decision; the coordinator applies it on its next tick.
+    pub(super) fn decide_approval(
+        &mut self,
+        task: Option<usize>,
+        approved: bool,
+        comment: Option<String>,
+    ) -> ControlResponse {
+        if !self.approvals.iter().any(|a| a.task == task) {
+            return ControlResponse::error(match task {
+                Some(n) => format!("Task {n} is not awaiting approval"),
+                None => "The drone is not waiting for approval to open a PR".to_string(),
+            });
+        }
+        if self.approval_decisions.iter().any(|d| d.task == task) {
+            return ControlResponse::error("A decision is already being applied");
+        }
+        let comment = comment
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        self.approval_decisions.push(ApprovalDecision {
+            task,
+            approved,
+            comment,
+        });
+        ControlResponse::ok(serde_json::Value::Null)
+    }
+
+    /// Merge approved tasks, send rejected ones back to their workers with
+    /// the reviewer's comment, and fail those rejected without one.
+    pub(super) async fn apply_approval_decisions(&mut self) {
+        let (decisions, pr_decisions): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.approval_decisions)
+                .into_iter()
+                .partition(|d| d.task.is_some());
+        self.approval_decisions = pr_decisions;
+
+        for decision in decisions {
+            let Some(task_number) = decision.task else {
+                continue;
+            };
+            let task_id = self.task_number_to_id(task_number);
+            self.remove_pending_approval(Some(task_number));
+            self.emitter.emit_approval_decided(
+                Some(&task_id),
+                decision.approved,
+                decision.comment.as_deref(),
+            );
+
+            if decision.approved {
+                match &decision.comment {
+                    Some(comment) => eprintln!("[hive] Task {task_id} approved: {comment}"),
+                    None => eprintln!("[hive] Task {task_id} approved"),
+                }
+                let worker_name = self
+                    .scheduler
+                    .get_task(task_number)
+                    .map(|t| t.worker_name())
+                    .unwrap_or_default();
+                self.complete_worker_task(task_number, worker_name).await;
+            } else if let Some(comment) = decision.comment {
+                eprintln!("[hive] Task {task_id} rejected, sending it back to its worker");
+                self.send_back_for_rework(task_number, &comment).await;
+            } else {
+                eprintln!("[hive] Task {task_id} rejected");
+                if let Some(wt) = self.worktrees.remove(&task_number) {
+                    worktrees::remove(&self.config.working_dir, &wt).await;
+                }
+                self.scheduler.mark_failed(task_number);
+                self.emitter.update_task_file(
+                    &task_id,
+                    "completed",
+                    None,
+                    Some("Rejected by reviewer"),
+                );
+            }
+        }
+
+        if self.phase == Phase::AwaitingApproval && self.approvals.is_empty() {
+            self.transition_phase(Phase::Monitor);
+        }
+    }
+
+    /// Restart a rejected task's worker in its worktree, with the reviewer's
+    /// comment as its progress summary.
+    async fn send_back_for_rework(&mut self, task_number: usize, comment: &str) {
+        let Some(task) = self.scheduler.get_task(task_number).cloned() else {
+            return;
+        };
+        let rework = PausedWorker {
+            task_number,
+            iteration: 1,
+            messages: Vec::new(),
+            continuation: Some(rework_progress(comment)),
+        };
+        if let Err(e) = paused_workers::save(&self.drone_dir(), &rework) {
+            eprintln!("[hive] Could not save feedback for task {task_number}: {e:#}");
+        }
+        // spawn_worker_for_task reopens the worktree
+        self.worktrees.remove(&task_number);
+        self.spawn_worker_for_task(task).await;
+        self.scheduler.mark_running(task_number);
+    }
+
+    /// Hold the drone before the PR phase until a reviewer decides. Returns
+    /// `None` if the drone is aborted while waiting.
+    pub(super) async fn await_pr_approval(
+        &mut self,
+        verification_passed: bool,
+    ) -> Option<ApprovalDecision> {
+        let drone_dir = self.drone_dir();
+        let diff = match std::fs::read_to_string(drone_dir.join(BASE_COMMIT_FILE)) {
+            Ok(base) => worktrees::diff_since(&self.config.working_dir, base.trim()).await,
+            Err(e) => Err(e.into()),
+        }
+        .unwrap_or_else(|e| format!("Could not compute the diff: {e:#}"));
+
+        let mut notes = if verification_passed {
+            "All verification checks passed.".to_string()
+        } else {
+            "Some verification checks failed.".to_string()
+        };
+        for note in worker_notes::read_all_notes(&drone_dir) {
+            notes.push_str("\n\n");
+            notes.push_str(&format_note(&note));
+        }
+
+        self.transition_phase(Phase::AwaitingApproval);
+        eprintln!(
+            "[hive] Waiting for approval to open the PR (hive approve {})",
+            self.config.drone_name
+        );
+        let title = "Open the pull request".to_string();
+        self.emitter.emit_approval_requested(None, &title);
+        self.add_pending_approval(PendingApproval::new(None, title, diff, notes));
+
+        loop {
+            self.handle_control_requests();
+            if self.is_aborted() {
+                self.remove_pending_approval(None);
+                return None;
+            }
+            if let Some(i) = self
+                .approval_decisions
+                .iter()
+                .position(|d| d.task.is_none())
+            {
+                let decision = self.approval_decisions.remove(i);
+                self.remove_pending_approval(None);
+                self.emitter.emit_approval_decided(
+                    None,
+                    decision.approved,
+                    decision.comment.as_deref(),
+                );
+                return Some(decision);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    /// On pause, hand tasks awaiting approval to the paused-worker store so
+    /// the resumed drone asks for their review again instead of redoing them.
+    pub(super) fn park_pending_approvals(&mut self) {
+        let drone_dir = self.drone_dir();
+        for approval in std::mem::take(&mut self.approvals) {
+            let Some(task_number) = approval.task else {
+                continue;
+            };
+            let parked = PausedWorker {
+                task_number,
+                iteration: 1,
+                messages: Vec::new(),
+                continuation: Some(
+                    "Your changes are committed and were awaiting review when the drone paused. \
+                     Check they are complete, then finish."
+                        .to_string(),
+                ),
+            };
+            let _ = paused_workers::save(&drone_dir, &parked);
+        }
+        let _ = approvals::save(&drone_dir, &[]);
+    }
+
+    fn add_pending_approval(&mut self, approval: PendingApproval) {
+        self.approvals.push(approval);
+        self.save_approvals();
+    }
+
+    fn remove_pending_approval(&mut self, task: Option<usize>) {
+        self.approvals.retain(|a| a.task != task);
+        self.save_approvals();
+    }
+
+    fn save_approvals(&self) {
+        if let Err(e) = approvals::save(&self.drone_dir(), &self.approvals) {
+            eprintln!("[hive] Could not write approvals: {e:#}");
+        }
+    }
+}
+
+fn format_note(note: &WorkerNote) -> String {
+    let mut out = format!("Task {}: {}", note.task_number, note.task_title);
+    if !note.files_changed.is_empty() {
+        out.push_str(&format!(
+            "\nFiles changed: {}",
+            note.files_changed.join(", ")
+        ));
+    }
+    if !note.summary.is_empty() {
+        out.push_str(&format!("\n{}", note.summary));
+    }
+    out
+}
+
+fn rework_progress(comment: &str) -> String {
+    format!(
+        "Your changes are committed on this branch, but a reviewer rejected them:\n\n> {}\n\n\
+         Address this feedback before finishing.",
+        comment.replace('\n', "\n> ")
+    )
+}
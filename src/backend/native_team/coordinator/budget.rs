@@ -0,0 +1,54 @@
+use std::sync::atomic::Ordering;
+
+use super::super::budget::{self, BudgetBreach};
+use super::TeamCoordinator;
+
+impl TeamCoordinator {
+    /// Stop work that has reached its budget. A drone-wide breach aborts the
+    /// whole team; a task breach aborts only that task's worker, which is
+    /// then failed without retry.
+    pub(super) fn enforce_budget(&mut self) {
+        let limits = self.config.budget;
+        if limits.is_unlimited() {
+            return;
+        }
+
+        if !self.over_budget {
+            if let Some(breach) = budget::check_drone(&limits, self.emitter.ledger()) {
+                self.report_breach(&breach);
+                self.over_budget = true;
+                self.abort_flag.store(true, Ordering::Relaxed);
+                self.abort_all_workers();
+                return;
+            }
+        }
+
+        let breached: Vec<(usize, BudgetBreach)> = self
+            .workers
+            .keys()
+            .filter(|n| !self.budget_stopped.contains(n))
+            .filter_map(|&n| {
+                budget::check_task(&limits, self.emitter.ledger(), &n.to_string()).map(|b| (n, b))
+            })
+            .collect();
+        for (task_number, breach) in breached {
+            self.report_breach(&breach);
+            if let Some(handle) = self.workers.get(&task_number) {
+                handle.abort_flag.store(true, Ordering::Relaxed);
+            }
+            self.budget_stopped.insert(task_number);
+        }
+    }
+
+    fn report_breach(&self, breach: &BudgetBreach) {
+        let scope = match &breach.task_id {
+            Some(id) => format!("Task {id}"),
+            None => "Drone".to_string(),
+        };
+        eprintln!(
+            "[hive] {scope} reached its budget of {} (spent ${:.2}, {} tokens), stopping",
+            breach.limit, breach.spent.usd, breach.spent.tokens
+        );
+        self.emitter.emit_budget_exceeded(breach);
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use tokio::sync::mpsc;
+
+use super::super::control::{self, ControlRequest, ControlResponse};
+use super::super::scheduler::TaskState;
+use super::{Phase, TeamCoordinator};
+
+impl TeamCoordinator {
+    /// Start listening for control requests. A drone without a socket
+    /// still runs; it just can't be steered.
+    pub(super) fn start_control_socket(&mut self, drone_dir: &Path) {
+        let (tx, rx) = mpsc::channel(16);
+        match control::serve(
+            &control::socket_path(drone_dir),
+            tx,
+            self.abort_flag.clone(),
+        ) {
+            Ok(server) => {
+                self.control_rx = Some(rx);
+                self.control_server = Some(server);
+            }
+            Err(e) => eprintln!("[hive] Control socket unavailable: {e:#}"),
+        }
+    }
+
+    /// Answer every control request queued since the last tick.
+    pub(super) fn handle_control_requests(&mut self) {
+        while let Some(call) = self.control_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+            let response = self.handle_control(call.request);
+            let _ = call.reply.send(response);
+        }
+    }
+
+    fn handle_control(&mut self, request: ControlRequest) -> ControlResponse {
+        match request {
+            ControlRequest::Status => ControlResponse::ok(self.control_status()),
+            ControlRequest::AbortDrone => {
+                self.abort_flag.store(true, Ordering::Relaxed);
+                self.abort_all_workers();
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::AbortTask { task } => self.abort_task(task),
+            ControlRequest::RetryTask { task } => {
+                if !self.scheduler.reset(task) {
+                    return ControlResponse::error(format!("Task {task} has not failed"));
+                }
+                self.operator_stopped.remove(&task);
+                let task_id = self.task_number_to_id(task);
+                eprintln!("[hive] Retrying task {task_id} on request");
+                self.emitter
+                    .update_task_file(&task_id, "pending", None, None);
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::SetMaxAgents { max_agents } => {
+                if max_agents == 0 {
+                    return ControlResponse::error("max_agents must be at least 1");
+                }
+                eprintln!("[hive] Max agents set to {max_agents}");
+                self.config.max_agents = max_agents;
+                self.scheduler.set_max_concurrent(max_agents);
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::Pause => {
+                eprintln!("[hive] Dispatch paused");
+                self.dispatch_paused = true;
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::Resume => {
+                eprintln!("[hive] Dispatch resumed");
+                self.dispatch_paused = false;
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::PauseDrone => {
+                let dispatching = matches!(self.phase, Phase::Monitor | Phase::AwaitingApproval);
+                if !dispatching || self.approvals.iter().any(|a| a.task.is_none()) {
+                    return ControlResponse::error(
+                        "A drone can only be paused while it is dispatching tasks",
+                    );
+                }
+                eprintln!("[hive] Pausing: workers stop after their current turn");
+                self.pause_flag.store(true, Ordering::Relaxed);
+                ControlResponse::ok(serde_json::Value::Null)
+            }
+            ControlRequest::Approve { task, comment } => self.decide_approval(task, true, comment),
+            ControlRequest::Reject { task, comment } => self.decide_approval(task, false, comment),
+            ControlRequest::InjectMessage { task, message } => match self.workers.get(&task) {
+                Some(handle) => {
+                    handle.inbox.push(message);
+                    ControlResponse::ok(serde_json::Value::Null)
+                }
+                None => ControlResponse::error(format!("Task {task} has no running worker")),
+            },
+        }
+    }
+
+    fn abort_task(&mut self, task: usize) -> ControlResponse {
+        if self.approvals.iter().any(|a| a.task == Some(task)) {
+            eprintln!("[hive] Stopping task {task} on request");
+            return self.decide_approval(Some(task), false, None);
+        }
+        if let Some(handle) = self.workers.get(&task) {
+            eprintln!("[hive] Stopping task {task} on request");
+            handle.abort_flag.store(true, Ordering::Relaxed);
+            self.operator_stopped.insert(task);
+            return ControlResponse::ok(serde_json::Value::Null);
+        }
+        if self.scheduler.state(task) != Some(TaskState::Pending) {
+            return ControlResponse::error(format!("Task {task} is not pending or running"));
+        }
+        eprintln!("[hive] Cancelling task {task} on request");
+        self.operator_stopped.insert(task);
+        self.scheduler.mark_failed(task);
+        let task_id = self.task_number_to_id(task);
+        self.emitter
+            .update_task_file(&task_id, "completed", None, Some("Stopped by operator"));
+        ControlResponse::ok(serde_json::Value::Null)
+    }
+
+    fn control_status(&self) -> serde_json::Value {
+        let tasks: Vec<serde_json::Value> = self
+            .scheduler
+            .tasks()
+            .map(|(task, state)| {
+                serde_json::json!({
+                    "number": task.number,
+                    "title": task.title,
+                    "state": state,
+                    "worker": self.workers.get(&task.number).map(|h| &h.worker_name),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "phase": self.phase.as_str(),
+            "paused": self.dispatch_paused,
+            "awaiting_approval": self.approvals.iter().map(|a| a.task).collect::<Vec<_>>(),
+            "pausing": self.pause_flag.load(Ordering::Relaxed),
+            "max_agents": self.scheduler.max_concurrent(),
+            "tasks": tasks,
+        })
+    }
+}
@@ -0,0 +1,188 @@
+use tokio::task::JoinHandle;
+
+use super::super::phases;
+use super::super::worktrees::{self, MergeOutcome, WorkerWorktree};
+use super::TeamCoordinator;
+
+/// How far merging a finished task's changes got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Integration {
+    Merged,
+    /// A resolver agent is working on a conflict
+    Resolving,
+    Failed,
+}
+
+/// A conflict resolver agent running in the drone worktree. Only one runs
+/// at a time, since it leaves the worktree mid-merge.
+pub(super) struct ConflictResolution {
+    wt: WorkerWorktree,
+    files: Vec<String>,
+    worker_name: String,
+    handle: JoinHandle<bool>,
+}
+
+impl ConflictResolution {
+    pub(super) fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    pub(super) fn abort(&self) {
+        self.handle.abort();
+    }
+}
+
+impl TeamCoordinator {
+    /// Commit a finished worker's checkout and merge it into the drone worktree.
+    ///
+    /// Returns `Merged` when the task's changes are integrated (or the worker
+    /// ran in the shared checkout). On a conflict the task enters the
+    /// `Conflict` state and a resolver agent gets one attempt in the
+    /// background; if that fails the merge is aborted and the task is
+    /// retried from the updated drone branch.
+    pub(super) async fn integrate_worker_changes(
+        &mut self,
+        task_number: usize,
+        worker_name: String,
+    ) -> Integration {
+        let Some(wt) = self.worktrees.remove(&task_number) else {
+            return Integration::Merged;
+        };
+        let title = self
+            .scheduler
+            .get_task(task_number)
+            .map(|t| t.title.clone())
+            .unwrap_or_default();
+        let message = format!("hive: task {task_number} — {title}");
+        let drone_worktree = self.config.working_dir.clone();
+
+        let outcome = match worktrees::commit_all(&wt, &message).await {
+            Ok(_) => worktrees::merge_back(&drone_worktree, &wt, &message).await,
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(MergeOutcome::Merged) => {
+                worktrees::remove(&drone_worktree, &wt).await;
+                Integration::Merged
+            }
+            Ok(MergeOutcome::Conflict { files }) => {
+                self.start_conflict_resolver(wt, files, worker_name)
+            }
+            Err(e) => {
+                let task_id = self.task_number_to_id(task_number);
+                let error_msg = format!("Merging worker branch failed: {e:#}");
+                eprintln!("[hive] Task {task_id}: {error_msg}");
+                self.emitter.emit_worker_error(&task_id, &error_msg, None);
+                self.record_failure(task_number, &error_msg, Vec::new());
+                worktrees::remove(&drone_worktree, &wt).await;
+                self.scheduler.mark_failed(task_number);
+                self.retry_or_fail(task_number);
+                Integration::Failed
+            }
+        }
+    }
+
+    /// Mark the task `Conflict` and start a resolver agent in the drone
+    /// worktree. It runs in the background, like a worker; the monitor loop
+    /// picks up its outcome with `collect_conflict_resolution`.
+    fn start_conflict_resolver(
+        &mut self,
+        wt: WorkerWorktree,
+        files: Vec<String>,
+        worker_name: String,
+    ) -> Integration {
+        let task_number = wt.task_number;
+        let task_id = self.task_number_to_id(task_number);
+
+        eprintln!(
+            "[hive] Task {task_id}: merge conflict in {} file(s), starting resolver",
+            files.len()
+        );
+        self.scheduler.mark_conflict(task_number);
+        self.emitter
+            .update_task_file(&task_id, "conflict", None, Some("Resolving merge conflict"));
+        self.emitter.emit_task_update(&task_id, "conflict", None);
+
+        let Some(task) = self.scheduler.get_task(task_number).cloned() else {
+            return Integration::Failed;
+        };
+        let config = self.config.clone();
+        let creds = self.creds.clone();
+        let store = self.session_store.clone();
+        let emitter = self.emitter.clone();
+        let conflicted = files.clone();
+        let handle = tokio::spawn(async move {
+            phases::run_conflict_resolver(&config, &creds, store, &emitter, &task, &conflicted)
+                .await
+                && worktrees::conclude_merge(&config.working_dir, &conflicted)
+                    .await
+                    .unwrap_or(false)
+        });
+        self.resolving = Some(ConflictResolution {
+            wt,
+            files,
+            worker_name,
+            handle,
+        });
+        Integration::Resolving
+    }
+
+    /// If the resolver has finished, complete its task or abort the merge
+    /// and retry the task, then merge the tasks that finished meanwhile.
+    pub(super) async fn collect_conflict_resolution(&mut self) {
+        if !self
+            .resolving
+            .as_ref()
+            .is_some_and(ConflictResolution::is_finished)
+        {
+            return;
+        }
+        let Some(resolution) = self.resolving.take() else {
+            return;
+        };
+        let ConflictResolution {
+            wt,
+            files,
+            worker_name,
+            handle,
+        } = resolution;
+        let resolved = handle.await.unwrap_or(false);
+
+        if resolved {
+            let task_id = self.task_number_to_id(wt.task_number);
+            self.emitter
+                .emit_merge_conflict(&task_id, &wt.branch, &files, "resolved");
+            worktrees::remove(&self.config.working_dir, &wt).await;
+            self.finish_worker_task(wt.task_number, worker_name).await;
+        } else {
+            self.conflict_unresolved(wt, files).await;
+        }
+
+        while self.resolving.is_none() && !self.merge_queue.is_empty() {
+            let (task_number, worker_name) = self.merge_queue.remove(0);
+            self.complete_worker_task(task_number, worker_name).await;
+        }
+    }
+
+    async fn conflict_unresolved(&mut self, wt: WorkerWorktree, files: Vec<String>) {
+        let task_number = wt.task_number;
+        let task_id = self.task_number_to_id(task_number);
+        let drone_worktree = self.config.working_dir.clone();
+
+        worktrees::abort_merge(&drone_worktree).await;
+        worktrees::remove(&drone_worktree, &wt).await;
+        let error = format!(
+            "Merging the task's branch conflicted in {} and the resolver could not fix it",
+            files.join(", ")
+        );
+        self.record_failure(task_number, &error, Vec::new());
+        let resolution = if self.retry_or_fail(task_number) {
+            "retrying"
+        } else {
+            "failed"
+        };
+        self.emitter
+            .emit_merge_conflict(&task_id, &wt.branch, &files, resolution);
+    }
+}
@@ -1,3 +1,10 @@
+mod approvals;
+mod budget;
+mod control;
+mod merge;
+mod proposals;
+mod setup;
+mod triage;
 mod workers;
 
 use std::collections::{HashMap, HashSet};
@@ -5,22 +12,34 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use anyhow::Result;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 use crate::agent_teams::preseed_tasks;
 use crate::backend::SpawnConfig;
-use crate::types::{DroneState, StructuredTask};
+use crate::types::{DroneState, SandboxMode, StructuredTask};
 use crate::webui::auth::credentials::Credentials;
 use crate::webui::chat::session::SessionStore;
+use crate::webui::tools::sandbox;
 
+use super::approvals::{ApprovalDecision, PendingApproval};
+use super::control::ControlCall;
 use super::events::{EventEmitter, WorkerInfo};
+use super::proposals::TaskProposals;
 use super::scheduler::TaskScheduler;
+use super::triage::TaskFailure;
 use super::worker::WorkerHandle;
+use super::worktrees::WorkerWorktree;
+use merge::ConflictResolution;
 
 /// Phases of the team coordinator's lifecycle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Phase {
+    Setup,
     Dispatch,
     Monitor,
+    /// Parked until a reviewer approves or rejects work
+    AwaitingApproval,
     Verify,
     Pr,
     Complete,
@@ -30,8 +49,10 @@ pub enum Phase {
 impl Phase {
     pub fn as_str(&self) -> &'static str {
         match self {
+            Phase::Setup => "setup",
             Phase::Dispatch => "dispatch",
             Phase::Monitor => "monitor",
+            Phase::AwaitingApproval => "awaiting_approval",
             Phase::Verify => "verify",
             Phase::Pr => "pr",
             Phase::Complete => "complete",
@@ -45,6 +66,8 @@ pub struct TeamCoordinator {
     pub(super) config: SpawnConfig,
     pub(super) scheduler: TaskScheduler,
     pub(super) workers: HashMap<usize, WorkerHandle>,
+    /// Isolated checkouts of running workers, merged back on completion.
+    pub(super) worktrees: HashMap<usize, WorkerWorktree>,
     /// All workers that have ever been part of the team (accumulated).
     pub(super) all_members: Vec<WorkerInfo>,
     pub(super) emitter: Arc<EventEmitter>,
@@ -52,28 +75,87 @@ pub struct TeamCoordinator {
     pub(super) creds: Credentials,
     pub(super) session_store: SessionStore,
     pub(super) phase: Phase,
+    /// Set once the drone-wide budget is reached
+    pub(super) over_budget: bool,
+    /// Tasks whose workers were stopped by a per-task budget
+    pub(super) budget_stopped: HashSet<usize>,
+    /// Follow-up tasks proposed by workers; `None` when proposals are disabled
+    pub(super) proposals: Option<Arc<TaskProposals>>,
+    /// Requests received on the control socket
+    pub(super) control_rx: Option<mpsc::Receiver<ControlCall>>,
+    pub(super) control_server: Option<JoinHandle<()>>,
+    /// Set by a `pause` control request; no new tasks start while set
+    pub(super) dispatch_paused: bool,
+    /// Tasks stopped by an `abort_task` control request
+    pub(super) operator_stopped: HashSet<usize>,
+    /// Set by a `pause_drone` control request; shared with every worker
+    pub(super) pause_flag: Arc<AtomicBool>,
+    /// Work parked at an approval gate, mirrored to `approvals.json`
+    pub(super) approvals: Vec<PendingApproval>,
+    /// Reviewer decisions received but not yet applied
+    pub(super) approval_decisions: Vec<ApprovalDecision>,
+    /// Why each task's latest attempt failed, handed to triage
+    pub(super) failures: HashMap<usize, TaskFailure>,
+    /// Tasks out of retries, waiting for the triage agent
+    pub(super) awaiting_triage: Vec<usize>,
+    /// Tasks already triaged or created by a split; never triaged again
+    pub(super) triaged: HashSet<usize>,
+    /// Tasks triage skipped, with the reason, reported in the PR
+    pub(super) skipped: Vec<String>,
+    /// The merge conflict being resolved in the drone worktree, if any
+    resolving: Option<ConflictResolution>,
+    /// Finished tasks (and their worker names) waiting for the resolver to
+    /// free the drone worktree before they merge
+    merge_queue: Vec<(usize, String)>,
 }
 
 impl TeamCoordinator {
     pub fn new(
-        config: SpawnConfig,
+        mut config: SpawnConfig,
         tasks: Vec<StructuredTask>,
         creds: Credentials,
         emitter: Arc<EventEmitter>,
         abort_flag: Arc<AtomicBool>,
         session_store: SessionStore,
     ) -> Self {
+        let proposals = (config.max_proposed_tasks > 0).then(|| {
+            Arc::new(TaskProposals::new(
+                &config.structured_tasks,
+                config.max_proposed_tasks,
+            ))
+        });
         let scheduler = TaskScheduler::new(tasks, config.max_agents, &HashSet::new());
+        // Every sandbox needs it; look it up here, not on each agent run
+        if config.sandbox.mode() != SandboxMode::Off {
+            config.git_common_dir = sandbox::git_common_dir(&config.working_dir);
+        }
         Self {
             config,
             scheduler,
             workers: HashMap::new(),
+            worktrees: HashMap::new(),
             all_members: Vec::new(),
             emitter,
             abort_flag,
             creds,
             session_store,
             phase: Phase::Dispatch,
+            over_budget: false,
+            budget_stopped: HashSet::new(),
+            proposals,
+            control_rx: None,
+            control_server: None,
+            dispatch_paused: false,
+            operator_stopped: HashSet::new(),
+            pause_flag: Arc::new(AtomicBool::new(false)),
+            approvals: Vec::new(),
+            approval_decisions: Vec::new(),
+            failures: HashMap::new(),
+            awaiting_triage: Vec::new(),
+            triaged: HashSet::new(),
+            skipped: Vec::new(),
+            resolving: None,
+            merge_queue: Vec::new(),
         }
     }
 
@@ -84,6 +166,7 @@ impl TeamCoordinator {
 
         // Pre-seed tasks to filesystem so TUI sees them immediately.
         let drone_dir = std::path::PathBuf::from(".hive/drones").join(&self.config.drone_name);
+        self.start_control_socket(&drone_dir);
         let seeded = preseed_tasks(
             &self.config.team_name,
             &self.config.structured_tasks,
@@ -119,40 +202,88 @@ impl TeamCoordinator {
 
         // Write initial team config (empty, updated as workers spawn)
         let _ = self.emitter.write_team_config(&[]);
+        self.init_approvals().await;
+
+        // === SETUP: fail fast if the baseline is already broken ===
+        if let Err(error) = self.run_setup_tasks().await {
+            eprintln!("[hive] {error}");
+            self.emitter.set_drone_error(&error);
+            self.transition_phase(Phase::Failed);
+            self.finish(false);
+            return Ok(());
+        }
 
         // === DISPATCH + MONITOR loop ===
         self.transition_phase(Phase::Monitor);
         if let Err(e) = self.run_monitor_loop().await {
             eprintln!("[hive] Monitor loop error: {e:#}");
             self.transition_phase(Phase::Failed);
+        } else if self.is_pausing() && !self.scheduler.all_completed() {
+            self.pause();
+            return Ok(());
         } else if self.scheduler.has_failures() {
             eprintln!("[hive] Some tasks failed permanently, skipping verify/PR");
             self.transition_phase(Phase::Failed);
         }
 
+        // Work that finished between budget checks may still have crossed it
+        self.enforce_budget();
         if self.is_aborted() {
             self.finish(false);
             return Ok(());
         }
 
         // === VERIFY + PR phases ===
-        if self.phase != Phase::Failed {
+        while self.phase != Phase::Failed {
             self.transition_phase(Phase::Verify);
             let passed = super::phases::run_verify_phase(
                 &self.config,
                 &self.creds,
                 self.session_store.clone(),
+                &self.emitter,
             )
             .await;
 
+            // === APPROVAL: a reviewer signs off before the PR is opened ===
+            let mut approval_comment = None;
+            if self.pr_needs_approval() {
+                let Some(decision) = self.await_pr_approval(passed).await else {
+                    self.finish(false);
+                    return Ok(());
+                };
+                if !decision.approved {
+                    if let Some(comment) = decision.comment {
+                        eprintln!("[hive] PR rejected, revising before asking again");
+                        super::phases::run_review_fix(
+                            &self.config,
+                            &self.creds,
+                            self.session_store.clone(),
+                            &self.emitter,
+                            &comment,
+                        )
+                        .await;
+                        continue;
+                    }
+                    eprintln!("[hive] PR rejected, not opening one");
+                    self.emitter.set_drone_error("PR rejected by reviewer");
+                    self.transition_phase(Phase::Failed);
+                    break;
+                }
+                approval_comment = decision.comment;
+            }
+
             self.transition_phase(Phase::Pr);
             super::phases::run_pr_phase(
                 &self.config,
                 &self.creds,
                 self.session_store.clone(),
+                &self.emitter,
                 passed,
+                &self.skipped,
+                approval_comment.as_deref(),
             )
             .await;
+            break;
         }
 
         // === COMPLETE ===
@@ -163,18 +294,30 @@ impl TeamCoordinator {
     /// The main dispatch + monitor loop (Ralph pattern).
     async fn run_monitor_loop(&mut self) -> Result<()> {
         while !self.scheduler.all_completed() {
+            self.enforce_budget();
+            self.accept_proposals();
+            self.handle_control_requests();
             if self.is_aborted() {
                 self.abort_all_workers();
                 return Ok(());
             }
+            self.apply_approval_decisions().await;
+            self.collect_conflict_resolution().await;
+            self.triage_failed_tasks().await;
+            if self.is_pausing() && self.workers.is_empty() && self.resolving.is_none() {
+                return Ok(());
+            }
 
             // Collect ready tasks (cloned to avoid borrow conflict)
-            let ready: Vec<StructuredTask> = self
-                .scheduler
-                .ready_tasks()
-                .iter()
-                .map(|t| (*t).clone())
-                .collect();
+            let ready: Vec<StructuredTask> = if self.dispatch_paused || self.is_pausing() {
+                Vec::new()
+            } else {
+                self.scheduler
+                    .ready_tasks()
+                    .iter()
+                    .map(|t| (*t).clone())
+                    .collect()
+            };
             for task in ready {
                 let task_number = task.number;
                 self.spawn_worker_for_task(task).await;
@@ -182,7 +325,11 @@ impl TeamCoordinator {
             }
 
             if self.workers.is_empty() {
-                if self.scheduler.has_failures() {
+                if self.scheduler.has_failures()
+                    && self.approvals.is_empty()
+                    && self.resolving.is_none()
+                    && !self.scheduler.has_backing_off()
+                {
                     eprintln!("[hive] All remaining tasks have unmet deps or failures");
                     break;
                 }
@@ -190,8 +337,9 @@ impl TeamCoordinator {
                 continue;
             }
 
-            let (result, worker_name) = self.wait_any_worker().await;
-            self.handle_worker_result(result, worker_name).await;
+            if let Some((result, worker_name)) = self.wait_any_worker().await {
+                self.handle_worker_result(result, worker_name).await;
+            }
         }
 
         Ok(())
@@ -208,18 +356,51 @@ impl TeamCoordinator {
         for handle in self.workers.values() {
             handle.abort_flag.store(true, Ordering::Relaxed);
         }
+        if let Some(resolution) = &self.resolving {
+            resolution.abort();
+        }
     }
 
     fn is_aborted(&self) -> bool {
         self.abort_flag.load(Ordering::Relaxed)
     }
 
+    fn is_pausing(&self) -> bool {
+        self.pause_flag.load(Ordering::Relaxed)
+    }
+
+    /// End the run with every worker's conversation saved, leaving task
+    /// files as they are so `hive resume` picks up where this run stopped.
+    fn pause(&mut self) {
+        self.park_pending_approvals();
+        eprintln!(
+            "[hive] Drone paused; run `hive resume {}` to continue",
+            self.config.drone_name
+        );
+        self.close_control_socket();
+        self.emitter.set_drone_state(DroneState::Paused);
+        self.emitter.emit_stop();
+    }
+
+    fn close_control_socket(&self) {
+        if let Some(server) = &self.control_server {
+            server.abort();
+            let drone_dir = std::path::PathBuf::from(".hive/drones").join(&self.config.drone_name);
+            let _ = std::fs::remove_file(super::control::socket_path(&drone_dir));
+        }
+    }
+
     fn finish(&self, success: bool) {
         let state = if success {
             DroneState::Completed
+        } else if self.over_budget || !self.budget_stopped.is_empty() {
+            DroneState::BudgetExceeded
+        } else if self.is_aborted() {
+            DroneState::Stopped
         } else {
             DroneState::Error
         };
+        self.close_control_socket();
         self.emitter.set_drone_state(state);
         self.emitter.emit_stop();
         let _ = crate::agent_teams::auto_complete_tasks(&self.config.team_name);
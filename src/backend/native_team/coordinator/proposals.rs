@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use crate::agent_teams::preseed_added_task;
+
+use super::TeamCoordinator;
+
+impl TeamCoordinator {
+    /// Add tasks proposed by workers to the plan, the scheduler and the
+    /// task files, so they are dispatched like any other task.
+    pub(super) fn accept_proposals(&mut self) {
+        let Some(proposals) = &self.proposals else {
+            return;
+        };
+        let drone_dir = PathBuf::from(".hive/drones").join(&self.config.drone_name);
+        for task in proposals.drain() {
+            eprintln!(
+                "[hive] Worker proposed task {}: {}",
+                task.number, task.title
+            );
+            let number = task.number;
+            self.config.structured_tasks.push(task.clone());
+            self.scheduler.add_task(task);
+            if let Err(e) = preseed_added_task(
+                &self.config.team_name,
+                &self.config.structured_tasks,
+                number,
+                &drone_dir,
+            ) {
+                eprintln!("[hive] Could not seed proposed task {number}: {e:#}");
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::types::{StructuredTask, TaskType};
+
+use super::super::quality_gate::{self, QualityGateConfig};
+use super::{Phase, TeamCoordinator};
+
+/// Longest a single setup command may run; dependency installs can be slow.
+const SETUP_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+impl TeamCoordinator {
+    /// Run the `run:` commands of each setup task in plan order, in the drone
+    /// worktree, before any worker is dispatched. Stops at the first failing
+    /// command and returns a message naming it, with its output.
+    pub(super) async fn run_setup_tasks(&mut self) -> Result<(), String> {
+        let setup: Vec<StructuredTask> = self
+            .config
+            .structured_tasks
+            .iter()
+            .filter(|t| t.task_type == TaskType::Setup && !t.run.is_empty())
+            .cloned()
+            .collect();
+        if setup.is_empty() {
+            return Ok(());
+        }
+
+        self.transition_phase(Phase::Setup);
+        for task in &setup {
+            let task_id = task.number.to_string();
+            for command in &task.run {
+                eprintln!("[hive] Setup task {}: {command}", task.number);
+                let run = quality_gate::run_gate(&QualityGateConfig {
+                    name: format!("setup-{}", task.number),
+                    command: command.clone(),
+                    timeout: SETUP_COMMAND_TIMEOUT,
+                    cwd: self.config.working_dir.clone(),
+                    required: true,
+                    // Setup commands come from the plan and usually install
+                    // dependencies, so they run outside the sandbox
+                    sandbox: None,
+                })
+                .await;
+                self.emitter.emit_setup_command(&task_id, &run);
+                if !run.passed() {
+                    return Err(format!(
+                        "Setup task {} ({}) failed: `{command}`\n{}",
+                        task.number,
+                        task.title,
+                        run.failure_output().trim_end()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+
+use crate::agent_teams::preseed_added_task;
+use crate::types::{StructuredTask, TaskType};
+use crate::webui::anthropic::types::Message;
+
+use super::super::phases;
+use super::super::triage::{SplitTask, TaskFailure, TriageDecision};
+use super::TeamCoordinator;
+
+impl TeamCoordinator {
+    /// Remember why a task's latest attempt failed, for triage.
+    pub(super) fn record_failure(
+        &mut self,
+        task_number: usize,
+        error: &str,
+        transcript: Vec<Message>,
+    ) {
+        self.failures.insert(
+            task_number,
+            TaskFailure {
+                error: error.to_string(),
+                transcript,
+            },
+        );
+    }
+
+    /// Queue a task that used up its retries for triage. Each task, and
+    /// each task a split creates, is triaged at most once.
+    pub(super) fn queue_triage(&mut self, task_number: usize) {
+        if self.config.triage && self.triaged.insert(task_number) {
+            self.awaiting_triage.push(task_number);
+        }
+    }
+
+    /// Ask the triage agent about every queued task and apply its decisions.
+    pub(super) async fn triage_failed_tasks(&mut self) {
+        for task_number in std::mem::take(&mut self.awaiting_triage) {
+            let Some(task) = self.scheduler.get_task(task_number).cloned() else {
+                continue;
+            };
+            let task_id = self.task_number_to_id(task_number);
+            eprintln!("[hive] Triaging task {task_id} after its last retry failed");
+            self.emitter
+                .update_task_file(&task_id, "in_progress", None, Some("Triaging failure"));
+
+            let failure = self
+                .failures
+                .remove(&task_number)
+                .unwrap_or_else(|| TaskFailure {
+                    error: "No error was recorded".to_string(),
+                    transcript: Vec::new(),
+                });
+            let dependents: Vec<&StructuredTask> = self
+                .scheduler
+                .tasks()
+                .map(|(t, _)| t)
+                .filter(|t| t.depends_on.contains(&task_number))
+                .collect();
+            let decision = phases::run_triage(
+                &self.config,
+                &self.creds,
+                self.session_store.clone(),
+                &self.emitter,
+                &task,
+                &failure,
+                &dependents,
+            )
+            .await
+            .unwrap_or_else(|| TriageDecision::Fail {
+                reason: "The triage agent gave no usable decision".to_string(),
+            });
+            self.apply_triage(&task, decision);
+        }
+    }
+
+    fn apply_triage(&mut self, task: &StructuredTask, decision: TriageDecision) {
+        let task_number = task.number;
+        let task_id = self.task_number_to_id(task_number);
+        let mut new_tasks = Vec::new();
+
+        match &decision {
+            TriageDecision::Split { tasks, .. } => {
+                new_tasks = self.split_task(task, tasks);
+                self.scheduler.mark_skipped(task_number);
+                eprintln!(
+                    "[hive] Triage split task {task_id} into tasks {}",
+                    new_tasks.join(", ")
+                );
+                self.emitter.update_task_file(
+                    &task_id,
+                    "completed",
+                    None,
+                    Some(&format!("Split into tasks {}", new_tasks.join(", "))),
+                );
+            }
+            TriageDecision::Rewrite { description, .. } => {
+                self.scheduler.rewrite(task_number, description.clone());
+                if let Some(t) = self
+                    .config
+                    .structured_tasks
+                    .iter_mut()
+                    .find(|t| t.number == task_number)
+                {
+                    t.body = description.clone();
+                }
+                eprintln!("[hive] Triage rewrote task {task_id}, retrying it");
+                self.emitter.update_task_file(
+                    &task_id,
+                    "pending",
+                    None,
+                    Some("Rewritten by triage"),
+                );
+            }
+            TriageDecision::Skip { reason } => {
+                self.scheduler.mark_skipped(task_number);
+                self.skipped
+                    .push(format!("{}. {}: {reason}", task_number, task.title));
+                eprintln!("[hive] Triage skipped task {task_id}: {reason}");
+                self.emitter.update_task_file(
+                    &task_id,
+                    "completed",
+                    None,
+                    Some(&format!("Skipped: {reason}")),
+                );
+            }
+            TriageDecision::Fail { reason } => {
+                eprintln!("[hive] Triage left task {task_id} failed: {reason}");
+                self.emitter
+                    .update_task_file(&task_id, "completed", None, None);
+            }
+        }
+
+        self.emitter.emit_triage_decision(
+            &task_id,
+            decision.action(),
+            decision.reason(),
+            &new_tasks,
+        );
+    }
+
+    /// Add the split tasks as a chain after the failed task's dependencies,
+    /// and make the failed task's dependents wait for the last one. Returns
+    /// the new tasks' ids.
+    fn split_task(&mut self, task: &StructuredTask, parts: &[SplitTask]) -> Vec<String> {
+        let drone_dir = PathBuf::from(".hive/drones").join(&self.config.drone_name);
+        let mut previous: Option<usize> = None;
+        let mut numbers = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            let number = self.next_task_number();
+            let last = i + 1 == parts.len();
+            let new_task = StructuredTask {
+                number,
+                title: part.title.trim().to_string(),
+                body: format!(
+                    "{}\n\nSplit from task {}: {}.",
+                    part.description.trim(),
+                    task.number,
+                    task.title
+                ),
+                task_type: TaskType::Work,
+                model: task.model.clone(),
+                parallel: task.parallel,
+                files: task.files.clone(),
+                depends_on: match previous {
+                    Some(prev) => vec![prev],
+                    None => task.depends_on.clone(),
+                },
+                priority: task.priority,
+                resource: task.resource.clone(),
+                run: Vec::new(),
+                // The original task's checks and sign-off apply once all parts are done
+                verify: if last {
+                    task.verify.clone()
+                } else {
+                    Vec::new()
+                },
+                approval: last && task.approval,
+                retry: task.retry.clone(),
+                limits: task.limits,
+            };
+            self.triaged.insert(number);
+            self.config.structured_tasks.push(new_task.clone());
+            self.scheduler.add_task(new_task);
+            if let Err(e) = preseed_added_task(
+                &self.config.team_name,
+                &self.config.structured_tasks,
+                number,
+                &drone_dir,
+            ) {
+                eprintln!("[hive] Could not seed split task {number}: {e:#}");
+            }
+            previous = Some(number);
+            numbers.push(self.task_number_to_id(number));
+        }
+
+        if let Some(last) = previous {
+            self.scheduler.redirect_dependents(task.number, last);
+            for t in &mut self.config.structured_tasks {
+                for dep in &mut t.depends_on {
+                    if *dep == task.number {
+                        *dep = last;
+                    }
+                }
+            }
+        }
+        numbers
+    }
+
+    /// Accepted proposals are already in the plan's tasks, so the fallback
+    /// can't reuse a proposed task's number.
+    fn next_task_number(&self) -> usize {
+        self.proposals
+            .as_ref()
+            .and_then(|proposals| proposals.reserve_number())
+            .unwrap_or_else(|| {
+                self.config
+                    .structured_tasks
+                    .iter()
+                    .map(|t| t.number)
+                    .max()
+                    .unwrap_or(0)
+                    + 1
+            })
+    }
+}
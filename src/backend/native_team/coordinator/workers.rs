@@ -1,21 +1,33 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::types::StructuredTask;
+use crate::types::{ErrorClass, RetryPolicy, StructuredTask};
 
 use super::super::events::WorkerInfo;
-use super::super::worker::{spawn_worker, WorkerConfig, WorkerResult};
+use super::super::paused_workers;
+use super::super::worker::{spawn_worker, WorkerConfig, WorkerError, WorkerResult};
 use super::super::worker_notes;
+use super::super::worktrees;
+use super::merge::Integration;
 use super::TeamCoordinator;
 
+/// First delay before retrying a task after a transient error, when the
+/// retry policy sets no backoff.
+const TRANSIENT_BACKOFF_SECS: u64 = 15;
+
 impl TeamCoordinator {
     /// Spawn a worker for a specific task.
     pub(super) async fn spawn_worker_for_task(&mut self, task: StructuredTask) {
         let task_number = task.number;
         let task_id = self.task_number_to_id(task_number);
         let worker_name = task.worker_name();
-        let model = task
-            .model
-            .clone()
+        // Retries may move up to a stronger model
+        let escalated = match self.retry_policy(task_number).escalate_model {
+            Some(model) if self.scheduler.retry_count(task_number) > 0 => Some(model),
+            _ => None,
+        };
+        let model = escalated
+            .or_else(|| task.model.clone())
             .unwrap_or_else(|| self.config.model.clone());
 
         self.emitter.update_task_file(
@@ -33,11 +45,59 @@ impl TeamCoordinator {
         let drone_dir = PathBuf::from(".hive/drones").join(&self.config.drone_name);
         let dep_notes = worker_notes::read_dependency_notes(&drone_dir, &task.depends_on);
 
+        // A worker paused by `hive pause` continues in the checkout it left
+        let saved = paused_workers::take(&drone_dir, task_number);
+        let reopened = match saved {
+            Some(_) => worktrees::reopen(&drone_dir, &self.config.drone_name, task_number).await,
+            None => None,
+        };
+        let resume = match (saved, &reopened) {
+            (Some(saved), Some(_)) => Some(saved),
+            (Some(_), None) => {
+                eprintln!(
+                    "[hive] Worktree of paused task {task_number} is gone, restarting it from scratch"
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
+        // Give the worker its own checkout so parallel workers don't collide
+        let created = match reopened {
+            Some(wt) => {
+                eprintln!("[hive] Resuming paused worker for task {task_number}");
+                Ok(wt)
+            }
+            None => {
+                worktrees::create(
+                    &self.config.working_dir,
+                    &drone_dir,
+                    &self.config.drone_name,
+                    task_number,
+                )
+                .await
+            }
+        };
+        let cwd = match created {
+            Ok(wt) => {
+                let path = wt.path.clone();
+                self.worktrees.insert(task_number, wt);
+                path
+            }
+            Err(e) => {
+                eprintln!(
+                    "[hive] Could not create worktree for task {task_number}, using shared checkout: {e:#}"
+                );
+                self.config.working_dir.clone()
+            }
+        };
+
+        let limits = task.limits;
         let handle = spawn_worker(WorkerConfig {
             task,
             creds: self.creds.clone(),
             model,
-            cwd: self.config.working_dir.clone(),
+            cwd,
             team_name: self.config.team_name.clone(),
             drone_name: self.config.drone_name.clone(),
             prd_path: self.config.prd_path.clone(),
@@ -46,14 +106,39 @@ impl TeamCoordinator {
             session_store: self.session_store.clone(),
             global_abort: self.abort_flag.clone(),
             dependency_notes: dep_notes,
+            quality_gates: self.config.quality_gates.clone(),
+            task_proposals: self.proposals.clone(),
+            pause_flag: self.pause_flag.clone(),
+            resume,
+            limits: limits.or(&self.config.task_limits),
+            sandbox: self.config.sandbox.clone(),
+            git_common_dir: self.config.git_common_dir.clone(),
+            persistent_shell: self.config.persistent_shell,
         });
 
         self.workers.insert(task_number, handle);
     }
 
-    /// Wait for any running worker to complete. Returns (WorkerResult, worker_name).
-    pub(super) async fn wait_any_worker(&mut self) -> (WorkerResult, String) {
+    /// Wait for any running worker to complete. Returns (WorkerResult, worker_name),
+    /// or `None` once a reviewer decision is waiting to be applied.
+    pub(super) async fn wait_any_worker(&mut self) -> Option<(WorkerResult, String)> {
         loop {
+            self.enforce_budget();
+            self.accept_proposals();
+            self.handle_control_requests();
+            if self.approval_decisions.iter().any(|d| d.task.is_some()) {
+                return None;
+            }
+            if self.resolving.as_ref().is_some_and(|r| r.is_finished()) {
+                return None;
+            }
+            // A retry's backoff ran out: let the monitor loop dispatch it
+            if !self.dispatch_paused
+                && !self.is_pausing()
+                && !self.scheduler.ready_tasks().is_empty()
+            {
+                return None;
+            }
             let finished = self
                 .workers
                 .iter()
@@ -68,15 +153,19 @@ impl TeamCoordinator {
                     Ok(Err(e)) => WorkerResult {
                         task_number: key,
                         success: false,
-                        error: Some(format!("{e:#}")),
+                        error: Some(WorkerError::Internal(format!("{e:#}"))),
+                        paused: false,
+                        transcript: Vec::new(),
                     },
                     Err(e) => WorkerResult {
                         task_number: key,
                         success: false,
-                        error: Some(format!("Worker panicked: {e}")),
+                        error: Some(WorkerError::Internal(format!("Worker panicked: {e}"))),
+                        paused: false,
+                        transcript: Vec::new(),
                     },
                 };
-                return (result, worker_name);
+                return Some((result, worker_name));
             }
 
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -85,53 +174,196 @@ impl TeamCoordinator {
 
     /// Handle a worker result: update scheduler, emit events, fast re-dispatch.
     pub(super) async fn handle_worker_result(&mut self, result: WorkerResult, worker_name: String) {
+        // Pick up anything the worker proposed just before it finished
+        self.accept_proposals();
         let task_id = self.task_number_to_id(result.task_number);
 
-        if result.success {
-            self.scheduler.mark_completed(result.task_number);
-            self.emitter
-                .update_task_file(&task_id, "completed", None, None);
-            let subject = self
-                .scheduler
-                .get_task(result.task_number)
-                .map(|t| t.title.clone())
-                .unwrap_or_default();
+        if result.paused {
+            // Keep the worktree on disk: the resumed worker continues in it
+            self.worktrees.remove(&result.task_number);
+            eprintln!("[hive] Worker {worker_name} paused");
             self.emitter
-                .emit_worker_done(&task_id, &subject, &worker_name);
+                .update_task_file(&task_id, "pending", None, Some("Paused"));
+            return;
+        }
 
-            // Fast re-dispatch: immediately check for next ready task
-            let ready: Vec<StructuredTask> = self
-                .scheduler
-                .ready_tasks()
-                .iter()
-                .map(|t| (*t).clone())
-                .collect();
-            if let Some(task) = ready.into_iter().next() {
-                let num = task.number;
-                self.spawn_worker_for_task(task).await;
-                self.scheduler.mark_running(num);
+        if result.success {
+            if self.task_needs_approval(result.task_number) {
+                self.request_task_approval(result.task_number).await;
+            } else {
+                self.complete_worker_task(result.task_number, worker_name)
+                    .await;
             }
         } else {
-            let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-            eprintln!("[hive] Worker {worker_name} failed: {error_msg}");
-            self.emitter.emit_worker_error(&task_id, &error_msg);
+            let error = result
+                .error
+                .unwrap_or_else(|| WorkerError::Internal("Unknown error".to_string()));
+            let class = error.class();
+            let error_msg = error.to_string();
+            eprintln!(
+                "[hive] Worker {worker_name} failed ({}): {error_msg}",
+                class.as_str()
+            );
+            self.emitter
+                .emit_worker_error(&task_id, &error_msg, Some(class));
+            self.record_failure(result.task_number, &error_msg, result.transcript);
+            if let Some(wt) = self.worktrees.remove(&result.task_number) {
+                worktrees::remove(&self.config.working_dir, &wt).await;
+            }
             self.scheduler.mark_failed(result.task_number);
-
-            if self.scheduler.requeue(result.task_number) {
-                eprintln!(
-                    "[hive] Retrying task {} (worker-{})",
-                    task_id, result.task_number
+            if self.budget_stopped.contains(&result.task_number) {
+                self.emitter.update_task_file(
+                    &task_id,
+                    "completed",
+                    None,
+                    Some("Stopped: task budget reached"),
                 );
-                self.emitter
-                    .update_task_file(&task_id, "pending", None, None);
-            } else {
-                eprintln!(
-                    "[hive] Task {} (worker-{}) exceeded max retries, marking as failed",
-                    task_id, result.task_number
+            } else if self.operator_stopped.contains(&result.task_number) {
+                self.emitter.update_task_file(
+                    &task_id,
+                    "completed",
+                    None,
+                    Some("Stopped by operator"),
                 );
-                self.emitter
-                    .update_task_file(&task_id, "completed", None, None);
+            } else if class == ErrorClass::Transient {
+                self.retry_transient(result.task_number);
+            } else if self.retry_policy(result.task_number).is_permanent(class) {
+                self.fail_permanently(result.task_number, class);
+            } else {
+                self.retry_or_fail(result.task_number);
+            }
+        }
+    }
+
+    /// The task's retry policy, completed from the drone-wide one.
+    pub(super) fn retry_policy(&self, task_number: usize) -> RetryPolicy {
+        let task_policy = self
+            .scheduler
+            .get_task(task_number)
+            .map(|t| t.retry.clone())
+            .unwrap_or_default();
+        task_policy.or(&self.config.retry)
+    }
+
+    /// Re-queue a task after a rate limit, outage or network error without
+    /// using one of its attempts. Falls back to `retry_or_fail` if the
+    /// errors keep coming.
+    fn retry_transient(&mut self, task_number: usize) {
+        let task_id = self.task_number_to_id(task_number);
+        let base = Duration::from_secs(
+            self.retry_policy(task_number)
+                .backoff_secs
+                .unwrap_or(TRANSIENT_BACKOFF_SECS),
+        );
+        if self.scheduler.requeue_transient(task_number, base) {
+            eprintln!(
+                "[hive] Task {task_id} hit a transient error, retrying it without using an attempt"
+            );
+            self.emitter.update_task_file(
+                &task_id,
+                "pending",
+                None,
+                Some("Waiting to retry after a transient error"),
+            );
+        } else {
+            self.retry_or_fail(task_number);
+        }
+    }
+
+    /// Fail a task whose error class the retry policy treats as permanent.
+    /// Credential and abort failures are not sent to triage: changing the
+    /// task would not fix them.
+    fn fail_permanently(&mut self, task_number: usize, class: ErrorClass) {
+        let task_id = self.task_number_to_id(task_number);
+        eprintln!(
+            "[hive] Task {task_id} failed with a permanent {} error, not retrying",
+            class.as_str()
+        );
+        self.scheduler.mark_failed(task_number);
+        self.emitter
+            .update_task_file(&task_id, "completed", None, None);
+        if !matches!(class, ErrorClass::Credentials | ErrorClass::Aborted) {
+            self.queue_triage(task_number);
+        }
+    }
+
+    /// Merge a finished task's changes, mark it completed and start the next
+    /// ready task. Waits its turn while another task's conflict is resolved.
+    pub(super) async fn complete_worker_task(&mut self, task_number: usize, worker_name: String) {
+        if self.resolving.is_some() {
+            self.merge_queue.push((task_number, worker_name));
+            return;
+        }
+        if self
+            .integrate_worker_changes(task_number, worker_name.clone())
+            .await
+            == Integration::Merged
+        {
+            self.finish_worker_task(task_number, worker_name).await;
+        }
+    }
+
+    /// Mark a merged task completed and start the next ready task.
+    pub(super) async fn finish_worker_task(&mut self, task_number: usize, worker_name: String) {
+        let task_id = self.task_number_to_id(task_number);
+        self.scheduler.mark_completed(task_number);
+        self.emitter
+            .update_task_file(&task_id, "completed", None, None);
+        let subject = self
+            .scheduler
+            .get_task(task_number)
+            .map(|t| t.title.clone())
+            .unwrap_or_default();
+        self.emitter
+            .emit_worker_done(&task_id, &subject, &worker_name);
+
+        if self.dispatch_paused || self.is_pausing() {
+            return;
+        }
+
+        // Fast re-dispatch: immediately check for next ready task
+        let ready: Vec<StructuredTask> = self
+            .scheduler
+            .ready_tasks()
+            .iter()
+            .map(|t| (*t).clone())
+            .collect();
+        if let Some(task) = ready.into_iter().next() {
+            let num = task.number;
+            self.spawn_worker_for_task(task).await;
+            self.scheduler.mark_running(num);
+        }
+    }
+
+    /// Re-queue a failed task, or leave it failed once retries are exhausted
+    /// (queuing it for triage when enabled). Returns `true` if the task was
+    /// re-queued.
+    pub(super) fn retry_or_fail(&mut self, task_number: usize) -> bool {
+        let task_id = self.task_number_to_id(task_number);
+        let policy = self.retry_policy(task_number);
+        if self.scheduler.requeue(task_number, &policy) {
+            let retry = self.scheduler.retry_count(task_number);
+            let delay = policy.backoff(retry);
+            let mut detail = format!("attempt {} of {}", retry + 1, policy.max_attempts());
+            if !delay.is_zero() {
+                detail.push_str(&format!(", in {}s", delay.as_secs()));
+            }
+            if let Some(model) = &policy.escalate_model {
+                detail.push_str(&format!(", on {model}"));
             }
+            eprintln!("[hive] Retrying task {task_id} (worker-{task_number}): {detail}");
+            self.emitter
+                .update_task_file(&task_id, "pending", None, None);
+            true
+        } else {
+            eprintln!(
+                "[hive] Task {task_id} (worker-{task_number}) exceeded max retries, marking as failed"
+            );
+            self.scheduler.mark_failed(task_number);
+            self.emitter
+                .update_task_file(&task_id, "completed", None, None);
+            self.queue_triage(task_number);
+            false
         }
     }
 
@@ -5,9 +5,11 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 use serde_json::json;
 
+use super::budget::{BudgetBreach, SpendLedger};
+use super::quality_gate::GateRun;
 use crate::agent_teams::{team_dir, team_tasks_dir, AgentTeamTask};
 use crate::events::HiveEvent;
-use crate::types::{DroneState, DroneStatus};
+use crate::types::{DroneState, DroneStatus, ErrorClass};
 
 /// Writes filesystem artifacts for TUI compatibility.
 ///
@@ -17,6 +19,8 @@ pub struct EventEmitter {
     events_path: PathBuf,
     status_path: PathBuf,
     team_name: String,
+    /// Spend recorded through `emit_cost`, for budget checks
+    ledger: SpendLedger,
 }
 
 impl EventEmitter {
@@ -25,9 +29,14 @@ impl EventEmitter {
             events_path: drone_dir.join("events.ndjson"),
             status_path: status_path.to_path_buf(),
             team_name: team_name.to_string(),
+            ledger: SpendLedger::load(&drone_dir.join("cost.ndjson")),
         }
     }
 
+    pub fn ledger(&self) -> &SpendLedger {
+        &self.ledger
+    }
+
     /// Append a HiveEvent to events.ndjson.
     pub fn emit(&self, event: &HiveEvent) {
         let Ok(line) = serde_json::to_string(event) else {
@@ -191,20 +200,122 @@ impl EventEmitter {
         self.update_status(&status);
     }
 
-    pub fn emit_quality_gate(&self, task_id: &str, passed: bool, output: &str) {
+    /// Record why the drone failed in its status file.
+    pub fn set_drone_error(&self, message: &str) {
+        let Ok(contents) = fs::read_to_string(&self.status_path) else {
+            return;
+        };
+        let Ok(mut status) = serde_json::from_str::<DroneStatus>(&contents) else {
+            return;
+        };
+        status.error_count += 1;
+        status.last_error = Some(message.to_string());
+        status.updated = now();
+        self.update_status(&status);
+    }
+
+    pub fn emit_setup_command(&self, task_id: &str, run: &GateRun) {
+        self.emit(&HiveEvent::SetupCommand {
+            ts: now(),
+            task_id: task_id.to_string(),
+            command: run.command.clone(),
+            passed: run.passed(),
+            output: run.failure_output(),
+        });
+    }
+
+    pub fn emit_quality_gate(&self, task_id: &str, run: &GateRun) {
         self.emit(&HiveEvent::QualityGateResult {
             ts: now(),
             task_id: task_id.to_string(),
-            passed,
-            output: output.to_string(),
+            gate: Some(run.name.clone()),
+            command: Some(run.command.clone()),
+            passed: run.passed(),
+            advisory: !run.required,
+            output: run.failure_output(),
         });
     }
 
-    pub fn emit_worker_error(&self, task_id: &str, error: &str) {
+    pub fn emit_worker_error(&self, task_id: &str, error: &str, class: Option<ErrorClass>) {
         self.emit(&HiveEvent::WorkerError {
             ts: now(),
             task_id: task_id.to_string(),
             error_message: error.to_string(),
+            error_class: class.map(|c| c.as_str().to_string()),
+        });
+    }
+
+    pub fn emit_ownership_violation(&self, task_id: &str, worker: &str, tool: &str, path: &str) {
+        self.emit(&HiveEvent::FileOwnershipViolation {
+            ts: now(),
+            task_id: task_id.to_string(),
+            agent: worker.to_string(),
+            tool: tool.to_string(),
+            path: path.to_string(),
+        });
+    }
+
+    pub fn emit_merge_conflict(
+        &self,
+        task_id: &str,
+        branch: &str,
+        files: &[String],
+        resolution: &str,
+    ) {
+        self.emit(&HiveEvent::MergeConflict {
+            ts: now(),
+            task_id: task_id.to_string(),
+            branch: branch.to_string(),
+            files: files.to_vec(),
+            resolution: resolution.to_string(),
+        });
+    }
+
+    pub fn emit_budget_exceeded(&self, breach: &BudgetBreach) {
+        self.emit(&HiveEvent::BudgetExceeded {
+            ts: now(),
+            task_id: breach.task_id.clone(),
+            limit: breach.limit.clone(),
+            spent_usd: breach.spent.usd,
+            spent_tokens: breach.spent.tokens,
+        });
+    }
+
+    pub fn emit_approval_requested(&self, task_id: Option<&str>, title: &str) {
+        self.emit(&HiveEvent::ApprovalRequested {
+            ts: now(),
+            task_id: task_id.map(str::to_string),
+            title: title.to_string(),
+        });
+    }
+
+    pub fn emit_approval_decided(
+        &self,
+        task_id: Option<&str>,
+        approved: bool,
+        comment: Option<&str>,
+    ) {
+        self.emit(&HiveEvent::ApprovalDecided {
+            ts: now(),
+            task_id: task_id.map(str::to_string),
+            approved,
+            comment: comment.map(str::to_string),
+        });
+    }
+
+    pub fn emit_triage_decision(
+        &self,
+        task_id: &str,
+        action: &str,
+        reason: &str,
+        new_tasks: &[String],
+    ) {
+        self.emit(&HiveEvent::TriageDecision {
+            ts: now(),
+            task_id: task_id.to_string(),
+            action: action.to_string(),
+            reason: reason.to_string(),
+            new_tasks: new_tasks.to_vec(),
         });
     }
 
@@ -225,19 +336,39 @@ pub struct WorkerInfo {
 
 impl EventEmitter {
     /// Append a cost record to cost.ndjson in the drone directory.
-    /// Each line: `{"input_tokens":N,"output_tokens":N,"cache_read":N,"cache_create":N}`
-    /// The polling code sums all lines to get the total.
-    pub fn emit_cost(&self, usage: &crate::webui::anthropic::types::UsageStats) {
+    /// Each line covers one API call:
+    /// `{"model":..,"worker":..,"task":..,"input_tokens":N,"output_tokens":N,"cache_read":N,"cache_create":N,"cost_usd":F}`
+    /// The polling code sums all lines to get the totals.
+    pub fn emit_cost(
+        &self,
+        model: &str,
+        worker: &str,
+        task: Option<&str>,
+        usage: &crate::webui::anthropic::types::UsageStats,
+    ) {
         let cost_path = self
             .events_path
             .parent()
             .unwrap_or(std::path::Path::new("."))
             .join("cost.ndjson");
+        let cost_usd = crate::webui::anthropic::pricing::cost_usd(model, usage);
+        self.ledger.record(
+            task,
+            cost_usd,
+            usage.input_tokens
+                + usage.output_tokens
+                + usage.cache_read_input_tokens
+                + usage.cache_creation_input_tokens,
+        );
         let Ok(line) = serde_json::to_string(&serde_json::json!({
+            "model": model,
+            "worker": worker,
+            "task": task,
             "input_tokens": usage.input_tokens,
             "output_tokens": usage.output_tokens,
             "cache_read": usage.cache_read_input_tokens,
             "cache_create": usage.cache_creation_input_tokens,
+            "cost_usd": cost_usd,
         })) else {
             return;
         };
@@ -1,4 +1,9 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::webui::tools::WritePolicy;
+
+use super::events::EventEmitter;
 
 /// Guards file access per worker based on `task.files` metadata.
 ///
@@ -23,6 +28,12 @@ impl FileOwnershipGuard {
         if self.allowed_patterns.is_empty() {
             return Ok(());
         }
+        // `src/auth/x/../../lib.rs` would match `src/auth` but write `src/lib.rs`
+        if !is_safe_path(path) {
+            return Err(format!(
+                "Write to '{path}' is refused: paths may not contain '..'"
+            ));
+        }
 
         let normalized = normalize_path(path);
         for pattern in &self.allowed_patterns {
@@ -51,10 +62,94 @@ impl FileOwnershipGuard {
             text.push_str(&format!("- `{pattern}`\n"));
         }
         text.push_str("\nDo NOT modify files outside these paths. Read access is unrestricted.");
+        text.push_str(
+            " Writes outside these paths are rejected by the Write, Edit and Bash tools.",
+        );
         text
     }
 }
 
+/// Enforces a worker's `FileOwnershipGuard` inside the Write/Edit/Bash tools.
+///
+/// Paths are matched relative to the worker's checkout. Writes under the
+/// system temp dir are always allowed; anything else outside the checkout is
+/// rejected. Every rejection is recorded as a `FileOwnershipViolation` event.
+pub struct WorkerWritePolicy {
+    guard: FileOwnershipGuard,
+    roots: Vec<PathBuf>,
+    task_id: String,
+    worker_name: String,
+    emitter: Arc<EventEmitter>,
+}
+
+impl WorkerWritePolicy {
+    pub fn new(
+        file_patterns: &[String],
+        cwd: &Path,
+        task_id: &str,
+        worker_name: &str,
+        emitter: Arc<EventEmitter>,
+    ) -> Self {
+        let mut roots = vec![cwd.to_path_buf()];
+        if let Ok(canonical) = cwd.canonicalize() {
+            roots.push(canonical);
+        }
+        Self {
+            guard: FileOwnershipGuard::new(file_patterns),
+            roots,
+            task_id: task_id.to_string(),
+            worker_name: worker_name.to_string(),
+            emitter,
+        }
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        self.roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .map(|rel| rel.to_string_lossy().to_string())
+    }
+}
+
+impl WritePolicy for WorkerWritePolicy {
+    fn check_write(&self, tool: &str, path: &Path) -> Result<(), String> {
+        // A `..` left in the path (its parent doesn't exist yet) escapes
+        // the prefix checks below, the temp dir included
+        let result = if !is_safe_path(&path.to_string_lossy()) {
+            Err(format!(
+                "Write to '{}' is refused: paths may not contain '..'",
+                path.display()
+            ))
+        } else {
+            match self.relative_path(path) {
+                Some(rel) => self.guard.check_write(&rel),
+                None if is_temp_path(path) => Ok(()),
+                None => Err(format!(
+                    "Write to '{}' is outside the task's working directory",
+                    path.display()
+                )),
+            }
+        };
+
+        if result.is_err() {
+            let shown = self
+                .relative_path(path)
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+            self.emitter
+                .emit_ownership_violation(&self.task_id, &self.worker_name, tool, &shown);
+        }
+        result
+    }
+}
+
+fn is_temp_path(path: &Path) -> bool {
+    let tmp = std::env::temp_dir();
+    path.starts_with(&tmp)
+        || tmp
+            .canonicalize()
+            .is_ok_and(|canonical| path.starts_with(canonical))
+}
+
 /// Normalize a path by stripping leading `./` and trailing `/`.
 fn normalize_path(path: &str) -> String {
     let p = path.strip_prefix("./").unwrap_or(path);
@@ -141,6 +236,57 @@ mod tests {
         assert!(text.contains("restricted"));
     }
 
+    #[test]
+    fn test_worker_policy_relative_to_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let drone_dir = dir.path().join("drone");
+        std::fs::create_dir_all(&drone_dir).unwrap();
+        let emitter = Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "test-team",
+        ));
+        let cwd = dir.path().join("repo");
+        let policy = WorkerWritePolicy::new(&["src/auth".to_string()], &cwd, "1", "auth", emitter);
+
+        assert!(policy
+            .check_write("Write", &cwd.join("src/auth/mod.rs"))
+            .is_ok());
+        assert!(policy.check_write("Edit", &cwd.join("src/lib.rs")).is_err());
+        assert!(policy
+            .check_write("Write", Path::new("/etc/hosts"))
+            .is_err());
+
+        let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+        assert_eq!(events.lines().count(), 2);
+        assert!(events.contains("FileOwnershipViolation"));
+        assert!(events.contains("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_traversal_to_another_tasks_file_is_refused() {
+        let guard = FileOwnershipGuard::new(&["src/auth".to_string()]);
+        assert!(guard.check_write("src/auth/x/../../lib.rs").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let drone_dir = dir.path().join("drone");
+        std::fs::create_dir_all(&drone_dir).unwrap();
+        let emitter = Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "test-team",
+        ));
+        let cwd = dir.path().join("repo");
+        std::fs::create_dir_all(cwd.join("src/auth")).unwrap();
+        let policy = WorkerWritePolicy::new(&["src/auth".to_string()], &cwd, "1", "auth", emitter);
+        let input = serde_json::json!({"file_path": "src/auth/x/../../lib.rs"});
+        let err =
+            crate::webui::tools::write_policy::enforce(&policy, "Write", &input, &cwd).unwrap_err();
+        assert!(err.to_string().contains(".."), "{err}");
+        let tmp_escape = std::env::temp_dir().join("missing/../../etc/hosts");
+        assert!(policy.check_write("Write", &tmp_escape).is_err());
+    }
+
     #[test]
     fn test_safe_path_no_traversal() {
         assert!(is_safe_path("src/main.rs"));
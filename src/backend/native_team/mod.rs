@@ -1,12 +1,19 @@
+pub mod approvals;
+pub mod budget;
+pub mod control;
 pub mod coordinator;
 pub mod events;
 pub mod file_ownership;
+pub mod paused_workers;
 mod phases;
 pub mod prompts;
+pub mod proposals;
 pub mod quality_gate;
 pub mod scheduler;
+pub mod triage;
 pub mod worker;
 pub mod worker_notes;
+pub mod worktrees;
 
 use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
@@ -40,11 +47,11 @@ impl ExecutionBackend for NativeTeamBackend {
     }
 
     fn stop(&self, handle: &SpawnHandle) -> Result<()> {
-        // Write abort signal — coordinator polls this
-        let abort_path = std::path::PathBuf::from(".hive/drones")
-            .join(&handle.backend_type)
-            .join(".abort");
-        let _ = std::fs::write(&abort_path, "1");
+        // Ask the coordinator to stop its workers; signal it if it isn't listening
+        let drone_dir = std::path::PathBuf::from(".hive/drones").join(&handle.backend_type);
+        if control::send(&drone_dir, &control::ControlRequest::AbortDrone).is_err() {
+            terminate_coordinator(&drone_dir);
+        }
 
         // Also try to stop the old way (if any Claude CLI processes)
         let _ = crate::backend::agent_team::stop_by_worktree_match(&handle.backend_id);
@@ -67,6 +74,22 @@ impl ExecutionBackend for NativeTeamBackend {
     }
 }
 
+/// Send SIGTERM to the coordinator process recorded in the drone's `.pid`.
+fn terminate_coordinator(drone_dir: &std::path::Path) {
+    let Some(pid) = std::fs::read_to_string(drone_dir.join(".pid"))
+        .ok()
+        .and_then(|p| p.trim().parse::<i32>().ok())
+    else {
+        return;
+    };
+    if pid as u32 != std::process::id() && crate::commands::common::is_process_running(pid) {
+        let _ = nix::sys::signal::kill(
+            nix::unistd::Pid::from_raw(pid),
+            nix::sys::signal::Signal::SIGTERM,
+        );
+    }
+}
+
 /// Launch the native team coordinator.
 ///
 /// Spawns the coordinator on a non-daemon thread with its own tokio runtime.
@@ -130,6 +153,16 @@ fn launch_native_team(config: &SpawnConfig) -> Result<SpawnHandle> {
     // `hive stop` sends SIGTERM to this PID.
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async move {
+        // Background processes and persistent shells run in their own
+        // process groups, so SIGTERM alone would leave them behind
+        tokio::spawn(async {
+            use tokio::signal::unix::{signal, SignalKind};
+            if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+                terminate.recv().await;
+                crate::webui::tools::background::kill_all();
+                std::process::exit(143);
+            }
+        });
         if let Err(e) = coordinator.run().await {
             eprintln!("[hive] Native team coordinator error: {e:#}");
         }
@@ -0,0 +1,101 @@
+//! Conversations of workers stopped by `hive pause` (or tasks sent back by a
+//! reviewer), saved under `<drone_dir>/paused/` and picked up the next time
+//! the task is dispatched.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::webui::anthropic::types::Message;
+
+const PAUSED_DIR: &str = "paused";
+
+/// Where a paused worker left off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PausedWorker {
+    pub task_number: usize,
+    /// Worker loop iteration to continue from
+    pub iteration: usize,
+    /// History of the interrupted iteration, ending with tool results the
+    /// model has not seen yet. Empty if the worker paused between iterations.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Progress summary for the next iteration's prompt
+    #[serde(default)]
+    pub continuation: Option<String>,
+}
+
+fn path(drone_dir: &Path, task_number: usize) -> PathBuf {
+    drone_dir
+        .join(PAUSED_DIR)
+        .join(format!("task-{task_number}.json"))
+}
+
+pub fn save(drone_dir: &Path, worker: &PausedWorker) -> Result<()> {
+    let path = path(drone_dir, worker.task_number);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(worker)?)?;
+    Ok(())
+}
+
+/// Load and remove the saved state of a task's worker, if any.
+pub fn take(drone_dir: &Path, task_number: usize) -> Option<PausedWorker> {
+    let path = path(drone_dir, task_number);
+    let contents = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::remove_file(&path);
+    serde_json::from_str(&contents).ok()
+}
+
+/// Task numbers with a saved worker conversation.
+pub fn list(drone_dir: &Path) -> Vec<usize> {
+    let Ok(entries) = std::fs::read_dir(drone_dir.join(PAUSED_DIR)) else {
+        return Vec::new();
+    };
+    let mut numbers: Vec<usize> = entries
+        .flatten()
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()?
+                .strip_prefix("task-")?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    numbers.sort_unstable();
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webui::anthropic::types::MessageContent;
+
+    #[test]
+    fn test_paused_worker_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let worker = PausedWorker {
+            task_number: 3,
+            iteration: 2,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: MessageContent::Text("Complete this task".to_string()),
+            }],
+            continuation: Some("Edited src/lib.rs".to_string()),
+        };
+        save(tmp.path(), &worker).unwrap();
+        assert_eq!(list(tmp.path()), vec![3]);
+
+        let restored = take(tmp.path(), 3).unwrap();
+        assert_eq!(restored.iteration, 2);
+        assert_eq!(restored.messages.len(), 1);
+        assert_eq!(restored.continuation.as_deref(), Some("Edited src/lib.rs"));
+
+        // Taking consumes the saved state
+        assert!(take(tmp.path(), 3).is_none());
+        assert!(list(tmp.path()).is_empty());
+    }
+}
@@ -2,33 +2,108 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use crate::backend::SpawnConfig;
+use crate::commands::common::truncate_with_ellipsis;
+use crate::types::StructuredTask;
 use crate::webui::anthropic::types::{ContentBlock, Message, MessageContent};
 use crate::webui::auth::credentials::Credentials;
 use crate::webui::chat::handlers::agentic::{run_agentic_loop, AgenticLoopParams};
 use crate::webui::chat::session::{Effort, SessionStore};
 use crate::webui::provider;
 use crate::webui::tools::definitions::builtin_tool_definitions;
+use crate::webui::tools::permissions::{Permissions, TrustLevel};
+use crate::webui::tools::ProcessSandbox;
+
+use super::events::EventEmitter;
+use super::quality_gate::{self, GateRun};
+use super::triage::{self, TaskFailure, TriageDecision};
+use super::worker::CostRecorder;
 
 const MAX_VERIFY_ATTEMPTS: usize = 3;
 
+/// Longest verifier report carried into the fix prompt.
+const MAX_REPORT_CHARS: usize = 4000;
+
+/// What one verification attempt found: the agent's last message and the
+/// quality gates re-run afterwards. Fed to the next fix attempt.
+struct VerifyOutcome {
+    verifier_text: String,
+    gates: Vec<GateRun>,
+}
+
+impl VerifyOutcome {
+    fn passed(&self) -> bool {
+        self.verifier_text.contains("VERIFY_PASS") && !self.gates.iter().any(GateRun::blocks)
+    }
+
+    /// Markdown summary of the failing commands and the verifier's findings.
+    fn failure_report(&self) -> String {
+        let mut report = quality_gate::failure_report(&self.gates);
+
+        let text = self.verifier_text.trim();
+        report.push_str("### Verifier report\n\n");
+        if text.is_empty() {
+            report.push_str("(the verifier produced no report)\n");
+        } else {
+            report.push_str(&truncate_with_ellipsis(text, MAX_REPORT_CHARS));
+            report.push('\n');
+        }
+        report
+    }
+}
+
 /// Run the verification phase with fix-and-retry loop.
 pub async fn run_verify_phase(
     config: &SpawnConfig,
     creds: &Credentials,
     store: SessionStore,
+    emitter: &Arc<EventEmitter>,
 ) -> bool {
-    for attempt in 0..MAX_VERIFY_ATTEMPTS {
-        let prompt = if attempt == 0 {
-            super::prompts::build_verifier_prompt(config)
-        } else {
-            let failures = "Previous verification attempt failed. Please re-check.";
-            super::prompts::build_fix_prompt(failures, config)
+    let sandbox = ProcessSandbox::new(
+        &config.sandbox,
+        &config.working_dir,
+        config.git_common_dir.as_deref(),
+    )
+    .map(Arc::new);
+    let gate_configs = quality_gate::build_gate_configs(
+        &config.quality_gates,
+        &config.project_languages,
+        &config.working_dir,
+        &[],
+        sandbox.as_ref(),
+    );
+    let mut previous: Option<VerifyOutcome> = None;
+
+    for _ in 0..MAX_VERIFY_ATTEMPTS {
+        let prompt = match &previous {
+            None => super::prompts::build_verifier_prompt(config),
+            Some(outcome) => super::prompts::build_fix_prompt(&outcome.failure_report(), config),
         };
 
-        let result = run_single_agent(&prompt, "verifier", config, creds, store.clone()).await;
-        if result.contains("VERIFY_PASS") {
+        let verifier_text = run_single_agent(
+            &prompt,
+            "verifier",
+            None,
+            config,
+            creds,
+            store.clone(),
+            emitter,
+        )
+        .await;
+
+        // Re-run the gates ourselves rather than trusting the agent's summary
+        let gates = quality_gate::run_gates(&gate_configs, |run| {
+            emitter.emit_quality_gate("verify", run);
+        })
+        .await;
+
+        let outcome = VerifyOutcome {
+            verifier_text,
+            gates,
+        };
+        if outcome.passed() {
             return true;
         }
+        previous = Some(outcome);
     }
 
     false
@@ -39,19 +114,96 @@ pub async fn run_pr_phase(
     config: &SpawnConfig,
     creds: &Credentials,
     store: SessionStore,
+    emitter: &Arc<EventEmitter>,
     verification_passed: bool,
+    skipped: &[String],
+    approval_comment: Option<&str>,
+) {
+    let prompt =
+        super::prompts::build_pr_prompt(config, verification_passed, skipped, approval_comment);
+    run_single_agent(&prompt, "pr-agent", None, config, creds, store, emitter).await;
+}
+
+/// Revise the drone's work after a reviewer rejected it at the PR gate.
+pub async fn run_review_fix(
+    config: &SpawnConfig,
+    creds: &Credentials,
+    store: SessionStore,
+    emitter: &Arc<EventEmitter>,
+    comment: &str,
 ) {
-    let prompt = super::prompts::build_pr_prompt(config, verification_passed);
-    run_single_agent(&prompt, "pr-agent", config, creds, store).await;
+    let prompt = super::prompts::build_review_fix_prompt(comment, config);
+    run_single_agent(&prompt, "reviser", None, config, creds, store, emitter).await;
 }
 
-/// Run a single agent (verifier/PR) and return the last assistant text.
+/// Ask the triage agent what to do with a task that failed on every retry.
+/// Returns `None` if it gives no usable decision.
+pub async fn run_triage(
+    config: &SpawnConfig,
+    creds: &Credentials,
+    store: SessionStore,
+    emitter: &Arc<EventEmitter>,
+    task: &StructuredTask,
+    failure: &TaskFailure,
+    dependents: &[&StructuredTask],
+) -> Option<TriageDecision> {
+    let transcript = if failure.transcript.is_empty() {
+        "(no transcript was recorded)".to_string()
+    } else {
+        triage::render_transcript(&failure.transcript)
+    };
+    let prompt =
+        super::prompts::build_triage_prompt(task, &failure.error, &transcript, dependents, config);
+    let name = format!("{}-triage", task.worker_name());
+    let task_id = task.number.to_string();
+    let result = run_single_agent(
+        &prompt,
+        &name,
+        Some(&task_id),
+        config,
+        creds,
+        store,
+        emitter,
+    )
+    .await;
+    triage::parse_decision(&result)
+}
+
+/// Hand an in-progress merge conflict to a resolver agent.
+/// Returns `true` if the agent reports every conflict resolved.
+pub async fn run_conflict_resolver(
+    config: &SpawnConfig,
+    creds: &Credentials,
+    store: SessionStore,
+    emitter: &Arc<EventEmitter>,
+    task: &StructuredTask,
+    files: &[String],
+) -> bool {
+    let prompt = super::prompts::build_conflict_resolver_prompt(task, files, config);
+    let name = format!("{}-resolver", task.worker_name());
+    let task_id = task.number.to_string();
+    let result = run_single_agent(
+        &prompt,
+        &name,
+        Some(&task_id),
+        config,
+        creds,
+        store,
+        emitter,
+    )
+    .await;
+    result.contains("CONFLICT_RESOLVED")
+}
+
+/// Run a single agent (verifier/PR/resolver) and return the last assistant text.
 async fn run_single_agent(
     system_prompt: &str,
     name: &str,
+    task_id: Option<&str>,
     config: &SpawnConfig,
     creds: &Credentials,
     store: SessionStore,
+    emitter: &Arc<EventEmitter>,
 ) -> String {
     let model_id = provider::resolve_model(&config.model, creds);
     let (tx, _rx) = tokio::sync::broadcast::channel::<String>(256);
@@ -79,6 +231,24 @@ async fn run_single_agent(
         max_turns: Some(25),
         mcp_pool: None,
         deferred_tools_active: false,
+        permissions: Some(Arc::new(Permissions::load(
+            TrustLevel::Drone,
+            &config.working_dir,
+        ))),
+        write_policy: None,
+        sandbox: ProcessSandbox::new(
+            &config.sandbox,
+            &config.working_dir,
+            config.git_common_dir.as_deref(),
+        )
+        .map(Arc::new),
+        shell: None,
+        processes: None,
+        prompt_caching: true,
+        usage_sink: Some(Arc::new(CostRecorder::new(emitter.clone(), name, task_id))),
+        task_proposer: None,
+        inbox: None,
+        pause_flag: None,
     };
 
     match run_agentic_loop(params).await {
@@ -112,3 +282,65 @@ pub fn extract_last_text(messages: &[Message]) -> String {
     }
     String::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::native_team::quality_gate::GateResult;
+
+    fn run(name: &str, command: &str, required: bool, result: GateResult) -> GateRun {
+        GateRun {
+            name: name.to_string(),
+            command: command.to_string(),
+            required,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_failure_report_includes_gates_and_findings() {
+        let outcome = VerifyOutcome {
+            verifier_text: "VERIFY_FAIL: test_login panics on empty password".to_string(),
+            gates: vec![
+                run("fmt", "cargo fmt --check", true, GateResult::Passed),
+                run(
+                    "tests",
+                    "cargo test",
+                    true,
+                    GateResult::Failed {
+                        output: "test auth::test_login ... FAILED".to_string(),
+                    },
+                ),
+            ],
+        };
+        assert!(!outcome.passed());
+
+        let report = outcome.failure_report();
+        assert!(report.contains("### tests failed: `cargo test`"));
+        assert!(report.contains("test auth::test_login ... FAILED"));
+        assert!(!report.contains("cargo fmt"));
+        assert!(report.contains("test_login panics on empty password"));
+    }
+
+    #[test]
+    fn test_pass_requires_verifier_and_required_gates() {
+        let timed_out = VerifyOutcome {
+            verifier_text: "All good. VERIFY_PASS".to_string(),
+            gates: vec![run("tests", "npm test", true, GateResult::Timeout)],
+        };
+        assert!(!timed_out.passed());
+        assert!(timed_out.failure_report().contains("Timed out"));
+
+        let advisory = VerifyOutcome {
+            verifier_text: "All good. VERIFY_PASS".to_string(),
+            gates: vec![run("docs", "./check-links.sh", false, GateResult::Timeout)],
+        };
+        assert!(advisory.passed());
+
+        let clean = VerifyOutcome {
+            verifier_text: "All good. VERIFY_PASS".to_string(),
+            gates: Vec::new(),
+        };
+        assert!(clean.passed());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::backend::SpawnConfig;
 use crate::types::StructuredTask;
 
+use super::quality_gate;
 use super::worker_notes::{self, WorkerNote};
 
 // Re-export shared helpers from agent_team prompts
@@ -17,6 +18,7 @@ pub fn build_worker_prompt(
 ) -> String {
     let plan_content = std::fs::read_to_string(&config.prd_path).unwrap_or_default();
     let notes_section = worker_notes::format_notes_for_prompt(dependency_notes);
+    let acceptance_section = acceptance_section(task);
 
     let worker_name = task.worker_name();
     format!(
@@ -35,7 +37,7 @@ pub fn build_worker_prompt(
 ## File Ownership
 
 {ownership_hint}
-{notes_section}
+{acceptance_section}{notes_section}
 ## Rules
 
 - Focus ONLY on your assigned task — do not work on other tasks
@@ -53,6 +55,25 @@ pub fn build_worker_prompt(
         plan_content = plan_content.trim(),
         ownership_hint = ownership_hint,
         notes_section = notes_section,
+        acceptance_section = acceptance_section,
+    )
+}
+
+/// Extra rule appended to the worker prompt when `ProposeTask` is offered.
+pub const PROPOSE_TASK_RULE: &str =
+    "\n- If you find necessary work outside your task, call ProposeTask \
+     instead of doing it yourself";
+
+/// Tell the worker which `verify:` commands will decide whether it is done.
+fn acceptance_section(task: &StructuredTask) -> String {
+    if task.verify.is_empty() {
+        return String::new();
+    }
+    let commands: Vec<String> = task.verify.iter().map(|c| format!("- `{c}`")).collect();
+    format!(
+        "\n## Acceptance Checks\n\nYour task is only accepted once these commands pass. \
+         Run them yourself before reporting TASK_COMPLETE:\n\n{}\n",
+        commands.join("\n")
     )
 }
 
@@ -84,7 +105,7 @@ pub fn build_continuation_prompt(task: &StructuredTask, progress: &str) -> Strin
 
 /// Build the system prompt for the verification phase.
 pub fn build_verifier_prompt(config: &SpawnConfig) -> String {
-    let verification_commands = build_verification_commands(&config.project_languages);
+    let verification_commands = verification_commands(config);
 
     format!(
         r#"You are the verification agent for team "{team_name}".
@@ -113,7 +134,7 @@ Fix any issues you find. This is critical — the code must pass all checks.
 
 /// Build the prompt for a fix-and-retry iteration after verification failure.
 pub fn build_fix_prompt(failures: &str, config: &SpawnConfig) -> String {
-    let verification_commands = build_verification_commands(&config.project_languages);
+    let verification_commands = verification_commands(config);
 
     format!(
         r#"You are the fix agent for team "{team_name}".
@@ -144,16 +165,199 @@ The following verification checks failed:
     )
 }
 
+/// Build the system prompt for revising the drone's work after a reviewer
+/// rejected it before the PR.
+pub fn build_review_fix_prompt(comment: &str, config: &SpawnConfig) -> String {
+    let verification_commands = verification_commands(config);
+
+    format!(
+        r#"You are the revision agent for team "{team_name}".
+
+## Reviewer Feedback
+
+A reviewer looked at the team's changes before the pull request was opened and asked for changes:
+
+{comment}
+
+## Your Task
+
+1. Make the changes the reviewer asked for
+2. Re-run the verification commands:
+
+{verification_commands}
+
+## Rules
+
+- Only address the feedback; don't rework unrelated code
+- Don't commit, push or open a PR; the changes are reviewed again first
+- Include "REVISION_DONE" in your final message"#,
+        team_name = config.team_name,
+        comment = comment,
+        verification_commands = verification_commands,
+    )
+}
+
+/// The drone's declared quality gates, or the commands detected from the
+/// project languages when the plan and config declare none.
+fn verification_commands(config: &SpawnConfig) -> String {
+    if config.quality_gates.is_empty() {
+        return build_verification_commands(&config.project_languages);
+    }
+    quality_gate::build_gate_configs(&config.quality_gates, &[], &config.working_dir, &[], None)
+        .iter()
+        .map(|gate| {
+            let advisory = if gate.required { "" } else { " (advisory)" };
+            format!("- `{}` — {}{advisory}", gate.command.trim(), gate.name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build the prompt for resolving a merge conflict between a finished
+/// worker's branch and the drone worktree.
+pub fn build_conflict_resolver_prompt(
+    task: &StructuredTask,
+    files: &[String],
+    config: &SpawnConfig,
+) -> String {
+    let file_list: String = files.iter().map(|f| format!("- `{f}`\n")).collect();
+
+    format!(
+        r#"You are the merge conflict resolver for team "{team_name}".
+
+## Context
+
+Task **{number}. {title}** was completed on its own branch, but merging it
+back into the team branch stopped on conflicts. The merge is in progress in
+your working directory.
+
+## Task Description
+
+{body}
+
+## Conflicted Files
+
+{file_list}
+## Your Task
+
+1. Read each conflicted file and understand both sides of every conflict
+2. Edit the files so they keep the intent of BOTH sides — do not drop either change
+3. Remove every conflict marker (`<<<<<<<`, `=======`, `>>>>>>>`)
+
+## Rules
+
+- Only edit the conflicted files listed above
+- Do NOT run `git commit`, `git merge --abort` or `git reset` — the coordinator concludes the merge
+- Include "CONFLICT_RESOLVED" in your final message when every conflict is resolved
+- Include "CONFLICT_UNRESOLVED: <reason>" if you cannot resolve them"#,
+        team_name = config.team_name,
+        number = task.number,
+        title = task.title,
+        body = task.body,
+        file_list = file_list,
+    )
+}
+
+/// Build the prompt for triaging a task that failed on every retry.
+pub fn build_triage_prompt(
+    task: &StructuredTask,
+    error: &str,
+    transcript: &str,
+    dependents: &[&StructuredTask],
+    config: &SpawnConfig,
+) -> String {
+    let dependents = if dependents.is_empty() {
+        "No other task depends on this one.".to_string()
+    } else {
+        dependents
+            .iter()
+            .map(|t| format!("- {}. {}", t.number, t.title))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        r#"You are the failure triage agent for team "{team_name}".
+
+## Failed Task
+
+Task **{number}. {title}** failed on every attempt.
+
+{body}
+
+## Last Error
+
+{error}
+
+## Tasks Waiting On It
+
+{dependents}
+
+## Last Worker Transcript
+
+{transcript}
+
+## Your Task
+
+Work out why the task keeps failing, then choose ONE action:
+
+- `split`: the task is too broad; replace it with smaller tasks that run one after another
+- `rewrite`: the description is wrong or ambiguous; retry it with a corrected description
+- `skip`: the task is not needed for the rest of the work; the drone carries on without it
+- `fail`: none of the above would help; the drone stops before the PR
+
+## Rules
+
+- You may read the code, but do NOT modify any files
+- Only skip a task the tasks waiting on it can do without, and justify it in `reason`
+- End your final message with "TRIAGE_DECISION" followed by one JSON object:
+
+```json
+{{"action": "split", "reason": "...", "tasks": [{{"title": "...", "description": "..."}}]}}
+{{"action": "rewrite", "reason": "...", "description": "..."}}
+{{"action": "skip", "reason": "..."}}
+{{"action": "fail", "reason": "..."}}
+```"#,
+        team_name = config.team_name,
+        number = task.number,
+        title = task.title,
+        body = task.body,
+        error = error,
+        dependents = dependents,
+        transcript = transcript,
+    )
+}
+
 /// Build the prompt for the PR/MR phase.
-pub fn build_pr_prompt(config: &SpawnConfig, verification_passed: bool) -> String {
+/// `skipped` lists tasks that failure triage dropped, with the reason.
+pub fn build_pr_prompt(
+    config: &SpawnConfig,
+    verification_passed: bool,
+    skipped: &[String],
+    approval_comment: Option<&str>,
+) -> String {
     let pr_instructions = detect_pr_instructions(&config.remote_url);
     let worktree_path = config.worktree_path.display();
 
-    let status_note = if verification_passed {
+    let mut status_note = if verification_passed {
         "All verification checks passed.".to_string()
     } else {
         "Some verification checks failed. Include a \"Known Issues\" section in the PR.".to_string()
     };
+    if !skipped.is_empty() {
+        status_note.push_str(
+            "\n\nThese tasks were skipped after failing. List them, with the reason, in a \"Known Issues\" section:\n\n",
+        );
+        for task in skipped {
+            status_note.push_str(&format!("- {task}\n"));
+        }
+    }
+    if let Some(comment) = approval_comment {
+        status_note.push_str(&format!(
+            "\n\nA reviewer approved this PR with a comment. Include it in the PR description:\n\n> {}\n",
+            comment.replace('\n', "\n> ")
+        ));
+    }
 
     format!(
         r#"You are the PR agent for team "{team_name}".
@@ -0,0 +1,262 @@
+//! Follow-up tasks proposed by workers while a drone is running.
+//!
+//! Workers queue proposals through the `ProposeTask` tool; the coordinator
+//! drains the queue and adds each task to its scheduler and task files.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+
+use crate::types::{StructuredTask, TaskType};
+use crate::webui::tools::TaskProposer;
+
+/// Proposals shared between the coordinator and its workers, capped per drone.
+#[derive(Debug)]
+pub struct TaskProposals {
+    state: Mutex<ProposalState>,
+}
+
+#[derive(Debug)]
+struct ProposalState {
+    next_number: usize,
+    remaining: usize,
+    /// Plan work and proposed task numbers, valid as `depends_on` targets
+    known: HashSet<usize>,
+    /// Setup and PR task numbers, which workers can't depend on
+    reserved: HashSet<usize>,
+    pending: Vec<StructuredTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProposalInput {
+    title: String,
+    description: String,
+    #[serde(default)]
+    depends_on: Vec<usize>,
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+impl TaskProposals {
+    /// Proposed tasks are numbered after the highest plan task number.
+    pub fn new(tasks: &[StructuredTask], cap: usize) -> Self {
+        let (work, other): (Vec<_>, Vec<_>) =
+            tasks.iter().partition(|t| t.task_type == TaskType::Work);
+        let state = ProposalState {
+            next_number: tasks.iter().map(|t| t.number).max().unwrap_or(0) + 1,
+            remaining: cap,
+            known: work.iter().map(|t| t.number).collect(),
+            reserved: other.iter().map(|t| t.number).collect(),
+            pending: Vec::new(),
+        };
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Validate and queue a `ProposeTask` input from the worker of task
+    /// `proposed_by`. Returns the queued task.
+    pub fn propose(
+        &self,
+        proposed_by: usize,
+        input: &serde_json::Value,
+    ) -> Result<StructuredTask, String> {
+        let input: ProposalInput = serde_json::from_value(input.clone())
+            .map_err(|e| format!("Invalid ProposeTask input: {e}"))?;
+        let title = input.title.trim();
+        if title.is_empty() {
+            return Err("ProposeTask needs a non-empty title".to_string());
+        }
+
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        if state.remaining == 0 {
+            return Err(
+                "This drone has reached its limit of proposed tasks. Note the remaining work in your completion summary instead."
+                    .to_string(),
+            );
+        }
+        if let Some(dep) = input.depends_on.iter().find(|d| !state.known.contains(d)) {
+            if state.reserved.contains(dep) {
+                return Err(format!(
+                    "Task {dep} is a setup or PR task; proposed tasks can only depend on work tasks"
+                ));
+            }
+            return Err(format!("Unknown dependency: task {dep}"));
+        }
+
+        let task = StructuredTask {
+            number: state.next_number,
+            title: title.to_string(),
+            body: format!(
+                "{}\n\nProposed by the worker of task {proposed_by}.",
+                input.description.trim()
+            ),
+            task_type: TaskType::Work,
+            model: None,
+            parallel: true,
+            files: input.files,
+            depends_on: input.depends_on,
+            priority: 0,
+            resource: None,
+            run: Vec::new(),
+            verify: Vec::new(),
+            approval: false,
+            retry: Default::default(),
+            limits: Default::default(),
+        };
+        state.next_number += 1;
+        state.remaining -= 1;
+        state.known.insert(task.number);
+        state.pending.push(task.clone());
+        Ok(task)
+    }
+
+    /// Claim the next task number for a task the coordinator adds itself
+    /// (e.g. when triage splits a task). Doesn't count against the cap.
+    /// Returns `None` if the lock is poisoned, like `propose` and `drain`.
+    pub fn reserve_number(&self) -> Option<usize> {
+        let mut state = self.state.lock().ok()?;
+        let number = state.next_number;
+        state.next_number += 1;
+        state.known.insert(number);
+        Some(number)
+    }
+
+    /// Take the proposals queued since the last call, in proposal order.
+    pub fn drain(&self) -> Vec<StructuredTask> {
+        self.state
+            .lock()
+            .map(|mut s| std::mem::take(&mut s.pending))
+            .unwrap_or_default()
+    }
+}
+
+/// `TaskProposer` handed to the worker of one task.
+pub struct WorkerProposer {
+    proposals: Arc<TaskProposals>,
+    task_number: usize,
+}
+
+impl WorkerProposer {
+    pub fn new(proposals: Arc<TaskProposals>, task_number: usize) -> Self {
+        Self {
+            proposals,
+            task_number,
+        }
+    }
+}
+
+impl TaskProposer for WorkerProposer {
+    fn propose(&self, input: &serde_json::Value) -> Result<String, String> {
+        let task = self.proposals.propose(self.task_number, input)?;
+        Ok(format!(
+            "Added task {}: {}. Another worker will pick it up once its dependencies complete; carry on with your own task.",
+            task.number, task.title
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan_task(number: usize) -> StructuredTask {
+        StructuredTask {
+            number,
+            title: format!("Task {number}"),
+            parallel: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_proposals_are_numbered_after_plan_and_capped() {
+        let proposals = TaskProposals::new(&[plan_task(1), plan_task(2)], 2);
+
+        let first = proposals
+            .propose(
+                1,
+                &serde_json::json!({"title": "Add migration", "description": "x"}),
+            )
+            .unwrap();
+        assert_eq!(first.number, 3);
+        let second = proposals
+            .propose(
+                2,
+                &serde_json::json!({"title": "Wire it", "description": "y", "depends_on": [3]}),
+            )
+            .unwrap();
+        assert_eq!(second.number, 4);
+        assert_eq!(second.depends_on, vec![3]);
+
+        let err = proposals
+            .propose(1, &serde_json::json!({"title": "More", "description": "z"}))
+            .unwrap_err();
+        assert!(err.contains("limit"), "{err}");
+
+        let drained: Vec<usize> = proposals.drain().iter().map(|t| t.number).collect();
+        assert_eq!(drained, vec![3, 4]);
+        assert!(proposals.drain().is_empty());
+    }
+
+    #[test]
+    fn test_proposal_rejects_unknown_dependency() {
+        let proposals = TaskProposals::new(&[plan_task(1)], 5);
+        let err = proposals
+            .propose(
+                1,
+                &serde_json::json!({"title": "T", "description": "d", "depends_on": [7]}),
+            )
+            .unwrap_err();
+        assert_eq!(err, "Unknown dependency: task 7");
+        assert!(proposals
+            .propose(1, &serde_json::json!({"title": " ", "description": "d"}))
+            .is_err());
+        assert!(proposals.drain().is_empty());
+    }
+
+    #[test]
+    fn test_poisoned_lock_is_reported_everywhere() {
+        let proposals = TaskProposals::new(&[plan_task(1)], 5);
+        assert_eq!(proposals.reserve_number(), Some(2));
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = proposals.state.lock().unwrap();
+            panic!("poison");
+        });
+        assert_eq!(proposals.reserve_number(), None);
+        assert!(proposals
+            .propose(1, &serde_json::json!({"title": "T", "description": "d"}))
+            .is_err());
+        assert!(proposals.drain().is_empty());
+    }
+
+    #[test]
+    fn test_proposal_rejects_setup_and_pr_dependencies() {
+        let setup = StructuredTask {
+            task_type: TaskType::Setup,
+            ..plan_task(1)
+        };
+        let pr = StructuredTask {
+            task_type: TaskType::Pr,
+            ..plan_task(3)
+        };
+        let proposals = TaskProposals::new(&[setup, plan_task(2), pr], 5);
+        for dep in [1, 3] {
+            let err = proposals
+                .propose(
+                    2,
+                    &serde_json::json!({"title": "T", "description": "d", "depends_on": [dep]}),
+                )
+                .unwrap_err();
+            assert!(err.contains("setup or PR task"), "{err}");
+        }
+        let task = proposals
+            .propose(
+                2,
+                &serde_json::json!({"title": "T", "description": "d", "depends_on": [2]}),
+            )
+            .unwrap();
+        assert_eq!(task.number, 4);
+    }
+}
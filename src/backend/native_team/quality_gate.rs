@@ -1,13 +1,21 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::backend::agent_team::prompts::build_verification_commands;
+use crate::types::QualityGateSpec;
+use crate::webui::tools::sandbox::{self, ProcessSandbox};
 
 /// Configuration for a quality gate check.
 pub struct QualityGateConfig {
+    pub name: String,
     pub command: String,
     pub timeout: Duration,
     pub cwd: PathBuf,
+    /// Advisory gates are reported but don't block completion
+    pub required: bool,
+    /// Run the command inside this sandbox
+    pub sandbox: Option<Arc<ProcessSandbox>>,
 }
 
 /// Result of running a quality gate.
@@ -17,6 +25,141 @@ pub enum GateResult {
     Timeout,
 }
 
+/// A gate command together with its result.
+pub struct GateRun {
+    pub name: String,
+    pub command: String,
+    pub required: bool,
+    pub result: GateResult,
+}
+
+impl GateRun {
+    pub fn passed(&self) -> bool {
+        matches!(self.result, GateResult::Passed)
+    }
+
+    /// Whether this run should hold back completion.
+    pub fn blocks(&self) -> bool {
+        self.required && !self.passed()
+    }
+
+    /// Output to report when the gate did not pass (empty if it did).
+    pub fn failure_output(&self) -> String {
+        match &self.result {
+            GateResult::Passed => String::new(),
+            GateResult::Failed { output } => output.clone(),
+            GateResult::Timeout => "Timed out".to_string(),
+        }
+    }
+}
+
+/// Run a quality gate and keep its command alongside the result.
+pub async fn run_gate(config: &QualityGateConfig) -> GateRun {
+    GateRun {
+        name: config.name.clone(),
+        command: config.command.clone(),
+        required: config.required,
+        result: run_quality_gate(config).await,
+    }
+}
+
+/// Run every gate in order, calling `on_result` as each one finishes.
+/// Later gates still run after a failure so that all problems are
+/// reported at once.
+pub async fn run_gates(
+    configs: &[QualityGateConfig],
+    mut on_result: impl FnMut(&GateRun),
+) -> Vec<GateRun> {
+    let mut runs = Vec::with_capacity(configs.len());
+    for config in configs {
+        let run = run_gate(config).await;
+        on_result(&run);
+        runs.push(run);
+    }
+    runs
+}
+
+/// Markdown sections for each gate that did not pass.
+pub fn failure_report(runs: &[GateRun]) -> String {
+    let mut report = String::new();
+    for run in runs.iter().filter(|r| !r.passed()) {
+        let label = if run.required {
+            "failed"
+        } else {
+            "failed (advisory)"
+        };
+        report.push_str(&format!(
+            "### {} {label}: `{}`\n\n```\n{}\n```\n\n",
+            run.name,
+            run.command,
+            run.failure_output().trim_end()
+        ));
+    }
+    report
+}
+
+/// Longest a task's own `verify:` command may run.
+const ACCEPTANCE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Required gates for a task's `verify:` acceptance commands.
+pub fn build_acceptance_configs(
+    commands: &[String],
+    cwd: &Path,
+    sandbox: Option<&Arc<ProcessSandbox>>,
+) -> Vec<QualityGateConfig> {
+    commands
+        .iter()
+        .map(|command| QualityGateConfig {
+            name: "acceptance".to_string(),
+            command: command.clone(),
+            timeout: ACCEPTANCE_TIMEOUT,
+            cwd: cwd.to_path_buf(),
+            required: true,
+            sandbox: sandbox.cloned(),
+        })
+        .collect()
+}
+
+/// Build the gates to run for a task (or, with no files, for the whole
+/// drone). Declared gates run in order with `{files}` replaced by the
+/// shell-quoted file list; without any, fall back to the one command
+/// detected from the project languages.
+pub fn build_gate_configs(
+    specs: &[QualityGateSpec],
+    project_languages: &[String],
+    cwd: &Path,
+    files: &[String],
+    sandbox: Option<&Arc<ProcessSandbox>>,
+) -> Vec<QualityGateConfig> {
+    if specs.is_empty() {
+        return build_gate_config(project_languages, cwd)
+            .map(|config| QualityGateConfig {
+                sandbox: sandbox.cloned(),
+                ..config
+            })
+            .into_iter()
+            .collect();
+    }
+
+    let quoted: Vec<String> = files.iter().map(|f| shell_quote(f)).collect();
+    let files_arg = quoted.join(" ");
+    specs
+        .iter()
+        .map(|spec| QualityGateConfig {
+            name: spec.name.clone(),
+            command: spec.command.replace("{files}", &files_arg),
+            timeout: Duration::from_secs(spec.timeout_secs),
+            cwd: cwd.to_path_buf(),
+            required: spec.required,
+            sandbox: sandbox.cloned(),
+        })
+        .collect()
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
 /// Run a quality gate command and return the result.
 pub async fn run_quality_gate(config: &QualityGateConfig) -> GateResult {
     let result = tokio::time::timeout(config.timeout, run_command(config)).await;
@@ -71,9 +214,12 @@ pub fn build_gate_config(project_languages: &[String], cwd: &Path) -> Option<Qua
     })?;
 
     Some(QualityGateConfig {
+        name: "check".to_string(),
         command,
         timeout: Duration::from_secs(120),
         cwd: cwd.to_path_buf(),
+        required: true,
+        sandbox: None,
     })
 }
 
@@ -83,12 +229,14 @@ struct CommandOutput {
 }
 
 async fn run_command(config: &QualityGateConfig) -> anyhow::Result<CommandOutput> {
-    let output = tokio::process::Command::new("sh")
-        .arg("-c")
-        .arg(&config.command)
-        .current_dir(&config.cwd)
-        .output()
-        .await?;
+    let output = sandbox::shell_command(
+        config.sandbox.as_deref(),
+        "sh",
+        &config.command,
+        &config.cwd,
+    )
+    .output()
+    .await?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -115,9 +263,12 @@ mod tests {
     #[tokio::test]
     async fn test_quality_gate_pass() {
         let config = QualityGateConfig {
+            name: "test".to_string(),
             command: "true".to_string(),
             timeout: Duration::from_secs(5),
             cwd: std::env::temp_dir(),
+            required: true,
+            sandbox: None,
         };
         let result = run_quality_gate(&config).await;
         assert!(matches!(result, GateResult::Passed));
@@ -126,9 +277,12 @@ mod tests {
     #[tokio::test]
     async fn test_quality_gate_fail() {
         let config = QualityGateConfig {
+            name: "test".to_string(),
             command: "echo 'error: something wrong' && false".to_string(),
             timeout: Duration::from_secs(5),
             cwd: std::env::temp_dir(),
+            required: true,
+            sandbox: None,
         };
         let result = run_quality_gate(&config).await;
         assert!(matches!(result, GateResult::Failed { .. }));
@@ -137,9 +291,12 @@ mod tests {
     #[tokio::test]
     async fn test_quality_gate_timeout() {
         let config = QualityGateConfig {
+            name: "test".to_string(),
             command: "sleep 10".to_string(),
             timeout: Duration::from_millis(100),
             cwd: std::env::temp_dir(),
+            required: true,
+            sandbox: None,
         };
         let result = run_quality_gate(&config).await;
         assert!(matches!(result, GateResult::Timeout));
@@ -157,4 +314,74 @@ mod tests {
         let config = build_gate_config(&[], Path::new("/tmp"));
         assert!(config.is_none());
     }
+
+    #[test]
+    fn test_declared_gates_replace_detection() {
+        let specs = vec![
+            QualityGateSpec {
+                name: "fmt".to_string(),
+                command: "cargo fmt --check".to_string(),
+                timeout_secs: 30,
+                required: true,
+            },
+            QualityGateSpec {
+                name: "unit".to_string(),
+                command: "npx vitest run {files}".to_string(),
+                timeout_secs: 600,
+                required: false,
+            },
+        ];
+        let files = vec!["src/a.ts".to_string(), "it's.ts".to_string()];
+        let configs = build_gate_configs(
+            &specs,
+            &["rust".to_string()],
+            Path::new("/tmp"),
+            &files,
+            None,
+        );
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].command, "cargo fmt --check");
+        assert_eq!(configs[0].timeout, Duration::from_secs(30));
+        assert_eq!(
+            configs[1].command,
+            "npx vitest run 'src/a.ts' 'it'\\''s.ts'"
+        );
+        assert!(!configs[1].required);
+
+        let detected =
+            build_gate_configs(&[], &["rust".to_string()], Path::new("/tmp"), &files, None);
+        assert_eq!(detected.len(), 1);
+        assert!(detected[0].command.contains("cargo"));
+    }
+
+    #[tokio::test]
+    async fn test_run_gates_reports_each_in_order() {
+        let gate = |name: &str, command: &str, required: bool| QualityGateConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            timeout: Duration::from_secs(5),
+            cwd: std::env::temp_dir(),
+            required,
+            sandbox: None,
+        };
+        let configs = vec![
+            gate("lint", "echo 'unused import' && false", false),
+            gate("build", "true", true),
+            gate("tests", "echo '1 failed' && false", true),
+        ];
+
+        let mut seen = Vec::new();
+        let runs = run_gates(&configs, |run| seen.push(run.name.clone())).await;
+        assert_eq!(seen, ["lint", "build", "tests"]);
+        assert!(!runs[0].blocks());
+        assert!(runs[1].passed());
+        assert!(runs[2].blocks());
+
+        let report = failure_report(&runs);
+        assert!(report.contains("### lint failed (advisory): `echo 'unused import' && false`"));
+        assert!(report.contains("### tests failed: "));
+        assert!(report.contains("1 failed"));
+        assert!(!report.contains("build"));
+    }
 }
@@ -1,18 +1,36 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
-use crate::types::{StructuredTask, TaskType};
+use serde::Serialize;
+
+use crate::types::{RetryPolicy, StructuredTask, TaskType};
 
 /// Status of a task in the scheduler.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TaskState {
     Pending,
     Running,
     Completed,
     Failed,
+    /// Finished, but its branch conflicted when merged back into the drone worktree
+    Conflict,
+    /// Dropped or split up by failure triage; counts as done for its dependents
+    Skipped,
+    /// Finished and parked for a reviewer; doesn't take a worker slot
+    AwaitingApproval,
+}
+
+impl TaskState {
+    /// Completed or skipped: nothing is left to run for the task.
+    pub fn is_done(self) -> bool {
+        matches!(self, TaskState::Completed | TaskState::Skipped)
+    }
 }
 
-/// Maximum number of retries before a task is permanently failed.
-const MAX_RETRIES: usize = 2;
+/// Times a task is re-queued after transient errors (rate limits, outages)
+/// before they start counting against its attempts.
+pub const MAX_TRANSIENT_RETRIES: usize = 5;
 
 /// A task wrapped with scheduling metadata.
 #[derive(Debug)]
@@ -20,12 +38,29 @@ struct ScheduledTask {
     task: StructuredTask,
     state: TaskState,
     retries: usize,
+    transient_retries: usize,
+    /// Not dispatched before this moment (retry backoff)
+    not_before: Option<Instant>,
+    /// Number of tasks on the longest `depends_on` chain starting here
+    critical_path: usize,
+}
+
+impl ScheduledTask {
+    fn delay(&mut self, delay: Duration) {
+        self.not_before = (!delay.is_zero()).then(|| Instant::now() + delay);
+    }
+
+    fn backing_off(&self, now: Instant) -> bool {
+        self.not_before.is_some_and(|t| t > now)
+    }
 }
 
 /// DAG-based task scheduler with dependency resolution.
 ///
 /// Resolves which tasks are ready to run based on their `depends_on`
-/// relationships, respects `parallel` flags, and enforces `max_concurrent`.
+/// relationships, respects `parallel` flags and `resource` classes, and
+/// enforces `max_concurrent`. Ready tasks are started by priority, then by
+/// critical-path length, so long dependency chains aren't left until last.
 pub struct TaskScheduler {
     tasks: Vec<ScheduledTask>,
     max_concurrent: usize,
@@ -42,9 +77,13 @@ impl TaskScheduler {
         max_concurrent: usize,
         completed_numbers: &HashSet<usize>,
     ) -> Self {
-        let scheduled = tasks
+        let tasks: Vec<StructuredTask> = tasks
             .into_iter()
             .filter(|t| t.task_type == TaskType::Work)
+            .collect();
+        let critical_paths = critical_path_lengths(&tasks);
+        let scheduled = tasks
+            .into_iter()
             .map(|task| {
                 let state = if completed_numbers.contains(&task.number) {
                     TaskState::Completed
@@ -52,9 +91,12 @@ impl TaskScheduler {
                     TaskState::Pending
                 };
                 ScheduledTask {
+                    critical_path: critical_paths.get(&task.number).copied().unwrap_or(1),
                     task,
                     state,
                     retries: 0,
+                    transient_retries: 0,
+                    not_before: None,
                 }
             })
             .collect();
@@ -64,32 +106,56 @@ impl TaskScheduler {
         }
     }
 
-    /// Return tasks that are ready to run: pending, all deps completed,
-    /// respects parallel flag and max_concurrent limit.
+    /// Return tasks that are ready to run: pending, all deps completed, not
+    /// backing off, respects parallel flag, resource classes and
+    /// max_concurrent limit. Ordered by priority, then critical-path length,
+    /// then plan order.
     pub fn ready_tasks(&self) -> Vec<&StructuredTask> {
-        let running_count = self
+        let running: Vec<&ScheduledTask> = self
             .tasks
             .iter()
             .filter(|t| t.state == TaskState::Running)
-            .count();
+            .collect();
+        let running_count = running.len();
         let available_slots = self.max_concurrent.saturating_sub(running_count);
 
         if available_slots == 0 {
             return Vec::new();
         }
 
+        let now = Instant::now();
+        let mut candidates: Vec<&ScheduledTask> = self
+            .tasks
+            .iter()
+            .filter(|st| {
+                st.state == TaskState::Pending
+                    && !st.backing_off(now)
+                    && self.deps_completed(st.task.number)
+            })
+            .collect();
+        // Stable sort keeps plan order among equals
+        candidates.sort_by_key(|st| {
+            (
+                std::cmp::Reverse(st.task.priority),
+                std::cmp::Reverse(st.critical_path),
+            )
+        });
+
+        let mut busy: HashSet<&str> = running
+            .iter()
+            .filter_map(|st| st.task.resource.as_deref())
+            .collect();
         let mut ready = Vec::new();
-        for st in &self.tasks {
-            if st.state != TaskState::Pending {
-                continue;
-            }
-            if !self.deps_completed(st.task.number) {
-                continue;
-            }
+        for st in candidates {
             // Non-parallel tasks: only run when nothing else is running
             if !st.task.parallel && running_count + ready.len() > 0 {
                 continue;
             }
+            if let Some(resource) = st.task.resource.as_deref() {
+                if !busy.insert(resource) {
+                    continue;
+                }
+            }
             ready.push(&st.task);
             if ready.len() >= available_slots {
                 break;
@@ -111,27 +177,127 @@ impl TaskScheduler {
         }
     }
 
+    pub fn mark_awaiting_approval(&mut self, task_number: usize) {
+        if let Some(st) = self.find_mut(task_number) {
+            st.state = TaskState::AwaitingApproval;
+        }
+    }
+
     pub fn mark_failed(&mut self, task_number: usize) {
         if let Some(st) = self.find_mut(task_number) {
             st.state = TaskState::Failed;
         }
     }
 
-    /// Re-queue a failed task as pending for retry.
-    /// Returns `false` if the task has exceeded its retry limit.
-    pub fn requeue(&mut self, task_number: usize) -> bool {
+    pub fn mark_conflict(&mut self, task_number: usize) {
+        if let Some(st) = self.find_mut(task_number) {
+            st.state = TaskState::Conflict;
+        }
+    }
+
+    pub fn mark_skipped(&mut self, task_number: usize) {
         if let Some(st) = self.find_mut(task_number) {
-            if st.retries >= MAX_RETRIES {
+            st.state = TaskState::Skipped;
+        }
+    }
+
+    /// Re-queue a failed task as pending for retry, after the policy's
+    /// backoff. Returns `false` if the task has used all its attempts.
+    pub fn requeue(&mut self, task_number: usize, policy: &RetryPolicy) -> bool {
+        if let Some(st) = self.find_mut(task_number) {
+            if st.retries + 1 >= policy.max_attempts() {
                 return false;
             }
             st.retries += 1;
             st.state = TaskState::Pending;
+            st.delay(policy.backoff(st.retries));
+            return true;
+        }
+        false
+    }
+
+    /// Re-queue a task that hit a transient error without using one of its
+    /// attempts. The delay starts at `base` and doubles each time. Returns
+    /// `false` once the task has had `MAX_TRANSIENT_RETRIES` of these.
+    pub fn requeue_transient(&mut self, task_number: usize, base: Duration) -> bool {
+        if let Some(st) = self.find_mut(task_number) {
+            if st.transient_retries >= MAX_TRANSIENT_RETRIES {
+                return false;
+            }
+            st.state = TaskState::Pending;
+            st.delay(base.saturating_mul(1 << st.transient_retries));
+            st.transient_retries += 1;
             return true;
         }
         false
     }
 
-    /// Return the number of times a task has been retried.
+    /// Whether a pending task is waiting out a retry backoff.
+    pub fn has_backing_off(&self) -> bool {
+        let now = Instant::now();
+        self.tasks
+            .iter()
+            .any(|st| st.state == TaskState::Pending && st.backing_off(now))
+    }
+
+    /// Put a failed or conflicted task back in the queue with a fresh retry
+    /// budget. Returns `false` if the task is in any other state.
+    pub fn reset(&mut self, task_number: usize) -> bool {
+        match self.find_mut(task_number) {
+            Some(st) if matches!(st.state, TaskState::Failed | TaskState::Conflict) => {
+                st.state = TaskState::Pending;
+                st.retries = 0;
+                st.transient_retries = 0;
+                st.not_before = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace a failed task's description and queue it again with a fresh
+    /// retry budget. Returns `false` if the task has not failed.
+    pub fn rewrite(&mut self, task_number: usize, body: String) -> bool {
+        match self.find_mut(task_number) {
+            Some(st) if st.state == TaskState::Failed => {
+                st.task.body = body;
+                st.state = TaskState::Pending;
+                st.retries = 0;
+                st.transient_retries = 0;
+                st.not_before = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Make every task that depends on `from` depend on `to` instead.
+    pub fn redirect_dependents(&mut self, from: usize, to: usize) {
+        for st in &mut self.tasks {
+            for dep in &mut st.task.depends_on {
+                if *dep == from {
+                    *dep = to;
+                }
+            }
+        }
+        self.update_critical_paths();
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    pub fn set_max_concurrent(&mut self, max_concurrent: usize) {
+        self.max_concurrent = max_concurrent;
+    }
+
+    /// Every task with its current state, in plan order.
+    pub fn tasks(&self) -> impl Iterator<Item = (&StructuredTask, TaskState)> {
+        self.tasks.iter().map(|st| (&st.task, st.state))
+    }
+
+    /// Return the number of times a task has been retried, not counting
+    /// retries after transient errors.
     pub fn retry_count(&self, task_number: usize) -> usize {
         self.tasks
             .iter()
@@ -141,13 +307,42 @@ impl TaskScheduler {
     }
 
     pub fn all_completed(&self) -> bool {
-        self.tasks.iter().all(|t| t.state == TaskState::Completed)
+        self.tasks.iter().all(|t| t.state.is_done())
     }
 
     pub fn has_failures(&self) -> bool {
         self.tasks.iter().any(|t| t.state == TaskState::Failed)
     }
 
+    /// Add a Work task discovered while the drone is running. Critical-path
+    /// lengths are recomputed, since the new task may extend a chain.
+    pub fn add_task(&mut self, task: StructuredTask) {
+        self.tasks.push(ScheduledTask {
+            task,
+            state: TaskState::Pending,
+            retries: 0,
+            transient_retries: 0,
+            not_before: None,
+            critical_path: 1,
+        });
+        self.update_critical_paths();
+    }
+
+    fn update_critical_paths(&mut self) {
+        let tasks: Vec<StructuredTask> = self.tasks.iter().map(|st| st.task.clone()).collect();
+        let critical_paths = critical_path_lengths(&tasks);
+        for st in &mut self.tasks {
+            st.critical_path = critical_paths.get(&st.task.number).copied().unwrap_or(1);
+        }
+    }
+
+    pub fn state(&self, task_number: usize) -> Option<TaskState> {
+        self.tasks
+            .iter()
+            .find(|st| st.task.number == task_number)
+            .map(|st| st.state)
+    }
+
     pub fn task_count(&self) -> usize {
         self.tasks.len()
     }
@@ -168,7 +363,7 @@ impl TaskScheduler {
             self.tasks
                 .iter()
                 .find(|t| t.task.number == *dep)
-                .map(|t| t.state == TaskState::Completed)
+                .map(|t| t.state.is_done())
                 .unwrap_or(true) // Missing dep = external, assume completed
         })
     }
@@ -178,5 +373,48 @@ impl TaskScheduler {
     }
 }
 
+/// Length of the longest `depends_on` chain starting at each task, counted
+/// in tasks (a task nothing depends on has length 1). Dependency cycles are
+/// cut where they are detected.
+fn critical_path_lengths(tasks: &[StructuredTask]) -> HashMap<usize, usize> {
+    let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+    for task in tasks {
+        for dep in &task.depends_on {
+            dependents.entry(*dep).or_default().push(task.number);
+        }
+    }
+
+    fn visit(
+        number: usize,
+        dependents: &HashMap<usize, Vec<usize>>,
+        lengths: &mut HashMap<usize, usize>,
+        visiting: &mut HashSet<usize>,
+    ) -> usize {
+        if let Some(&len) = lengths.get(&number) {
+            return len;
+        }
+        if !visiting.insert(number) {
+            return 0;
+        }
+        let longest_tail = dependents
+            .get(&number)
+            .into_iter()
+            .flatten()
+            .map(|&next| visit(next, dependents, lengths, visiting))
+            .max()
+            .unwrap_or(0);
+        visiting.remove(&number);
+        lengths.insert(number, longest_tail + 1);
+        longest_tail + 1
+    }
+
+    let mut lengths = HashMap::new();
+    let mut visiting = HashSet::new();
+    for task in tasks {
+        visit(task.number, &dependents, &mut lengths, &mut visiting);
+    }
+    lengths
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::types::{StructuredTask, TaskType};
+use crate::types::{StructuredTask, TaskType, DEFAULT_MAX_ATTEMPTS};
 
 use super::*;
 
@@ -8,12 +8,9 @@ fn make_task(number: usize, depends_on: Vec<usize>, parallel: bool) -> Structure
     StructuredTask {
         number,
         title: format!("Task {number}"),
-        body: String::new(),
-        task_type: TaskType::Work,
-        model: None,
         parallel,
-        files: Vec::new(),
         depends_on,
+        ..Default::default()
     }
 }
 
@@ -66,6 +63,23 @@ fn test_max_concurrent_respected() {
     assert_eq!(ready.len(), 2);
 }
 
+#[test]
+fn test_awaiting_approval_frees_slot_but_blocks_dependents() {
+    let tasks = vec![
+        make_task(1, vec![], true),
+        make_task(2, vec![1], true),
+        make_task(3, vec![], true),
+    ];
+    let mut scheduler = TaskScheduler::new(tasks, 1, &HashSet::new());
+    scheduler.mark_running(1);
+    assert!(scheduler.ready_tasks().is_empty());
+
+    scheduler.mark_awaiting_approval(1);
+    let ready: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(ready, vec![3]);
+    assert!(!scheduler.all_completed());
+}
+
 #[test]
 fn test_non_parallel_waits_for_empty() {
     let tasks = vec![
@@ -122,7 +136,7 @@ fn test_requeue_failed_task() {
     scheduler.mark_failed(1);
     assert!(scheduler.has_failures());
 
-    assert!(scheduler.requeue(1));
+    assert!(scheduler.requeue(1, &RetryPolicy::default()));
     let ready = scheduler.ready_tasks();
     assert_eq!(ready.len(), 1);
 }
@@ -133,16 +147,16 @@ fn test_requeue_respects_max_retries() {
     let mut scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
 
     // First attempt + 2 retries = 3 total attempts
-    for _ in 0..MAX_RETRIES {
+    for _ in 0..DEFAULT_MAX_ATTEMPTS - 1 {
         scheduler.mark_running(1);
         scheduler.mark_failed(1);
-        assert!(scheduler.requeue(1));
+        assert!(scheduler.requeue(1, &RetryPolicy::default()));
     }
 
     // Third failure should NOT requeue
     scheduler.mark_running(1);
     scheduler.mark_failed(1);
-    assert!(!scheduler.requeue(1));
+    assert!(!scheduler.requeue(1, &RetryPolicy::default()));
     assert!(scheduler.has_failures());
     assert!(scheduler.ready_tasks().is_empty());
 }
@@ -183,14 +197,220 @@ fn test_retry_count() {
 
     scheduler.mark_running(1);
     scheduler.mark_failed(1);
-    scheduler.requeue(1);
+    scheduler.requeue(1, &RetryPolicy::default());
     assert_eq!(scheduler.retry_count(1), 1);
 
     scheduler.mark_running(1);
     scheduler.mark_failed(1);
-    scheduler.requeue(1);
+    scheduler.requeue(1, &RetryPolicy::default());
     assert_eq!(scheduler.retry_count(1), 2);
 
     // Non-existent task returns 0
     assert_eq!(scheduler.retry_count(999), 0);
 }
+
+#[test]
+fn test_conflict_blocks_dependents_until_requeued() {
+    let tasks = vec![make_task(1, vec![], true), make_task(2, vec![1], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
+
+    scheduler.mark_running(1);
+    scheduler.mark_conflict(1);
+    assert_eq!(scheduler.state(1), Some(TaskState::Conflict));
+    assert!(!scheduler.has_failures());
+    assert!(scheduler.ready_tasks().is_empty());
+
+    assert!(scheduler.requeue(1, &RetryPolicy::default()));
+    let ready = scheduler.ready_tasks();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].number, 1);
+}
+
+#[test]
+fn test_ready_tasks_prefer_longest_chain() {
+    // 1 and 2 are independent; 2 heads the chain 2 → 3 → 4
+    let tasks = vec![
+        make_task(1, vec![], true),
+        make_task(2, vec![], true),
+        make_task(3, vec![2], true),
+        make_task(4, vec![3], true),
+    ];
+    let scheduler = TaskScheduler::new(tasks, 1, &HashSet::new());
+    let ready = scheduler.ready_tasks();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].number, 2);
+}
+
+#[test]
+fn test_priority_overrides_critical_path() {
+    let mut urgent = make_task(1, vec![], true);
+    urgent.priority = 5;
+    let tasks = vec![
+        urgent,
+        make_task(2, vec![], true),
+        make_task(3, vec![2], true),
+    ];
+    let scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
+    let order: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(order, vec![1, 2]);
+}
+
+#[test]
+fn test_shared_resource_never_overlaps() {
+    let with_resource = |number: usize, resource: &str| {
+        let mut task = make_task(number, vec![], true);
+        task.resource = Some(resource.to_string());
+        task
+    };
+    let tasks = vec![
+        with_resource(1, "db"),
+        with_resource(2, "db"),
+        make_task(3, vec![], true),
+        with_resource(4, "cache"),
+    ];
+    let mut scheduler = TaskScheduler::new(tasks, 4, &HashSet::new());
+    let order: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(order, vec![1, 3, 4]);
+
+    scheduler.mark_running(1);
+    assert!(scheduler.ready_tasks().iter().all(|t| t.number != 2));
+
+    scheduler.mark_completed(1);
+    let order: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(order, vec![2, 3, 4]);
+}
+
+#[test]
+fn test_critical_path_survives_cycles() {
+    let tasks = vec![make_task(1, vec![2], true), make_task(2, vec![1], true)];
+    let lengths = critical_path_lengths(&tasks);
+    assert_eq!(lengths.len(), 2);
+}
+
+#[test]
+fn test_added_task_waits_for_deps_and_extends_critical_path() {
+    let tasks = vec![make_task(1, vec![], true), make_task(2, vec![], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
+    scheduler.mark_running(1);
+    scheduler.mark_running(2);
+    scheduler.add_task(make_task(3, vec![2], true));
+    assert_eq!(scheduler.task_count(), 3);
+    assert!(scheduler.ready_tasks().is_empty());
+
+    scheduler.mark_completed(1);
+    scheduler.mark_completed(2);
+    assert!(!scheduler.all_completed());
+    let order: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(order, vec![3]);
+
+    let mut scheduler = TaskScheduler::new(
+        vec![make_task(1, vec![], true), make_task(2, vec![], true)],
+        1,
+        &HashSet::new(),
+    );
+    scheduler.add_task(make_task(3, vec![2], true));
+    let order: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(order, vec![2]);
+}
+
+#[test]
+fn test_reset_requeues_only_failed_tasks() {
+    let tasks = vec![make_task(1, vec![], true), make_task(2, vec![], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 2, &HashSet::new());
+    scheduler.mark_running(1);
+    for _ in 0..DEFAULT_MAX_ATTEMPTS - 1 {
+        scheduler.mark_failed(2);
+        assert!(scheduler.requeue(2, &RetryPolicy::default()));
+    }
+    scheduler.mark_failed(2);
+    assert!(!scheduler.requeue(2, &RetryPolicy::default()));
+
+    assert!(!scheduler.reset(1));
+    assert!(scheduler.reset(2));
+    assert_eq!(scheduler.state(2), Some(TaskState::Pending));
+    assert_eq!(scheduler.retry_count(2), 0);
+}
+
+#[test]
+fn test_skipped_task_unblocks_dependents() {
+    let tasks = vec![make_task(1, vec![], true), make_task(2, vec![1], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 2, &HashSet::new());
+    scheduler.mark_failed(1);
+    assert!(scheduler.ready_tasks().is_empty());
+
+    scheduler.mark_skipped(1);
+    let ready: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(ready, vec![2]);
+    scheduler.mark_completed(2);
+    assert!(scheduler.all_completed());
+    assert!(!scheduler.has_failures());
+}
+
+#[test]
+fn test_triage_rewrite_and_split() {
+    let tasks = vec![make_task(1, vec![], true), make_task(2, vec![1], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 2, &HashSet::new());
+    assert!(!scheduler.rewrite(1, "New body".to_string()));
+    scheduler.mark_failed(1);
+    assert!(scheduler.rewrite(1, "New body".to_string()));
+    assert_eq!(scheduler.get_task(1).unwrap().body, "New body");
+    assert_eq!(scheduler.state(1), Some(TaskState::Pending));
+
+    // Split task 1 into 3 -> 4; task 2 now waits for 4
+    scheduler.mark_failed(1);
+    scheduler.add_task(make_task(3, vec![], true));
+    scheduler.add_task(make_task(4, vec![3], true));
+    scheduler.redirect_dependents(1, 4);
+    scheduler.mark_skipped(1);
+    assert_eq!(scheduler.get_task(2).unwrap().depends_on, vec![4]);
+    let ready: Vec<usize> = scheduler.ready_tasks().iter().map(|t| t.number).collect();
+    assert_eq!(ready, vec![3]);
+}
+
+#[test]
+fn test_requeue_follows_policy_attempts_and_backoff() {
+    let tasks = vec![make_task(1, vec![], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
+    let policy = RetryPolicy {
+        max_attempts: Some(2),
+        backoff_secs: Some(60),
+        ..Default::default()
+    };
+
+    scheduler.mark_failed(1);
+    assert!(scheduler.requeue(1, &policy));
+    // Pending, but held back until the backoff has passed
+    assert_eq!(scheduler.state(1), Some(TaskState::Pending));
+    assert!(scheduler.ready_tasks().is_empty());
+    assert!(scheduler.has_backing_off());
+
+    scheduler.mark_failed(1);
+    assert!(!scheduler.requeue(1, &policy));
+
+    let single = RetryPolicy {
+        max_attempts: Some(1),
+        ..Default::default()
+    };
+    assert!(scheduler.reset(1));
+    assert_eq!(scheduler.ready_tasks().len(), 1);
+    scheduler.mark_failed(1);
+    assert!(!scheduler.requeue(1, &single));
+}
+
+#[test]
+fn test_transient_requeue_keeps_attempts() {
+    let tasks = vec![make_task(1, vec![], true)];
+    let mut scheduler = TaskScheduler::new(tasks, 3, &HashSet::new());
+
+    for _ in 0..MAX_TRANSIENT_RETRIES {
+        scheduler.mark_failed(1);
+        assert!(scheduler.requeue_transient(1, Duration::ZERO));
+        assert_eq!(scheduler.ready_tasks().len(), 1);
+    }
+    assert_eq!(scheduler.retry_count(1), 0);
+
+    scheduler.mark_failed(1);
+    assert!(!scheduler.requeue_transient(1, Duration::ZERO));
+    // The task's own attempts are still there
+    assert!(scheduler.requeue(1, &RetryPolicy::default()));
+}
@@ -0,0 +1,237 @@
+//! Failure triage for tasks that have used up their retries.
+//!
+//! A triage agent reads the task, the worker's error and its last transcript,
+//! then ends its reply with a `TRIAGE_DECISION` JSON object. The coordinator
+//! applies the decision: split the task, rewrite it, skip it, or let it fail.
+
+use serde::Deserialize;
+
+use crate::commands::common::truncate_with_ellipsis;
+use crate::webui::anthropic::types::{ContentBlock, Message, MessageContent};
+
+/// Marker the triage agent puts before its decision.
+pub const TRIAGE_SIGNAL: &str = "TRIAGE_DECISION";
+
+/// Longest transcript tail shown to the triage agent.
+const MAX_TRANSCRIPT_CHARS: usize = 12_000;
+
+/// Longest tool input or result kept per transcript entry.
+const MAX_ENTRY_CHARS: usize = 600;
+
+/// Why a task's latest attempt failed.
+#[derive(Debug, Clone, Default)]
+pub struct TaskFailure {
+    pub error: String,
+    pub transcript: Vec<Message>,
+}
+
+/// What the triage agent decided to do with a failed task.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TriageDecision {
+    /// Replace the task with smaller tasks, run one after another
+    Split {
+        tasks: Vec<SplitTask>,
+        reason: String,
+    },
+    /// Retry the task with a new description
+    Rewrite { description: String, reason: String },
+    /// Give up on the task; its dependents and the PR go ahead without it
+    Skip { reason: String },
+    /// Leave the task failed
+    Fail { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitTask {
+    pub title: String,
+    pub description: String,
+}
+
+impl TriageDecision {
+    pub fn action(&self) -> &'static str {
+        match self {
+            TriageDecision::Split { .. } => "split",
+            TriageDecision::Rewrite { .. } => "rewrite",
+            TriageDecision::Skip { .. } => "skip",
+            TriageDecision::Fail { .. } => "fail",
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        match self {
+            TriageDecision::Split { reason, .. }
+            | TriageDecision::Rewrite { reason, .. }
+            | TriageDecision::Skip { reason }
+            | TriageDecision::Fail { reason } => reason,
+        }
+    }
+}
+
+/// Read the decision that follows `TRIAGE_DECISION` in the agent's reply.
+/// Returns `None` if it is missing, malformed, or has nothing to act on.
+pub fn parse_decision(text: &str) -> Option<TriageDecision> {
+    let after = &text[text.rfind(TRIAGE_SIGNAL)? + TRIAGE_SIGNAL.len()..];
+    let json = &after[after.find('{')?..=after.rfind('}')?];
+    let decision: TriageDecision = serde_json::from_str(json).ok()?;
+    let usable = match &decision {
+        TriageDecision::Split { tasks, .. } => {
+            !tasks.is_empty() && tasks.iter().all(|t| !t.title.trim().is_empty())
+        }
+        TriageDecision::Rewrite { description, .. } => !description.trim().is_empty(),
+        TriageDecision::Skip { reason } => !reason.trim().is_empty(),
+        TriageDecision::Fail { .. } => true,
+    };
+    usable.then_some(decision)
+}
+
+/// Render the end of a worker's conversation as plain text: assistant text,
+/// tool calls and (truncated) tool results.
+pub fn render_transcript(messages: &[Message]) -> String {
+    let mut entries = Vec::new();
+    for msg in messages {
+        match &msg.content {
+            MessageContent::Text(text) => entries.push(format!("[{}] {}", msg.role, text.trim())),
+            MessageContent::Blocks(blocks) => {
+                for block in blocks {
+                    match block {
+                        ContentBlock::Text { text } if !text.trim().is_empty() => {
+                            entries.push(format!("[{}] {}", msg.role, text.trim()));
+                        }
+                        ContentBlock::ToolUse { name, input, .. } => entries.push(format!(
+                            "[tool call] {name} {}",
+                            truncate_with_ellipsis(&input.to_string(), MAX_ENTRY_CHARS)
+                        )),
+                        ContentBlock::ToolResult {
+                            content, is_error, ..
+                        } => {
+                            let label = if *is_error == Some(true) {
+                                "tool error"
+                            } else {
+                                "tool result"
+                            };
+                            entries.push(format!(
+                                "[{label}] {}",
+                                truncate_with_ellipsis(content.trim(), MAX_ENTRY_CHARS)
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    // Keep the most recent entries that fit
+    let mut kept = Vec::new();
+    let mut len = 0;
+    for entry in entries.iter().rev() {
+        // Entries are joined with a blank line
+        len += entry.len() + 2;
+        if len > MAX_TRANSCRIPT_CHARS {
+            kept.push("[earlier messages omitted]".to_string());
+            break;
+        }
+        kept.push(entry.clone());
+    }
+    kept.reverse();
+    kept.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decision_variants() {
+        let split = parse_decision(
+            "The task mixes a migration and an API change.\n\nTRIAGE_DECISION\n```json\n\
+             {\"action\": \"split\", \"reason\": \"too broad\", \"tasks\": [\
+             {\"title\": \"Add migration\", \"description\": \"Create the table\"},\
+             {\"title\": \"Wire the API\", \"description\": \"Use it\"}]}\n```",
+        )
+        .unwrap();
+        assert_eq!(split.action(), "split");
+        let TriageDecision::Split { tasks, reason } = split else {
+            unreachable!()
+        };
+        assert_eq!(reason, "too broad");
+        assert_eq!(tasks[1].title, "Wire the API");
+
+        let skip = parse_decision(
+            "TRIAGE_DECISION {\"action\": \"skip\", \"reason\": \"Optional docs polish\"}",
+        );
+        assert_eq!(
+            skip,
+            Some(TriageDecision::Skip {
+                reason: "Optional docs polish".to_string()
+            })
+        );
+
+        let rewrite = parse_decision(
+            "TRIAGE_DECISION {\"action\": \"rewrite\", \"reason\": \"r\", \"description\": \"Use sqlx\"}",
+        )
+        .unwrap();
+        assert_eq!(rewrite.reason(), "r");
+    }
+
+    #[test]
+    fn test_parse_decision_rejects_unusable_replies() {
+        assert_eq!(parse_decision("I think it should be skipped."), None);
+        assert_eq!(parse_decision("TRIAGE_DECISION {not json}"), None);
+        assert_eq!(
+            parse_decision(
+                "TRIAGE_DECISION {\"action\": \"split\", \"reason\": \"r\", \"tasks\": []}"
+            ),
+            None
+        );
+        assert_eq!(
+            parse_decision("TRIAGE_DECISION {\"action\": \"skip\", \"reason\": \" \"}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_render_transcript_keeps_the_latest_entries() {
+        let messages = vec![
+            Message {
+                role: "user".to_string(),
+                content: MessageContent::Text("Complete this task".to_string()),
+            },
+            Message {
+                role: "assistant".to_string(),
+                content: MessageContent::Blocks(vec![
+                    ContentBlock::Text {
+                        text: "Running the tests".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "t1".to_string(),
+                        name: "Bash".to_string(),
+                        input: serde_json::json!({"command": "cargo test"}),
+                    },
+                ]),
+            },
+            Message {
+                role: "user".to_string(),
+                content: MessageContent::Blocks(vec![ContentBlock::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    content: "x".repeat(MAX_TRANSCRIPT_CHARS),
+                    is_error: Some(true),
+                }]),
+            },
+        ];
+
+        let rendered = render_transcript(&messages);
+        assert!(rendered.contains("[tool call] Bash {\"command\":\"cargo test\"}"));
+        assert!(rendered.contains("[tool error] xxx"));
+        assert!(rendered.contains("[assistant] Running the tests"));
+
+        let long = vec![messages[0].clone(); 2]
+            .into_iter()
+            .chain(std::iter::repeat_n(messages[1].clone(), 200))
+            .collect::<Vec<_>>();
+        let rendered = render_transcript(&long);
+        assert!(rendered.starts_with("[earlier messages omitted]"));
+        assert!(rendered.len() <= MAX_TRANSCRIPT_CHARS + 100);
+    }
+}
@@ -1,23 +1,32 @@
-use crate::webui::anthropic::types::{ContentBlock, Message, MessageContent};
+use std::sync::Arc;
+
+use crate::webui::anthropic::types::{ContentBlock, Message, MessageContent, UsageStats};
+use crate::webui::chat::handlers::agentic::UsageSink;
 
 use super::super::events::EventEmitter;
 use super::WorkerConfig;
 
-/// Emit accumulated cost from the session store to cost.ndjson.
-pub async fn emit_cost_from_store(
-    store: &crate::webui::chat::session::SessionStore,
-    session_id: &str,
-    emitter: &EventEmitter,
-) {
-    let sessions = store.lock().await;
-    if let Some(s) = sessions.get(session_id) {
-        let usage = crate::webui::anthropic::types::UsageStats {
-            input_tokens: s.total_input_tokens,
-            output_tokens: s.total_output_tokens,
-            cache_creation_input_tokens: 0,
-            cache_read_input_tokens: 0,
-        };
-        emitter.emit_cost(&usage);
+/// Writes one cost.ndjson record per API call made by a worker or phase agent.
+pub struct CostRecorder {
+    emitter: Arc<EventEmitter>,
+    worker: String,
+    task: Option<String>,
+}
+
+impl CostRecorder {
+    pub fn new(emitter: Arc<EventEmitter>, worker: &str, task: Option<&str>) -> Self {
+        Self {
+            emitter,
+            worker: worker.to_string(),
+            task: task.map(str::to_string),
+        }
+    }
+}
+
+impl UsageSink for CostRecorder {
+    fn record(&self, model: &str, usage: &UsageStats) {
+        self.emitter
+            .emit_cost(model, &self.worker, self.task.as_deref(), usage);
     }
 }
 
@@ -55,5 +64,14 @@ pub fn spawn_config_ref(config: &WorkerConfig) -> crate::backend::SpawnConfig {
         remote_url: String::new(),
         mode: String::new(),
         project_languages: config.project_languages.clone(),
+        budget: Default::default(),
+        quality_gates: config.quality_gates.clone(),
+        max_proposed_tasks: 0,
+        triage: false,
+        retry: Default::default(),
+        task_limits: config.limits,
+        sandbox: config.sandbox.clone(),
+        git_common_dir: config.git_common_dir.clone(),
+        persistent_shell: config.persistent_shell,
     }
 }
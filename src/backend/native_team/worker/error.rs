@@ -0,0 +1,139 @@
+use crate::types::ErrorClass;
+use crate::webui::provider::ApiError;
+
+/// Why a worker failed, classified so the coordinator can pick a retry policy.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkerError {
+    /// Rate limit, overloaded or 5xx API response, or a network failure
+    Transient(String),
+    /// Missing, expired or rejected credentials
+    Credentials(String),
+    /// The API rejected the request for another reason
+    Api(String),
+    /// Stopped by the operator, a budget or the drone shutting down
+    Aborted,
+    /// Completion checks were still failing after the last iteration
+    GatesFailed(String),
+    /// The model reported the task as blocked
+    Blocked(String),
+    /// Ran past the task's timeout or turn budget
+    TimedOut(String),
+    /// A panic or other internal error
+    Internal(String),
+}
+
+impl WorkerError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            WorkerError::Transient(_) => ErrorClass::Transient,
+            WorkerError::Credentials(_) => ErrorClass::Credentials,
+            WorkerError::Api(_) => ErrorClass::Api,
+            WorkerError::Aborted => ErrorClass::Aborted,
+            WorkerError::GatesFailed(_) => ErrorClass::Gates,
+            WorkerError::Blocked(_) => ErrorClass::Blocked,
+            WorkerError::TimedOut(_) => ErrorClass::Timeout,
+            WorkerError::Internal(_) => ErrorClass::Internal,
+        }
+    }
+
+    /// Classify an error returned by the agentic loop.
+    pub fn from_loop_error(e: &anyhow::Error) -> WorkerError {
+        let message = format!("{e:#}");
+        if let Some(api) = e.chain().find_map(|c| c.downcast_ref::<ApiError>()) {
+            return match api.status {
+                401 | 403 => WorkerError::Credentials(message),
+                408 | 429 | 500..=599 => WorkerError::Transient(message),
+                _ => WorkerError::Api(message),
+            };
+        }
+        if e.chain().any(|c| c.is::<reqwest::Error>()) {
+            return WorkerError::Transient(message);
+        }
+        if message == "Aborted" {
+            return WorkerError::Aborted;
+        }
+        let credentials = [
+            "OAuth token refresh failed",
+            "AWS SSO session expired",
+            "AWS credentials error",
+        ];
+        if credentials.iter().any(|c| message.contains(c)) {
+            return WorkerError::Credentials(message);
+        }
+        WorkerError::Internal(message)
+    }
+}
+
+impl std::fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerError::Aborted => write!(f, "Aborted"),
+            WorkerError::GatesFailed(report) => {
+                write!(f, "Completion checks still failing:\n\n{report}")
+            }
+            WorkerError::Transient(msg)
+            | WorkerError::Credentials(msg)
+            | WorkerError::Api(msg)
+            | WorkerError::Blocked(msg)
+            | WorkerError::TimedOut(msg)
+            | WorkerError::Internal(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(status: u16) -> anyhow::Error {
+        anyhow::Error::new(ApiError {
+            status,
+            message: format!("status {status}"),
+        })
+    }
+
+    #[test]
+    fn test_classifies_api_statuses() {
+        assert_eq!(
+            WorkerError::from_loop_error(&api(529)).class(),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            WorkerError::from_loop_error(&api(429)).class(),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            WorkerError::from_loop_error(&api(401)).class(),
+            ErrorClass::Credentials
+        );
+        assert_eq!(
+            WorkerError::from_loop_error(&api(400)).class(),
+            ErrorClass::Api
+        );
+        // Context added on the way up does not hide the status
+        let wrapped = api(503).context("Agentic loop failed");
+        assert_eq!(
+            WorkerError::from_loop_error(&wrapped).class(),
+            ErrorClass::Transient
+        );
+    }
+
+    #[test]
+    fn test_classifies_other_errors_by_message() {
+        assert_eq!(
+            WorkerError::from_loop_error(&anyhow::anyhow!("Aborted")),
+            WorkerError::Aborted
+        );
+        assert_eq!(
+            WorkerError::from_loop_error(&anyhow::anyhow!(
+                "OAuth token refresh failed (400): invalid_grant"
+            ))
+            .class(),
+            ErrorClass::Credentials
+        );
+        assert_eq!(
+            WorkerError::from_loop_error(&anyhow::anyhow!("tool dispatch broke")).class(),
+            ErrorClass::Internal
+        );
+    }
+}
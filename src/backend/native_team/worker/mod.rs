@@ -1,5 +1,6 @@
 mod completion;
 mod emit;
+mod error;
 
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -9,38 +10,60 @@ use anyhow::Result;
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
 
-use crate::types::StructuredTask;
+use crate::types::{QualityGateSpec, SandboxConfig, StructuredTask, TaskLimits};
 use crate::webui::anthropic::types::{Message, MessageContent};
 use crate::webui::auth::credentials::Credentials;
-use crate::webui::chat::handlers::agentic::{run_agentic_loop, AgenticLoopParams};
+use crate::webui::chat::handlers::agentic::{
+    run_agentic_loop, AgenticLoopParams, MessageInbox, UsageSink,
+};
 use crate::webui::chat::session::{Effort, SessionStore};
 use crate::webui::provider;
 use crate::webui::tools::definitions::builtin_tool_definitions;
+use crate::webui::tools::permissions::{Permissions, TrustLevel};
+use crate::webui::tools::propose_task::propose_task_definition;
+use crate::webui::tools::shell::add_persistent_shell_tools;
+use crate::webui::tools::{
+    PersistentShell, ProcessRegistry, ProcessSandbox, TaskProposer, WritePolicy,
+};
 
+use super::control::WorkerInbox;
 use super::events::EventEmitter;
-use super::file_ownership::ownership_prompt_for_files;
-use super::prompts::{build_continuation_prompt, build_worker_prompt};
-use super::quality_gate::{self, GateResult};
+use super::file_ownership::{ownership_prompt_for_files, WorkerWritePolicy};
+use super::paused_workers::{self, PausedWorker};
+use super::prompts::{build_continuation_prompt, build_worker_prompt, PROPOSE_TASK_RULE};
+use super::proposals::{TaskProposals, WorkerProposer};
+use super::quality_gate::{self, GateRun};
 use super::worker_notes::{self, WorkerNote};
 
 pub use completion::{check_completion, extract_progress_summary};
-pub use emit::{emit_cost_from_store, emit_tool_events, spawn_config_ref};
+pub use emit::{emit_tool_events, spawn_config_ref, CostRecorder};
+pub use error::WorkerError;
 
 const MAX_ITERATIONS: usize = 10;
 
+/// Agentic turns per iteration, before the worker is re-prompted with its
+/// progress.
+const TURNS_PER_ITERATION: usize = 25;
+
 /// Handle returned when a worker is spawned.
 pub struct WorkerHandle {
     pub task_number: usize,
     pub worker_name: String,
     pub join_handle: JoinHandle<Result<WorkerResult>>,
     pub abort_flag: Arc<AtomicBool>,
+    /// Operator messages delivered to the worker between tool calls
+    pub inbox: Arc<WorkerInbox>,
 }
 
 /// Result of a worker completing its task.
 pub struct WorkerResult {
     pub task_number: usize,
     pub success: bool,
-    pub error: Option<String>,
+    pub error: Option<WorkerError>,
+    /// Stopped by `hive pause`; the conversation was saved for resume
+    pub paused: bool,
+    /// The last finished agentic loop's messages, handed to failure triage
+    pub transcript: Vec<Message>,
 }
 
 /// Configuration for spawning a worker.
@@ -57,6 +80,22 @@ pub struct WorkerConfig {
     pub session_store: SessionStore,
     pub global_abort: Arc<AtomicBool>,
     pub dependency_notes: Vec<WorkerNote>,
+    pub quality_gates: Vec<QualityGateSpec>,
+    /// Where `ProposeTask` calls are queued; the tool is withheld when `None`
+    pub task_proposals: Option<Arc<TaskProposals>>,
+    /// Drone-wide pause request: stop after the current API turn and save
+    pub pause_flag: Arc<AtomicBool>,
+    /// Conversation saved by an earlier pause, continued instead of restarting
+    pub resume: Option<PausedWorker>,
+    /// Timeout and turn budget for this attempt, already merged with the
+    /// drone's defaults
+    pub limits: TaskLimits,
+    /// Process sandbox for the worker's Bash calls and gates
+    pub sandbox: SandboxConfig,
+    /// The repository's shared git directory, writable in the sandbox
+    pub git_common_dir: Option<PathBuf>,
+    /// Run the worker's Bash calls in one long-lived shell
+    pub persistent_shell: bool,
 }
 
 /// Spawn a worker agent for a single task.
@@ -65,47 +104,159 @@ pub fn spawn_worker(config: WorkerConfig) -> WorkerHandle {
     let worker_name = config.task.worker_name();
     let abort_flag = Arc::new(AtomicBool::new(false));
     let abort_clone = abort_flag.clone();
+    let inbox = Arc::new(WorkerInbox::default());
+    let inbox_clone = inbox.clone();
 
-    let join_handle = tokio::spawn(async move { run_worker(config, abort_clone).await });
+    let join_handle =
+        tokio::spawn(async move { run_worker(config, abort_clone, inbox_clone).await });
 
     WorkerHandle {
         task_number,
         worker_name,
         join_handle,
         abort_flag,
+        inbox,
     }
 }
 
 /// The worker's main run loop.
-async fn run_worker(config: WorkerConfig, abort_flag: Arc<AtomicBool>) -> Result<WorkerResult> {
+async fn run_worker(
+    config: WorkerConfig,
+    abort_flag: Arc<AtomicBool>,
+    inbox: Arc<WorkerInbox>,
+) -> Result<WorkerResult> {
     let task_number = config.task.number;
     let model_id = provider::resolve_model(&config.model, &config.creds);
     let worker_name = config.task.worker_name();
 
+    let task_proposer: Option<Arc<dyn TaskProposer>> = config
+        .task_proposals
+        .clone()
+        .map(|p| Arc::new(WorkerProposer::new(p, task_number)) as Arc<dyn TaskProposer>);
+
     let ownership_hint = ownership_prompt_for_files(&config.task.files);
-    let system_prompt = build_worker_prompt(
+    let mut system_prompt = build_worker_prompt(
         &config.task,
         &spawn_config_ref(&config),
         &ownership_hint,
         &config.dependency_notes,
     );
-    let tools = builtin_tool_definitions();
+    let mut tools = builtin_tool_definitions();
+    if task_proposer.is_some() {
+        system_prompt.push_str(PROPOSE_TASK_RULE);
+        tools.push(propose_task_definition());
+    }
     let (tx, _rx) = broadcast::channel::<String>(256);
-    let gate_config = quality_gate::build_gate_config(&config.project_languages, &config.cwd);
+    let sandbox = ProcessSandbox::new(
+        &config.sandbox,
+        &config.cwd,
+        config.git_common_dir.as_deref(),
+    )
+    .map(Arc::new);
+    // Killed when the worker exits and this is dropped
+    let shell = config
+        .persistent_shell
+        .then(|| Arc::new(PersistentShell::new(&config.cwd, sandbox.clone())));
+    if shell.is_some() {
+        add_persistent_shell_tools(&mut tools);
+    }
+    // Background processes live as long as the worker, across iterations
+    let processes = Arc::new(ProcessRegistry::default());
+    // The task's own acceptance checks run first, then the shared gates
+    let mut gate_configs =
+        quality_gate::build_acceptance_configs(&config.task.verify, &config.cwd, sandbox.as_ref());
+    gate_configs.extend(quality_gate::build_gate_configs(
+        &config.quality_gates,
+        &config.project_languages,
+        &config.cwd,
+        &config.task.files,
+        sandbox.as_ref(),
+    ));
     let drone_dir = PathBuf::from(".hive/drones").join(&config.drone_name);
+    let write_policy: Option<Arc<dyn WritePolicy>> = if config.task.files.is_empty() {
+        None
+    } else {
+        Some(Arc::new(WorkerWritePolicy::new(
+            &config.task.files,
+            &config.cwd,
+            &task_number.to_string(),
+            &worker_name,
+            config.emitter.clone(),
+        )))
+    };
 
-    let mut continuation_context: Option<String> = None;
+    let permissions = Arc::new(Permissions::load(TrustLevel::Drone, &config.cwd));
 
-    for iteration in 0..MAX_ITERATIONS {
+    let cost_recorder: Arc<dyn UsageSink> = Arc::new(CostRecorder::new(
+        config.emitter.clone(),
+        &worker_name,
+        Some(&task_number.to_string()),
+    ));
+
+    let (first_iteration, mut resumed_messages, mut continuation_context) = match &config.resume {
+        Some(saved) => (
+            saved.iteration,
+            saved.messages.clone(),
+            saved.continuation.clone(),
+        ),
+        None => (0, Vec::new(), None),
+    };
+    let mut transcript: Vec<Message> = Vec::new();
+    // Report from the last completion checks, while they are still failing
+    let mut gate_failures: Option<String> = None;
+    let deadline = config
+        .limits
+        .timeout()
+        .map(|timeout| tokio::time::Instant::now() + timeout);
+    let max_turns = config.limits.max_turns();
+    let mut turns = 0;
+
+    for iteration in first_iteration..MAX_ITERATIONS {
         if abort_flag.load(Ordering::Relaxed) || config.global_abort.load(Ordering::Relaxed) {
             return Ok(WorkerResult {
                 task_number,
                 success: false,
-                error: Some("Aborted".to_string()),
+                error: Some(WorkerError::Aborted),
+                paused: false,
+                transcript,
+            });
+        }
+
+        let over_limit = if deadline.is_some_and(|d| tokio::time::Instant::now() >= d) {
+            Some(timeout_message(&config.limits))
+        } else if turns >= max_turns {
+            Some(format!(
+                "Used all {max_turns} agentic turns without finishing"
+            ))
+        } else {
+            None
+        };
+        if let Some(message) = over_limit {
+            return Ok(WorkerResult {
+                task_number,
+                success: false,
+                error: Some(WorkerError::TimedOut(message)),
+                paused: false,
+                transcript,
             });
         }
 
-        let messages = build_messages(iteration, &config.task, continuation_context.as_deref());
+        if config.pause_flag.load(Ordering::Relaxed) {
+            let state = PausedWorker {
+                task_number,
+                iteration,
+                messages: Vec::new(),
+                continuation: continuation_context,
+            };
+            return pause_worker(&drone_dir, &state);
+        }
+
+        let messages = if resumed_messages.is_empty() {
+            build_messages(iteration, &config.task, continuation_context.as_deref())
+        } else {
+            std::mem::take(&mut resumed_messages)
+        };
+        let sent = messages.len();
 
         let params = AgenticLoopParams {
             creds: &config.creds,
@@ -119,41 +270,99 @@ async fn run_worker(config: WorkerConfig, abort_flag: Arc<AtomicBool>) -> Result
             abort_flag: &abort_flag,
             store: config.session_store.clone(),
             effort: Effort::High,
-            max_turns: Some(25),
+            max_turns: Some(TURNS_PER_ITERATION.min(max_turns - turns)),
             mcp_pool: None,
             deferred_tools_active: false,
+            permissions: Some(permissions.clone()),
+            write_policy: write_policy.clone(),
+            sandbox: sandbox.clone(),
+            shell: shell.clone(),
+            processes: Some(processes.clone()),
+            prompt_caching: true,
+            usage_sink: Some(cost_recorder.clone()),
+            task_proposer: task_proposer.clone(),
+            inbox: Some(inbox.clone() as Arc<dyn MessageInbox>),
+            pause_flag: Some(&config.pause_flag),
         };
 
-        let result_messages = run_agentic_loop(params).await?;
+        // A stuck loop is dropped at the deadline rather than left holding a slot
+        let outcome = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, run_agentic_loop(params)).await,
+            None => Ok(run_agentic_loop(params).await),
+        };
+        let Ok(outcome) = outcome else {
+            return Ok(WorkerResult {
+                task_number,
+                success: false,
+                error: Some(WorkerError::TimedOut(timeout_message(&config.limits))),
+                paused: false,
+                transcript,
+            });
+        };
+        let result_messages = match outcome {
+            Ok(messages) => messages,
+            Err(e) => {
+                return Ok(WorkerResult {
+                    task_number,
+                    success: false,
+                    error: Some(WorkerError::from_loop_error(&e)),
+                    paused: false,
+                    transcript,
+                })
+            }
+        };
+
+        emit_tool_events(
+            &config.emitter,
+            &result_messages[sent.min(result_messages.len())..],
+        );
+        transcript = result_messages.clone();
+        turns += result_messages[sent.min(result_messages.len())..]
+            .iter()
+            .filter(|m| m.role == "assistant")
+            .count();
 
-        emit_cost_from_store(&config.session_store, &worker_name, &config.emitter).await;
-        emit_tool_events(&config.emitter, &result_messages);
+        // Paused between turns: the history ends with tool results the model
+        // has not answered yet, so it can be continued as-is
+        if config.pause_flag.load(Ordering::Relaxed)
+            && result_messages.last().is_some_and(|m| m.role == "user")
+        {
+            let state = PausedWorker {
+                task_number,
+                iteration,
+                messages: result_messages,
+                continuation: continuation_context,
+            };
+            return pause_worker(&drone_dir, &state);
+        }
+
+        // Messages that arrived after the last tool call start the next iteration
+        let unread = inbox.take();
+        if !unread.is_empty() {
+            continuation_context = Some(format!(
+                "{}\n\n## Messages from the operator\n\n{}",
+                extract_progress_summary(&result_messages),
+                unread.join("\n\n")
+            ));
+            continue;
+        }
 
         let (complete, blocked_reason) = check_completion(&result_messages);
 
         if complete {
-            // Run quality gate before accepting completion
-            if let Some(ref gc) = gate_config {
-                let task_id = task_number.to_string();
-                match quality_gate::run_quality_gate(gc).await {
-                    GateResult::Passed => {
-                        config.emitter.emit_quality_gate(&task_id, true, "");
-                    }
-                    GateResult::Failed { output } => {
-                        config.emitter.emit_quality_gate(&task_id, false, &output);
-                        continuation_context = Some(format!(
-                            "Quality gate failed. Fix these errors:\n\n{output}"
-                        ));
-                        continue;
-                    }
-                    GateResult::Timeout => {
-                        config
-                            .emitter
-                            .emit_quality_gate(&task_id, false, "Timed out");
-                        continuation_context = Some("Quality gate timed out.".into());
-                        continue;
-                    }
-                }
+            // Run acceptance checks and quality gates before accepting completion
+            let task_id = task_number.to_string();
+            let runs = quality_gate::run_gates(&gate_configs, |run| {
+                config.emitter.emit_quality_gate(&task_id, run);
+            })
+            .await;
+            if runs.iter().any(GateRun::blocks) {
+                let report = quality_gate::failure_report(&runs);
+                continuation_context = Some(format!(
+                    "Completion checks failed. Fix these errors:\n\n{report}"
+                ));
+                gate_failures = Some(report);
+                continue;
             }
 
             // Write worker notes for downstream tasks
@@ -171,6 +380,8 @@ async fn run_worker(config: WorkerConfig, abort_flag: Arc<AtomicBool>) -> Result
                 task_number,
                 success: true,
                 error: None,
+                paused: false,
+                transcript,
             });
         }
 
@@ -178,18 +389,54 @@ async fn run_worker(config: WorkerConfig, abort_flag: Arc<AtomicBool>) -> Result
             return Ok(WorkerResult {
                 task_number,
                 success: false,
-                error: Some(reason),
+                error: Some(WorkerError::Blocked(reason)),
+                paused: false,
+                transcript,
             });
         }
 
+        gate_failures = None;
         continuation_context = Some(extract_progress_summary(&result_messages));
     }
 
-    // Exhausted max iterations — treat as success (best effort)
+    // Exhausted max iterations with the checks still failing: a failure
+    if let Some(report) = gate_failures {
+        return Ok(WorkerResult {
+            task_number,
+            success: false,
+            error: Some(WorkerError::GatesFailed(report)),
+            paused: false,
+            transcript,
+        });
+    }
+
     Ok(WorkerResult {
         task_number,
-        success: true,
+        success: false,
+        error: Some(WorkerError::TimedOut(format!(
+            "Ran {MAX_ITERATIONS} iterations without reporting completion"
+        ))),
+        paused: false,
+        transcript,
+    })
+}
+
+fn timeout_message(limits: &TaskLimits) -> String {
+    format!(
+        "Ran past the task's {}s timeout",
+        limits.timeout_secs.unwrap_or_default()
+    )
+}
+
+/// Save where the worker stopped so the resumed drone can continue it.
+fn pause_worker(drone_dir: &std::path::Path, state: &PausedWorker) -> Result<WorkerResult> {
+    paused_workers::save(drone_dir, state)?;
+    Ok(WorkerResult {
+        task_number: state.task_number,
+        success: false,
         error: None,
+        paused: true,
+        transcript: Vec::new(),
     })
 }
 
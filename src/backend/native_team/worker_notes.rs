@@ -27,7 +27,7 @@ pub fn append_note(drone_dir: &Path, note: &WorkerNote) -> Result<()> {
 }
 
 /// Read all worker notes from the drone directory.
-fn read_all_notes(drone_dir: &Path) -> Vec<WorkerNote> {
+pub fn read_all_notes(drone_dir: &Path) -> Vec<WorkerNote> {
     let path = drone_dir.join(NOTES_FILE);
     let contents = match std::fs::read_to_string(&path) {
         Ok(c) => c,
@@ -48,6 +48,14 @@ pub fn read_dependency_notes(drone_dir: &Path, depends_on: &[usize]) -> Vec<Work
         .collect()
 }
 
+/// The latest note left for a task, if any.
+pub fn read_task_note(drone_dir: &Path, task_number: usize) -> Option<WorkerNote> {
+    read_all_notes(drone_dir)
+        .into_iter()
+        .rev()
+        .find(|note| note.task_number == task_number)
+}
+
 /// Detect files changed in the working directory using git.
 pub async fn detect_files_changed(cwd: &Path) -> Vec<String> {
     let output = tokio::process::Command::new("git")
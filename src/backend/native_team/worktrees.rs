@@ -0,0 +1,362 @@
+//! Per-worker git worktrees.
+//!
+//! Each worker gets its own checkout on a branch cut from the drone
+//! worktree's HEAD, so parallel workers never edit the same files on disk.
+//! When a task completes, the coordinator commits the worker's changes and
+//! merges its branch back into the drone worktree.
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use anyhow::{bail, Context, Result};
+
+/// A worker's isolated checkout.
+#[derive(Debug, Clone)]
+pub struct WorkerWorktree {
+    pub task_number: usize,
+    pub path: PathBuf,
+    pub branch: String,
+}
+
+/// Result of merging a worker branch back into the drone worktree.
+pub enum MergeOutcome {
+    Merged,
+    /// The merge stopped on conflicts and is still in progress in the drone
+    /// worktree. Callers must either conclude or abort it.
+    Conflict {
+        files: Vec<String>,
+    },
+}
+
+/// Branch name for a worker's task. Kept flat (not `hive/<drone>/...`)
+/// because `hive/<drone>` is already a branch and git refs can't nest under it.
+pub fn worker_branch(drone_name: &str, task_number: usize) -> String {
+    format!("hive/{drone_name}-task-{task_number}")
+}
+
+/// Create (or recreate) the worktree for a task under `<drone_dir>/worktrees/`.
+pub async fn create(
+    drone_worktree: &Path,
+    drone_dir: &Path,
+    drone_name: &str,
+    task_number: usize,
+) -> Result<WorkerWorktree> {
+    let inside = git(drone_worktree, &["rev-parse", "--is-inside-work-tree"]).await?;
+    if !inside.status.success() {
+        bail!("{} is not a git work tree", drone_worktree.display());
+    }
+
+    let root = drone_dir.join("worktrees");
+    std::fs::create_dir_all(&root).context("Creating worker worktrees directory")?;
+    let root = root.canonicalize()?;
+    let path = root.join(format!("task-{task_number}"));
+    let branch = worker_branch(drone_name, task_number);
+
+    // A previous attempt (retry or resume) may have left one behind
+    if path.exists() {
+        let path_str = path.to_string_lossy();
+        let _ = git(
+            drone_worktree,
+            &["worktree", "remove", "--force", &path_str],
+        )
+        .await;
+        let _ = std::fs::remove_dir_all(&path);
+    }
+    let _ = git(drone_worktree, &["worktree", "prune"]).await;
+
+    let path_str = path.to_string_lossy();
+    check(
+        git(
+            drone_worktree,
+            &["worktree", "add", "-B", &branch, &path_str, "HEAD"],
+        )
+        .await?,
+        "git worktree add",
+    )?;
+
+    Ok(WorkerWorktree {
+        task_number,
+        path,
+        branch,
+    })
+}
+
+/// Reuse the worktree a paused worker left behind, uncommitted edits and
+/// all. Returns `None` if it is gone or no longer on the task's branch.
+pub async fn reopen(
+    drone_dir: &Path,
+    drone_name: &str,
+    task_number: usize,
+) -> Option<WorkerWorktree> {
+    let path = drone_dir
+        .join("worktrees")
+        .join(format!("task-{task_number}"))
+        .canonicalize()
+        .ok()?;
+    let branch = worker_branch(drone_name, task_number);
+    let head = git(&path, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .await
+        .ok()?;
+    if !head.status.success() || String::from_utf8_lossy(&head.stdout).trim() != branch {
+        return None;
+    }
+    Some(WorkerWorktree {
+        task_number,
+        path,
+        branch,
+    })
+}
+
+/// Stage and commit everything in the worker's checkout.
+/// Returns `false` if there was nothing to commit.
+pub async fn commit_all(wt: &WorkerWorktree, message: &str) -> Result<bool> {
+    check(git(&wt.path, &["add", "-A"]).await?, "git add")?;
+    let staged = git(&wt.path, &["diff", "--cached", "--quiet"]).await?;
+    if staged.status.success() {
+        return Ok(false);
+    }
+    check(
+        git(&wt.path, &["commit", "--no-verify", "-m", message]).await?,
+        "git commit",
+    )?;
+    Ok(true)
+}
+
+/// Changes on a worker's branch since it was cut from the drone worktree.
+pub async fn branch_diff(drone_worktree: &Path, wt: &WorkerWorktree) -> Result<String> {
+    let range = format!("HEAD...{}", wt.branch);
+    diff(drone_worktree, &[&range]).await
+}
+
+/// Changes in `cwd` (committed or not) since `base`.
+pub async fn diff_since(cwd: &Path, base: &str) -> Result<String> {
+    diff(cwd, &[base]).await
+}
+
+pub async fn head_commit(cwd: &Path) -> Result<String> {
+    let output = git(cwd, &["rev-parse", "HEAD"]).await?;
+    let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    check(output, "git rev-parse")?;
+    Ok(head)
+}
+
+async fn diff(cwd: &Path, args: &[&str]) -> Result<String> {
+    let mut full = vec!["diff", "--no-color"];
+    full.extend_from_slice(args);
+    let output = git(cwd, &full).await?;
+    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    check(output, "git diff")?;
+    Ok(diff)
+}
+
+/// Merge a worker's branch into the drone worktree.
+pub async fn merge_back(
+    drone_worktree: &Path,
+    wt: &WorkerWorktree,
+    message: &str,
+) -> Result<MergeOutcome> {
+    let output = git(
+        drone_worktree,
+        &["merge", "--no-ff", "--no-edit", "-m", message, &wt.branch],
+    )
+    .await?;
+    if output.status.success() {
+        return Ok(MergeOutcome::Merged);
+    }
+
+    let files = conflicted_files(drone_worktree).await;
+    if files.is_empty() {
+        abort_merge(drone_worktree).await;
+        bail!(
+            "git merge {} failed: {}",
+            wt.branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(MergeOutcome::Conflict { files })
+}
+
+/// Commit an in-progress merge once every conflict has been resolved.
+/// Returns `false` (leaving the merge in progress) if conflict markers remain.
+pub async fn conclude_merge(drone_worktree: &Path, files: &[String]) -> Result<bool> {
+    let mut args = vec!["grep", "-l", "-e", "^<<<<<<< ", "-e", "^>>>>>>> ", "--"];
+    args.extend(files.iter().map(String::as_str));
+    let markers = git(drone_worktree, &args).await?;
+    if markers.status.success() {
+        return Ok(false);
+    }
+
+    // Stage only the conflicted files: the drone worktree also holds
+    // untracked orchestrator files (e.g. the `.hive` symlink).
+    let mut add = vec!["add", "--"];
+    add.extend(files.iter().map(String::as_str));
+    check(git(drone_worktree, &add).await?, "git add")?;
+    if !conflicted_files(drone_worktree).await.is_empty() {
+        return Ok(false);
+    }
+    check(
+        git(drone_worktree, &["commit", "--no-verify", "--no-edit"]).await?,
+        "git commit",
+    )?;
+    Ok(true)
+}
+
+/// Abort an in-progress merge in the drone worktree (best effort).
+pub async fn abort_merge(drone_worktree: &Path) {
+    let _ = git(drone_worktree, &["merge", "--abort"]).await;
+}
+
+/// Remove a worker's worktree and delete its branch (best effort).
+pub async fn remove(drone_worktree: &Path, wt: &WorkerWorktree) {
+    let path_str = wt.path.to_string_lossy();
+    let _ = git(
+        drone_worktree,
+        &["worktree", "remove", "--force", &path_str],
+    )
+    .await;
+    let _ = git(drone_worktree, &["branch", "-D", &wt.branch]).await;
+}
+
+async fn conflicted_files(cwd: &Path) -> Vec<String> {
+    match git(cwd, &["diff", "--name-only", "--diff-filter=U"]).await {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+async fn git(cwd: &Path, args: &[&str]) -> Result<Output> {
+    tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .with_context(|| format!("Running git {}", args.first().unwrap_or(&"")))
+}
+
+fn check(output: Output, what: &str) -> Result<()> {
+    if !output.status.success() {
+        bail!(
+            "{what} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(cwd: &Path, script: &str) {
+        let status = std::process::Command::new("sh")
+            .args(["-c", script])
+            .current_dir(cwd)
+            .status()
+            .unwrap();
+        assert!(status.success(), "{script}");
+    }
+
+    fn init_repo(dir: &Path) {
+        sh(
+            dir,
+            "git init -q -b main . && git config user.email t@t && git config user.name t \
+             && echo base > a.txt && echo base > b.txt && git add -A && git commit -qm init",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallel_workers_merge_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let drone_dir = tmp.path().join("drone");
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+
+        let one = create(&repo, &drone_dir, "d", 1).await.unwrap();
+        let two = create(&repo, &drone_dir, "d", 2).await.unwrap();
+        std::fs::write(one.path.join("a.txt"), "one\n").unwrap();
+        std::fs::write(two.path.join("b.txt"), "two\n").unwrap();
+
+        assert!(commit_all(&one, "task 1").await.unwrap());
+        assert!(commit_all(&two, "task 2").await.unwrap());
+        let diff = branch_diff(&repo, &one).await.unwrap();
+        assert!(diff.contains("+one"), "{diff}");
+        assert!(!diff.contains("+two"), "{diff}");
+        assert!(matches!(
+            merge_back(&repo, &one, "task 1").await.unwrap(),
+            MergeOutcome::Merged
+        ));
+        assert!(matches!(
+            merge_back(&repo, &two, "task 2").await.unwrap(),
+            MergeOutcome::Merged
+        ));
+        remove(&repo, &one).await;
+        remove(&repo, &two).await;
+
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "one\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("b.txt")).unwrap(),
+            "two\n"
+        );
+        assert!(!one.path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_reopen_keeps_uncommitted_edits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let drone_dir = tmp.path().join("drone");
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+
+        let wt = create(&repo, &drone_dir, "d", 1).await.unwrap();
+        std::fs::write(wt.path.join("a.txt"), "half done\n").unwrap();
+
+        let reopened = reopen(&drone_dir, "d", 1).await.unwrap();
+        assert_eq!(reopened.path, wt.path);
+        assert_eq!(reopened.branch, wt.branch);
+        assert_eq!(
+            std::fs::read_to_string(reopened.path.join("a.txt")).unwrap(),
+            "half done\n"
+        );
+        assert!(reopen(&drone_dir, "d", 2).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_conflict_then_resolve() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let drone_dir = tmp.path().join("drone");
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+
+        let one = create(&repo, &drone_dir, "d", 1).await.unwrap();
+        let two = create(&repo, &drone_dir, "d", 2).await.unwrap();
+        std::fs::write(one.path.join("a.txt"), "one\n").unwrap();
+        std::fs::write(two.path.join("a.txt"), "two\n").unwrap();
+        commit_all(&one, "task 1").await.unwrap();
+        commit_all(&two, "task 2").await.unwrap();
+        merge_back(&repo, &one, "task 1").await.unwrap();
+
+        let MergeOutcome::Conflict { files } = merge_back(&repo, &two, "task 2").await.unwrap()
+        else {
+            panic!("expected conflict");
+        };
+        assert_eq!(files, vec!["a.txt"]);
+        assert!(!conclude_merge(&repo, &files).await.unwrap());
+
+        std::fs::write(repo.join("a.txt"), "one and two\n").unwrap();
+        assert!(conclude_merge(&repo, &files).await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "one and two\n"
+        );
+    }
+}
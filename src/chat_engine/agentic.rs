@@ -3,12 +3,15 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 
 use crate::webui::anthropic::{
-    self,
+    self, cache,
     types::{ContentBlock, Message, MessageContent, MessagesRequest, ThinkingConfig},
 };
 use crate::webui::auth::credentials;
 use crate::webui::mcp_client::pool::McpPool;
 use crate::webui::provider;
+use crate::webui::tools::{
+    Permissions, PersistentShell, ProcessRegistry, ProcessSandbox, TaskProposer, WritePolicy,
+};
 
 use super::context;
 use super::persistence;
@@ -32,6 +35,40 @@ pub struct AgenticLoopParams<'a> {
     pub max_turns: Option<usize>,
     pub mcp_pool: Option<Arc<tokio::sync::Mutex<McpPool>>>,
     pub deferred_tools_active: bool,
+    /// Allow/ask/deny rules for built-in tools; only the built-in deny rules
+    /// apply when unset
+    pub permissions: Option<Arc<Permissions>>,
+    /// Optional restriction on which paths Write/Edit/Bash may write to
+    pub write_policy: Option<Arc<dyn WritePolicy>>,
+    /// Optional sandbox for Bash commands
+    pub sandbox: Option<Arc<ProcessSandbox>>,
+    /// Optional shell that Bash commands share, so `cd` and exports persist
+    pub shell: Option<Arc<PersistentShell>>,
+    /// Registry for BashBackground processes; when unset the loop keeps its
+    /// own, and its processes are killed when the loop ends
+    pub processes: Option<Arc<ProcessRegistry>>,
+    /// Mark the system prompt, tools and conversation prefix cacheable
+    pub prompt_caching: bool,
+    /// Optional receiver for the usage of every API call
+    pub usage_sink: Option<Arc<dyn UsageSink>>,
+    /// Optional receiver for ProposeTask calls (native-team workers)
+    pub task_proposer: Option<Arc<dyn TaskProposer>>,
+    /// Optional source of messages added after each round of tool results
+    pub inbox: Option<Arc<dyn MessageInbox>>,
+    /// When set, stop before the next API call. The returned history can be
+    /// passed back in later to continue the conversation.
+    pub pause_flag: Option<&'a std::sync::atomic::AtomicBool>,
+}
+
+/// Receives the token usage of each API call made by the loop.
+pub trait UsageSink: Send + Sync {
+    fn record(&self, model: &str, usage: &anthropic::types::UsageStats);
+}
+
+/// Messages sent to a running loop from outside, e.g. by an operator.
+pub trait MessageInbox: Send + Sync {
+    /// Take the messages received since the last call.
+    fn take(&self) -> Vec<String>;
 }
 
 /// The agentic loop: stream API response, execute tools, repeat until end_turn.
@@ -51,8 +88,20 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
         max_turns,
         mcp_pool,
         mut deferred_tools_active,
+        permissions,
+        write_policy,
+        sandbox,
+        shell,
+        processes,
+        prompt_caching,
+        usage_sink,
+        task_proposer,
+        inbox,
+        pause_flag,
     } = params;
     let max_tool_turns = max_turns.unwrap_or(25);
+    let permissions = permissions.unwrap_or_else(|| Arc::new(Permissions::builtin()));
+    let processes = processes.unwrap_or_default();
 
     // Resolve model output limit, then fit thinking budget + output within it
     let model_limit = anthropic::model::max_output_tokens(model, effort.thinking_enabled());
@@ -77,6 +126,9 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
         .unwrap_or_default();
 
     for _turn in 0..max_tool_turns {
+        if pause_flag.is_some_and(|p| p.load(Ordering::Relaxed)) {
+            break;
+        }
         if abort_flag.load(Ordering::Relaxed) {
             break;
         }
@@ -93,7 +145,14 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
         // Filter tools by tier: Core always, Deferred only when activated
         let api_tools = all_session_tools
             .as_ref()
-            .map(|tools| tool_tier::filter_by_tier(tools, deferred_tools_active));
+            .map(|tools| tool_tier::filter_by_tier(tools, deferred_tools_active))
+            .map(|tools| {
+                if prompt_caching {
+                    cache::mark_last_tool(tools)
+                } else {
+                    tools
+                }
+            });
 
         // Context window management: truncate if needed
         let estimated = context::estimate_total_tokens(&messages);
@@ -105,14 +164,18 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
             context::truncate_messages(&stripped, stripped_estimated)
         };
 
-        // Inject fresh project context into system prompt (30s TTL cache)
+        // Inject fresh project context into system prompt (30s TTL cache).
+        // With caching on, it goes in its own block after the cached base
+        // prompt so git changes don't invalidate the whole prefix.
         let effective_system = match system_prompt {
             Some(ref base) => {
                 let ctx = super::project_context::gather_project_context(cwd).await;
-                if ctx.is_empty() {
-                    Some(base.clone())
+                if prompt_caching {
+                    Some(cache::cached_system(base.clone(), Some(ctx)))
+                } else if ctx.is_empty() {
+                    Some(base.clone().into())
                 } else {
-                    Some(format!("{base}{ctx}"))
+                    Some(format!("{base}{ctx}").into())
                 }
             }
             None => None,
@@ -133,6 +196,7 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
             } else {
                 Some(1.0)
             },
+            cache_conversation: prompt_caching,
         };
 
         let (assistant_msg, usage, stop_reason) =
@@ -140,6 +204,9 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
 
         messages.push(assistant_msg.clone());
         broadcast_usage(tx, session_id, &usage, &store).await;
+        if let Some(sink) = &usage_sink {
+            sink.record(model, &usage);
+        }
 
         if stop_reason != "tool_use" || abort_flag.load(Ordering::Relaxed) {
             break;
@@ -151,17 +218,26 @@ pub async fn run_agentic_loop(params: AgenticLoopParams<'_>) -> anyhow::Result<V
         }
 
         // Pass full tool list so ToolSearch can enumerate all available tools
-        let all_tools_ref = all_session_tools.as_deref().unwrap_or(&[]);
-        let tool_results = tool_executor::execute_tools(
-            &tool_uses,
+        let exec_ctx = tool_executor::ToolExecContext {
             abort_flag,
-            &mcp_pool,
+            mcp_pool: &mcp_pool,
             cwd,
             tx,
-            all_tools_ref,
-            &mut deferred_tools_active,
-        )
-        .await;
+            all_tools: all_session_tools.as_deref().unwrap_or(&[]),
+            permissions: &permissions,
+            write_policy: write_policy.as_deref(),
+            sandbox: sandbox.as_deref(),
+            shell: shell.as_deref(),
+            processes: Some(&processes),
+            task_proposer: task_proposer.as_deref(),
+        };
+        let mut tool_results =
+            tool_executor::execute_tools(&tool_uses, &exec_ctx, &mut deferred_tools_active).await;
+        if let Some(inbox) = &inbox {
+            tool_results.extend(inbox.take().into_iter().map(|text| ContentBlock::Text {
+                text: format!("Message from the operator:\n\n{text}"),
+            }));
+        }
 
         let tool_result_message = Message {
             role: "user".to_string(),
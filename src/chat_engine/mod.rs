@@ -34,6 +34,10 @@ pub struct CreateSessionOpts {
     pub system_prompt: Option<String>,
     pub agent: Option<String>,
     pub max_turns: Option<usize>,
+    /// Prompt caching override (default: on)
+    pub prompt_caching: Option<bool>,
+    /// Keep one shell for the session's Bash calls (default: off)
+    pub persistent_shell: Option<bool>,
 }
 
 /// The chat engine — creates and manages sessions without HTTP.
@@ -144,6 +148,10 @@ impl ChatEngine {
             max_turns: session.max_turns,
             mcp_pool: session.mcp_pool.clone(),
             deferred_tools_active: session.deferred_tools_active,
+            prompt_caching: session.prompt_caching,
+            permission_requests: session.permission_requests.clone(),
+            shell: session.shell.clone(),
+            processes: session.processes.clone(),
         };
 
         drop(sessions);
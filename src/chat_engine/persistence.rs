@@ -21,6 +21,16 @@ pub struct SessionMeta {
     /// Cumulative output tokens (persisted for context usage display)
     #[serde(default)]
     pub total_output_tokens: u64,
+    /// Whether prompt caching is enabled for this session
+    #[serde(default = "default_prompt_caching")]
+    pub prompt_caching: bool,
+    /// Whether Bash calls share one long-lived shell
+    #[serde(default)]
+    pub persistent_shell: bool,
+}
+
+fn default_prompt_caching() -> bool {
+    true
 }
 
 fn sessions_dir() -> PathBuf {
@@ -7,6 +7,8 @@ use tokio::sync::{broadcast, Mutex};
 
 use crate::webui::anthropic::types::{Message, ToolDefinition};
 use crate::webui::mcp_client::pool::McpPool;
+use crate::webui::tools::permissions::PermissionRequests;
+use crate::webui::tools::{PersistentShell, ProcessRegistry};
 
 #[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -157,6 +159,14 @@ pub struct ChatSession {
     pub agent: Option<String>,
     /// Whether deferred (MCP) tools have been activated for this session
     pub deferred_tools_active: bool,
+    /// Send prompt-cache breakpoints with each request (default: on)
+    pub prompt_caching: bool,
+    /// Tool calls waiting for the user to allow or refuse them
+    pub permission_requests: PermissionRequests,
+    /// Shell shared by the session's Bash calls, when enabled
+    pub shell: Option<Arc<PersistentShell>>,
+    /// Processes started with BashBackground, kept across messages
+    pub processes: Arc<ProcessRegistry>,
 }
 
 pub type SessionStore = Arc<Mutex<HashMap<String, ChatSession>>>;
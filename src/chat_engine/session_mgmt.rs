@@ -6,7 +6,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 
 use crate::webui::mcp_client::pool::McpPool;
-use crate::webui::tools;
+use crate::webui::tools::{self, PersistentShell};
 
 use super::persistence;
 use super::session::{ChatMode, ChatSession, Effort, SessionStatus, SessionStore};
@@ -53,6 +53,8 @@ pub async fn create_session(
         system_prompt: sys_prompt.clone(),
         total_input_tokens: 0,
         total_output_tokens: 0,
+        prompt_caching: opts.prompt_caching.unwrap_or(true),
+        persistent_shell: opts.persistent_shell.unwrap_or(false),
     };
     persistence::write_meta(&meta);
 
@@ -86,6 +88,12 @@ pub async fn create_session(
         mcp_pool: Some(mcp_pool),
         agent: opts.agent,
         deferred_tools_active: false,
+        prompt_caching: meta.prompt_caching,
+        permission_requests: Default::default(),
+        shell: meta
+            .persistent_shell
+            .then(|| Arc::new(PersistentShell::new(&opts.cwd, None))),
+        processes: Default::default(),
     };
 
     store.lock().await.insert(id.clone(), session);
@@ -144,6 +152,12 @@ pub async fn restore_session(store: &SessionStore, id: &str) -> Option<()> {
         mcp_pool: Some(mcp_pool),
         agent: None,
         deferred_tools_active: false,
+        prompt_caching: meta.prompt_caching,
+        permission_requests: Default::default(),
+        shell: meta
+            .persistent_shell
+            .then(|| Arc::new(PersistentShell::new(&cwd, None))),
+        processes: Default::default(),
     };
 
     let id_owned = id.to_string();
@@ -5,6 +5,11 @@ use tracing::{error, info, warn};
 use crate::webui::anthropic;
 use crate::webui::auth::credentials;
 use crate::webui::mcp_client::pool::McpPool;
+use crate::webui::tools::permissions::{
+    PermissionPrompter, PermissionRequests, Permissions, TrustLevel,
+};
+use crate::webui::tools::shell::add_persistent_shell_tools;
+use crate::webui::tools::{PersistentShell, ProcessRegistry};
 
 use super::agentic::{run_agentic_loop, AgenticLoopParams};
 use super::persistence::{append_event, save_messages, update_meta_status};
@@ -28,6 +33,13 @@ pub struct AgenticTaskParams {
     pub max_turns: Option<usize>,
     pub mcp_pool: Option<Arc<tokio::sync::Mutex<McpPool>>>,
     pub deferred_tools_active: bool,
+    pub prompt_caching: bool,
+    /// Where answers to the session's permission requests arrive
+    pub permission_requests: PermissionRequests,
+    /// Shell the session's Bash calls share, if it keeps one
+    pub shell: Option<Arc<PersistentShell>>,
+    /// The session's background processes
+    pub processes: Arc<ProcessRegistry>,
 }
 
 pub fn spawn_agentic_task(params: AgenticTaskParams) {
@@ -47,6 +59,10 @@ pub fn spawn_agentic_task(params: AgenticTaskParams) {
         max_turns,
         mcp_pool,
         deferred_tools_active,
+        prompt_caching,
+        permission_requests,
+        shell,
+        processes,
     } = params;
 
     // Filter tools based on chat mode policy
@@ -65,6 +81,13 @@ pub fn spawn_agentic_task(params: AgenticTaskParams) {
                 .collect()
         }),
     };
+    let tools_opt = match (tools_opt, &shell) {
+        (Some(mut tools), Some(_)) => {
+            add_persistent_shell_tools(&mut tools);
+            Some(tools)
+        }
+        (tools, _) => tools,
+    };
 
     tokio::spawn(async move {
         info!(
@@ -97,6 +120,20 @@ pub fn spawn_agentic_task(params: AgenticTaskParams) {
             max_turns,
             mcp_pool,
             deferred_tools_active,
+            permissions: Some(Arc::new(
+                Permissions::load(TrustLevel::Chat, &session_cwd).with_prompter(
+                    PermissionPrompter::new(tx.clone(), permission_requests, abort_flag.clone()),
+                ),
+            )),
+            write_policy: None,
+            sandbox: None,
+            shell,
+            processes: Some(processes),
+            prompt_caching,
+            usage_sink: None,
+            task_proposer: None,
+            inbox: None,
+            pause_flag: None,
         })
         .await;
 
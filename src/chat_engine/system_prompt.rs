@@ -35,11 +35,12 @@ Write plans to `.hive/plans/<descriptive-slug>.md` with this structure:
 
 ### 1. Environment Setup
 - type: setup
+- run: <dependency install, e.g. pnpm install --frozen-lockfile>
+- run: <baseline build or test command, e.g. pnpm build>
 
-Create and checkout a feature branch from origin/main (or origin/master).
-Install dependencies (npm install, pnpm install, cargo fetch, pip install, etc.).
-Set up any required .env files, config, or local environment.
-Verify the project builds and tests pass BEFORE any code changes.
+Hive runs each `- run:` command in order in the worktree before dispatching any work,
+and fails the drone if one fails. Install dependencies, set up any required .env files
+or config, and verify the project builds BEFORE any code changes.
 
 ### 2. <First Work Task>
 - type: work
@@ -1,7 +1,8 @@
 //! Tool execution logic extracted from the agentic loop.
 //!
-//! Handles dispatching to built-in tools, MCP tools, and the ToolSearch
-//! meta-tool (which activates the deferred tool tier).
+//! Handles dispatching to built-in tools, MCP tools, the ToolSearch
+//! meta-tool (which activates the deferred tool tier), ProposeTask and
+//! BashReset.
 
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -9,7 +10,10 @@ use tokio::sync::broadcast;
 
 use crate::webui::anthropic::types::{ContentBlock, ToolDefinition};
 use crate::webui::mcp_client::pool::McpPool;
-use crate::webui::tools;
+use crate::webui::tools::{
+    self, Permissions, PersistentShell, ProcessContext, ProcessRegistry, ProcessSandbox,
+    TaskProposer, WritePolicy,
+};
 
 use super::compressor;
 
@@ -18,19 +22,50 @@ pub struct ToolSearchResult {
     pub content: String,
 }
 
+/// Shared context for executing a batch of tool calls, grouped to avoid too-many-arguments.
+pub struct ToolExecContext<'a> {
+    pub abort_flag: &'a Arc<std::sync::atomic::AtomicBool>,
+    pub mcp_pool: &'a Option<Arc<tokio::sync::Mutex<McpPool>>>,
+    pub cwd: &'a std::path::Path,
+    pub tx: &'a broadcast::Sender<String>,
+    /// Full tool list so ToolSearch can enumerate all available tools
+    pub all_tools: &'a [ToolDefinition],
+    /// Rules every built-in tool call is checked against
+    pub permissions: &'a Permissions,
+    /// Optional restriction on which paths built-in tools may write to
+    pub write_policy: Option<&'a dyn WritePolicy>,
+    /// Optional sandbox for Bash commands
+    pub sandbox: Option<&'a ProcessSandbox>,
+    /// Shell that Bash commands share; each call gets a fresh `bash -c` without one
+    pub shell: Option<&'a PersistentShell>,
+    /// Background processes started with BashBackground
+    pub processes: Option<&'a ProcessRegistry>,
+    /// Receiver for ProposeTask calls; the tool is unknown without one
+    pub task_proposer: Option<&'a dyn TaskProposer>,
+}
+
 /// Execute a batch of tool calls, returning ContentBlocks for the API.
 ///
 /// If a `ToolSearch` call is encountered, it is handled inline using the
 /// full `all_tools` list and `deferred_activated` is set to `true`.
 pub async fn execute_tools(
     tool_uses: &[(String, String, serde_json::Value)],
-    abort_flag: &Arc<std::sync::atomic::AtomicBool>,
-    mcp_pool: &Option<Arc<tokio::sync::Mutex<McpPool>>>,
-    cwd: &std::path::Path,
-    tx: &broadcast::Sender<String>,
-    all_tools: &[ToolDefinition],
+    ctx: &ToolExecContext<'_>,
     deferred_activated: &mut bool,
 ) -> Vec<ContentBlock> {
+    let ToolExecContext {
+        abort_flag,
+        mcp_pool,
+        cwd,
+        tx,
+        all_tools,
+        permissions,
+        write_policy,
+        sandbox,
+        shell,
+        processes,
+        task_proposer,
+    } = *ctx;
     let mut tool_result_blocks: Vec<ContentBlock> = Vec::new();
 
     for (tool_id, tool_name, tool_input) in tool_uses {
@@ -46,6 +81,15 @@ pub async fn execute_tools(
                 content,
                 is_error: false,
             }
+        } else if let (Some(proposer), "ProposeTask") = (task_proposer, tool_name.as_str()) {
+            tools::propose_task::execute(proposer, tool_input)
+        } else if let (Some(shell), tools::shell::RESET_TOOL) = (shell, tool_name.as_str()) {
+            shell.reset().await;
+            tools::ToolExecutionResult {
+                content: "Shell reset. The next command starts in the original working directory."
+                    .to_string(),
+                is_error: false,
+            }
         } else if tool_name.contains("__") {
             // MCP tool
             let mcp_result = if let Some(ref pool) = mcp_pool {
@@ -66,7 +110,20 @@ pub async fn execute_tools(
             }
         } else {
             // Built-in tool
-            match tools::execute_tool(tool_name, tool_input, cwd).await {
+            match tools::execute_tool(
+                tool_name,
+                tool_input,
+                cwd,
+                permissions,
+                write_policy,
+                ProcessContext {
+                    sandbox,
+                    shell,
+                    processes,
+                },
+            )
+            .await
+            {
                 Some(r) => r,
                 None => tools::ToolExecutionResult {
                     content: format!("Unknown tool: {tool_name}"),
@@ -87,11 +87,13 @@ mod tests {
                 name: "Read".into(),
                 description: String::new(),
                 input_schema: serde_json::json!({}),
+                cache_control: None,
             },
             ToolDefinition {
                 name: "mcp__playwright__click".into(),
                 description: String::new(),
                 input_schema: serde_json::json!({}),
+                cache_control: None,
             },
         ];
         let filtered = filter_by_tier(&tools, false);
@@ -106,11 +108,13 @@ mod tests {
                 name: "Read".into(),
                 description: String::new(),
                 input_schema: serde_json::json!({}),
+                cache_control: None,
             },
             ToolDefinition {
                 name: "mcp__playwright__click".into(),
                 description: String::new(),
                 input_schema: serde_json::json!({}),
+                cache_control: None,
             },
         ];
         let filtered = filter_by_tier(&tools, true);
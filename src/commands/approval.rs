@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+use crate::backend::native_team::approvals::{self, PendingApproval};
+use crate::backend::native_team::control::{self, ControlRequest};
+
+/// Show the work a drone is waiting on a reviewer for, with diffs and notes.
+pub fn review(name: String) -> Result<()> {
+    let drone_dir = PathBuf::from(".hive/drones").join(&name);
+    if !drone_dir.exists() {
+        bail!("Drone '{}' not found", name);
+    }
+
+    let pending = approvals::load(&drone_dir);
+    if pending.is_empty() {
+        println!(
+            "Drone '{}' has nothing awaiting approval.",
+            name.bright_cyan()
+        );
+        return Ok(());
+    }
+
+    for approval in &pending {
+        print_approval(approval);
+    }
+    println!(
+        "\nApprove with 'hive approve {name} [--task N]' or reject with 'hive reject {name} [--task N] --comment \"...\"'."
+    );
+    Ok(())
+}
+
+fn print_approval(approval: &PendingApproval) {
+    let heading = match approval.task {
+        Some(n) => format!("Task {n}: {}", approval.title),
+        None => approval.title.clone(),
+    };
+    println!("\n{} {}", "⏳".yellow(), heading.bold());
+    println!("  Requested {}", approval.requested.dimmed());
+    if !approval.notes.is_empty() {
+        println!();
+        for line in approval.notes.lines() {
+            println!("  {line}");
+        }
+    }
+    println!();
+    for line in approval.diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{line}");
+        }
+    }
+}
+
+/// Approve a task's changes, or the PR when `task` is `None`.
+pub fn approve(name: String, task: Option<usize>, comment: Option<String>) -> Result<()> {
+    decide(&name, ControlRequest::Approve { task, comment })?;
+    println!("{} Approved {}", "✓".green(), subject(task));
+    Ok(())
+}
+
+/// Reject a task's changes, or the PR when `task` is `None`. With a
+/// comment the work is revised; without one it is dropped.
+pub fn reject(name: String, task: Option<usize>, comment: Option<String>) -> Result<()> {
+    let revise = comment.as_deref().is_some_and(|c| !c.trim().is_empty());
+    decide(&name, ControlRequest::Reject { task, comment })?;
+    let outcome = if revise {
+        "sent back with your comment"
+    } else {
+        "dropped"
+    };
+    println!("{} Rejected {} — {}", "✗".red(), subject(task), outcome);
+    Ok(())
+}
+
+fn decide(name: &str, request: ControlRequest) -> Result<()> {
+    let drone_dir = PathBuf::from(".hive/drones").join(name);
+    if !drone_dir.exists() {
+        bail!("Drone '{}' not found", name);
+    }
+    let response = control::send(&drone_dir, &request)?;
+    if let Some(error) = response.error {
+        bail!("{}", error);
+    }
+    Ok(())
+}
+
+fn subject(task: Option<usize>) -> String {
+    match task {
+        Some(n) => format!("task {n}"),
+        None => "the pull request".to_string(),
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
+use crate::webui::anthropic::model::resolve_model;
+use crate::webui::anthropic::pricing;
+use crate::webui::anthropic::types::UsageStats;
+
 // ============================================================================
 // Cost Tracking
 // ============================================================================
@@ -18,6 +23,44 @@ pub(crate) struct CostSummary {
     pub output_tokens: u64,
     pub cache_read_tokens: u64,
     pub cache_creation_tokens: u64,
+    /// Per-task totals keyed by task id (native team mode only)
+    pub by_task: BTreeMap<String, CostBreakdown>,
+    /// Per-worker totals keyed by worker/agent name (native team mode only)
+    pub by_worker: BTreeMap<String, CostBreakdown>,
+}
+
+/// Cost and tokens attributed to one task or worker.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CostBreakdown {
+    /// Task the worker ran, for per-worker entries
+    pub task: Option<String>,
+    pub cost_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+}
+
+impl CostSummary {
+    /// Input, output and cache tokens combined.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+}
+
+impl CostBreakdown {
+    /// Input, output and cache tokens combined.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+
+    fn add(&mut self, usage: &UsageStats, cost_usd: f64) {
+        self.cost_usd += cost_usd;
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cache_read_tokens += usage.cache_read_input_tokens;
+        self.cache_creation_tokens += usage.cache_creation_input_tokens;
+    }
 }
 
 /// Parse cost/token info from a drone's activity.log at a specific project root.
@@ -117,60 +160,67 @@ fn parse_cost_from_log_path(log_path: &Path) -> CostSummary {
     summary
 }
 
-/// Pricing constants (per million tokens, Sonnet 4.5 as default).
-const INPUT_PRICE_PER_M: f64 = 3.0;
-const OUTPUT_PRICE_PER_M: f64 = 15.0;
-const CACHE_READ_PRICE_PER_M: f64 = 0.30;
-const CACHE_CREATE_PRICE_PER_M: f64 = 3.75;
-
 /// Parse cost from native team cost.ndjson.
-/// Each line has incremental usage from one agentic loop call.
-/// We take the latest line (most recent cumulative snapshot from worker).
-fn parse_cost_from_ndjson(path: &Path) -> CostSummary {
+/// Each line holds the usage of one API call, tagged with model, worker
+/// and task. Lines written before USD was recorded are priced from the
+/// model (or the default model when absent).
+pub(crate) fn parse_cost_from_ndjson(path: &Path) -> CostSummary {
     let contents = match fs::read_to_string(path) {
         Ok(c) => c,
         Err(_) => return CostSummary::default(),
     };
 
-    let mut total_input: u64 = 0;
-    let mut total_output: u64 = 0;
-    let mut total_cache_read: u64 = 0;
-    let mut total_cache_create: u64 = 0;
+    let mut summary = CostSummary::default();
+    let mut total = CostBreakdown::default();
 
     for line in contents.lines() {
         let parsed: serde_json::Value = match serde_json::from_str(line) {
             Ok(v) => v,
             Err(_) => continue,
         };
-        // Each line is a snapshot of one worker's session at that point.
-        // Sum across all workers (each worker writes its cumulative totals).
-        if let Some(v) = parsed.get("input_tokens").and_then(|v| v.as_u64()) {
-            total_input += v;
-        }
-        if let Some(v) = parsed.get("output_tokens").and_then(|v| v.as_u64()) {
-            total_output += v;
-        }
-        if let Some(v) = parsed.get("cache_read").and_then(|v| v.as_u64()) {
-            total_cache_read += v;
+        let count = |key: &str| parsed.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let usage = UsageStats {
+            input_tokens: count("input_tokens"),
+            output_tokens: count("output_tokens"),
+            cache_read_input_tokens: count("cache_read"),
+            cache_creation_input_tokens: count("cache_create"),
+        };
+        let cost = parsed
+            .get("cost_usd")
+            .and_then(|v| v.as_f64())
+            .unwrap_or_else(|| {
+                // Lines written before models were recorded came from Sonnet drones
+                let model = parsed
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_else(|| resolve_model("sonnet"));
+                pricing::cost_usd(model, &usage)
+            });
+
+        total.add(&usage, cost);
+        let task = parsed.get("task").and_then(|v| v.as_str());
+        if let Some(task) = task {
+            summary
+                .by_task
+                .entry(task.to_string())
+                .or_default()
+                .add(&usage, cost);
         }
-        if let Some(v) = parsed.get("cache_create").and_then(|v| v.as_u64()) {
-            total_cache_create += v;
+        if let Some(worker) = parsed.get("worker").and_then(|v| v.as_str()) {
+            let entry = summary.by_worker.entry(worker.to_string()).or_default();
+            entry.add(&usage, cost);
+            if task.is_some() {
+                entry.task = task.map(str::to_string);
+            }
         }
     }
 
-    let cost = (total_input as f64 * INPUT_PRICE_PER_M
-        + total_output as f64 * OUTPUT_PRICE_PER_M
-        + total_cache_read as f64 * CACHE_READ_PRICE_PER_M
-        + total_cache_create as f64 * CACHE_CREATE_PRICE_PER_M)
-        / 1_000_000.0;
-
-    CostSummary {
-        total_cost_usd: cost,
-        input_tokens: total_input,
-        output_tokens: total_output,
-        cache_read_tokens: total_cache_read,
-        cache_creation_tokens: total_cache_create,
-    }
+    summary.total_cost_usd = total.cost_usd;
+    summary.input_tokens = total.input_tokens;
+    summary.output_tokens = total.output_tokens;
+    summary.cache_read_tokens = total.cache_read_tokens;
+    summary.cache_creation_tokens = total.cache_creation_tokens;
+    summary
 }
 
 #[cfg(test)]
@@ -184,4 +234,33 @@ mod tests {
         assert_eq!(cs.input_tokens, 0);
         assert_eq!(cs.output_tokens, 0);
     }
+
+    #[test]
+    fn test_ndjson_breakdown_by_task_and_worker() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("cost.ndjson");
+        let lines = [
+            r#"{"model":"claude-sonnet-4-5-20250929","worker":"task-1-a","task":"1","input_tokens":100,"output_tokens":10,"cache_read":1000,"cache_create":50,"cost_usd":0.5}"#,
+            r#"{"model":"claude-sonnet-4-5-20250929","worker":"task-1-a","task":"1","input_tokens":100,"output_tokens":10,"cache_read":0,"cache_create":0,"cost_usd":0.25}"#,
+            r#"{"model":"us.anthropic.claude-opus-4-20250514-v1:0","worker":"verifier","task":null,"input_tokens":0,"output_tokens":0,"cache_read":0,"cache_create":0,"cost_usd":1.0}"#,
+            // Legacy line without USD or attribution: priced at Sonnet rates
+            r#"{"input_tokens":1000000,"output_tokens":0,"cache_read":0,"cache_create":0}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let summary = parse_cost_from_ndjson(&path);
+        assert!((summary.total_cost_usd - 4.75).abs() < 1e-9);
+        assert_eq!(summary.input_tokens, 1_000_200);
+        assert_eq!(summary.cache_read_tokens, 1000);
+        assert_eq!(summary.cache_creation_tokens, 50);
+
+        assert_eq!(summary.by_task.len(), 1);
+        let task = &summary.by_task["1"];
+        assert!((task.cost_usd - 0.75).abs() < 1e-9);
+        assert_eq!(task.output_tokens, 20);
+        assert_eq!(summary.by_worker.len(), 2);
+        assert!((summary.by_worker["verifier"].cost_usd - 1.0).abs() < 1e-9);
+        assert_eq!(summary.by_worker["task-1-a"].task.as_deref(), Some("1"));
+        assert_eq!(summary.by_worker["verifier"].task, None);
+    }
 }
@@ -12,7 +12,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command as ProcessCommand, Output};
 use std::time::{Duration, Instant};
 
-use crate::types::{DroneStatus, LegacyJsonPlan, Plan};
+use crate::types::{BudgetLimits, DroneStatus, LegacyJsonPlan, Plan};
 
 pub use time::{
     duration_between, elapsed_since, format_duration, parse_timestamp, SECONDS_PER_HOUR,
@@ -45,12 +45,16 @@ pub fn load_prd(path: &Path) -> Option<Plan> {
                 .unwrap_or("unknown")
                 .to_string();
             let structured_tasks = crate::plan_parser::parse_tasks(&contents);
+            let quality_gates = crate::plan_parser::parse_quality_gates(&contents);
             Some(Plan {
                 id,
                 content: contents,
                 target_branch: None,
                 base_branch: None,
                 structured_tasks,
+                budget: BudgetLimits::default(),
+                quality_gates,
+                sandbox: Default::default(),
             })
         }
         "json" => {
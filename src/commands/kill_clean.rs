@@ -67,7 +67,7 @@ fn kill_impl(name: String, quiet: bool) -> Result<()> {
     let handle = SpawnHandle {
         pid: None,
         backend_id: status.worktree.clone(),
-        backend_type: status.backend.clone(),
+        backend_type: name.clone(),
     };
 
     if !quiet {
@@ -208,7 +208,7 @@ fn clean_impl(name: String, force: bool, quiet: bool) -> Result<()> {
             let handle = SpawnHandle {
                 pid: None,
                 backend_id: status.worktree.clone(),
-                backend_type: status.backend.clone(),
+                backend_type: name.clone(),
             };
             let _ = backend.stop(&handle);
         } else {
@@ -288,6 +288,26 @@ fn clean_impl(name: String, force: bool, quiet: bool) -> Result<()> {
 
     // 3. Remove drone directory LAST (point of no return)
     fs::remove_dir_all(&drone_dir).context("Failed to remove drone directory")?;
+    // Worker worktrees lived under the drone directory — drop their metadata
+    let _ = ProcessCommand::new("git")
+        .args(["worktree", "prune"])
+        .output();
+
+    // Delete per-worker branches left by the native team coordinator
+    let worker_refs = ProcessCommand::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname:short)",
+            &format!("refs/heads/hive/{name}-task-*"),
+        ])
+        .output();
+    if let Ok(out) = worker_refs {
+        for branch in String::from_utf8_lossy(&out.stdout).lines() {
+            let _ = ProcessCommand::new("git")
+                .args(["branch", "-D", branch])
+                .output();
+        }
+    }
     if !quiet {
         println!("  {} Removed drone state", "✓".green());
     }
@@ -1,9 +1,12 @@
+pub mod approval;
 pub mod common;
 pub mod dev;
 pub mod init;
 pub mod install;
 pub mod kill_clean;
 pub mod logs;
+pub mod pause;
+pub mod plan;
 pub mod profile;
 pub mod provider;
 pub mod setup;
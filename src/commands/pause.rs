@@ -0,0 +1,55 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::backend::native_team::control::{self, ControlRequest};
+use crate::types::{DroneState, DroneStatus};
+
+/// Ask a running drone to pause. Workers stop after their current API turn
+/// and save their conversations; the coordinator then exits.
+pub fn pause(name: String) -> Result<()> {
+    let drone_dir = PathBuf::from(".hive/drones").join(&name);
+    if !drone_dir.exists() {
+        bail!("Drone '{}' not found", name);
+    }
+
+    let response = control::send(&drone_dir, &ControlRequest::PauseDrone)?;
+    if let Some(error) = response.error {
+        bail!("Cannot pause drone '{}': {}", name, error);
+    }
+
+    println!(
+        "{} Pausing drone '{}' — workers stop after their current turn",
+        "→".bright_blue(),
+        name.bright_cyan()
+    );
+    println!("  Continue with: hive resume {}", name);
+    Ok(())
+}
+
+/// Restart a paused drone, continuing the saved worker conversations.
+/// `model` and `max_agents` override the values the drone was started with.
+pub fn resume(name: String, model: Option<String>, max_agents: Option<usize>) -> Result<()> {
+    let status_path = PathBuf::from(".hive/drones")
+        .join(&name)
+        .join("status.json");
+    let contents =
+        fs::read_to_string(&status_path).with_context(|| format!("Drone '{}' not found", name))?;
+    let status: DroneStatus = serde_json::from_str(&contents)?;
+    if status.status != DroneState::Paused {
+        bail!(
+            "Drone '{}' is {}, not paused. Use 'hive start {}' to restart it.",
+            name,
+            status.status,
+            name
+        );
+    }
+
+    // Drones started before these were recorded used the `hive start` defaults
+    let model = model
+        .or(status.model)
+        .unwrap_or_else(|| "sonnet".to_string());
+    let max_agents = max_agents.or(status.max_agents).unwrap_or(3);
+    crate::commands::start::run(name, status.local_mode, model, max_agents, false, None)
+}
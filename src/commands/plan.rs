@@ -0,0 +1,49 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+
+use crate::plan_parser::validate::{self, Diagnostic, Severity};
+
+use super::start::find_plan;
+
+/// `hive plan validate <name>`: report problems in a plan without launching it.
+pub fn validate(name: String) -> Result<()> {
+    let project_root = std::env::current_dir()?;
+    let path = find_plan(&name, &project_root)?;
+    let diagnostics = check_plan(&path)?;
+    if diagnostics.is_empty() {
+        println!("{} {} is valid", "✓".green(), path.display());
+    }
+    Ok(())
+}
+
+/// Validate a markdown plan, print its diagnostics and fail if any of them
+/// is an error. Legacy JSON plans are not checked.
+pub fn check_plan(path: &Path) -> Result<Vec<Diagnostic>> {
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).context("Failed to read plan")?;
+    let diagnostics = validate::validate_plan(&content);
+
+    for d in &diagnostics {
+        let label = match d.severity {
+            Severity::Error => "error".red().bold(),
+            Severity::Warning => "warning".yellow().bold(),
+        };
+        match d.line {
+            Some(line) => println!("  {label}: {}:{line}: {}", path.display(), d.message),
+            None => println!("  {label}: {}: {}", path.display(), d.message),
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("{} has {errors} error(s)", path.display());
+    }
+    Ok(diagnostics)
+}
@@ -21,6 +21,9 @@ pub struct Profile {
     pub provider: Provider,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bedrock: Option<BedrockConfig>,
+    /// Override for the Anthropic API endpoint (proxies, gateways, local mocks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anthropic_base_url: Option<String>,
     pub created: String,
     pub updated: String,
 }
@@ -34,6 +37,7 @@ impl Default for Profile {
             environment: None,
             provider: Provider::Anthropic,
             bedrock: None,
+            anthropic_base_url: None,
             created: chrono::Utc::now().to_rfc3339(),
             updated: chrono::Utc::now().to_rfc3339(),
         }
@@ -134,6 +138,7 @@ pub fn create(name: String) -> Result<()> {
         environment: None,
         provider: Provider::Anthropic,
         bedrock: None,
+        anthropic_base_url: None,
         created: chrono::Utc::now().to_rfc3339(),
         updated: chrono::Utc::now().to_rfc3339(),
     };
@@ -151,6 +156,7 @@ pub fn create(name: String) -> Result<()> {
     println!("  - description: Profile description");
     println!("  - claude_wrapper: Path to Claude wrapper script");
     println!("  - environment: Environment variables as key-value pairs");
+    println!("  - anthropic_base_url: Alternative Anthropic API endpoint");
 
     Ok(())
 }
@@ -11,7 +11,10 @@ use std::process::Command as ProcessCommand;
 use crate::backend::{self, SpawnConfig};
 use crate::commands::profile;
 use crate::config;
-use crate::types::{DroneState, DroneStatus, ExecutionMode};
+use crate::types::{
+    DroneState, DroneStatus, ExecutionMode, SandboxMode, DEFAULT_MAX_PROPOSED_TASKS,
+};
+use crate::webui::tools::sandbox::check_available;
 
 // Re-export submodule items (used by run() and tests via `use super::*`)
 #[allow(unused_imports)]
@@ -44,7 +47,13 @@ pub fn run(
         let pid_alive = crate::commands::common::read_drone_pid(&name)
             .map(crate::commands::common::is_process_running)
             .unwrap_or(false);
-        if pid_alive {
+        // A paused coordinator has exited, but from the web dashboard it
+        // shares (and leaves alive) the server's PID
+        let paused = fs::read_to_string(drone_dir.join("status.json"))
+            .ok()
+            .and_then(|c| serde_json::from_str::<DroneStatus>(&c).ok())
+            .is_some_and(|s| s.status == DroneState::Paused);
+        if pid_alive && !paused {
             bail!(
                 "Drone '{}' is already running. Use 'hive stop {}' first.",
                 name,
@@ -77,6 +86,7 @@ pub fn run(
 
     // 2. Find plan
     let prd_path = find_plan(&name, &project_root)?;
+    crate::commands::plan::check_plan(&prd_path)?;
     let prd = load_plan(&prd_path)?;
     println!("  {} Found plan: {}", "✓".green(), prd.title());
 
@@ -98,6 +108,58 @@ pub fn run(
         max_agents.to_string().bright_cyan()
     );
 
+    // Plan frontmatter budgets and gates take precedence over .hive/config.json
+    let local_config = config::load_local_config_at(&project_root).ok();
+    let budget = prd.budget.or(local_config
+        .as_ref()
+        .and_then(|c| c.budget)
+        .unwrap_or_default());
+    if !budget.is_unlimited() {
+        println!("  {} Budget: {}", "→".bright_blue(), budget);
+    }
+    let max_proposed_tasks = local_config
+        .as_ref()
+        .and_then(|c| c.max_proposed_tasks)
+        .unwrap_or(DEFAULT_MAX_PROPOSED_TASKS);
+    let triage = local_config.as_ref().is_some_and(|c| c.triage);
+    if triage {
+        println!("  {} Failure triage: on", "→".bright_blue());
+    }
+    let retry = local_config
+        .as_ref()
+        .map(|c| c.retry.clone())
+        .unwrap_or_default();
+    let task_limits = local_config
+        .as_ref()
+        .map(|c| c.task_limits)
+        .unwrap_or_default();
+    let sandbox = prd.sandbox.clone().or(&local_config
+        .as_ref()
+        .map(|c| c.sandbox.clone())
+        .unwrap_or_default());
+    if sandbox.mode() != SandboxMode::Off {
+        check_available(&sandbox)?;
+        let network = if sandbox.network() { "on" } else { "off" };
+        println!("  {} Sandbox: bwrap (network {network})", "→".bright_blue());
+    }
+    let persistent_shell = local_config.as_ref().is_some_and(|c| c.persistent_shell);
+    if persistent_shell {
+        println!("  {} Persistent shell: on", "→".bright_blue());
+    }
+    let quality_gates = if prd.quality_gates.is_empty() {
+        local_config.map(|c| c.quality_gates).unwrap_or_default()
+    } else {
+        prd.quality_gates.clone()
+    };
+    if !quality_gates.is_empty() {
+        let names: Vec<&str> = quality_gates.iter().map(|g| g.name.as_str()).collect();
+        println!(
+            "  {} Quality gates: {}",
+            "→".bright_blue(),
+            names.join(", ")
+        );
+    }
+
     // 4. Handle worktree creation
     let worktree_path = if local {
         project_root.clone()
@@ -147,6 +209,7 @@ pub fn run(
         )?;
         println!("  {} Created status.json", "✓".green());
     }
+    record_launch_settings(&status_path, &model, max_agents)?;
 
     // Ensure inbox/outbox directories exist for inter-drone messaging
     fs::create_dir_all(drone_dir.join("inbox"))?;
@@ -187,6 +250,15 @@ pub fn run(
             remote_url,
             project_languages,
             mode: "native".to_string(),
+            budget,
+            quality_gates,
+            max_proposed_tasks,
+            triage,
+            retry,
+            task_limits,
+            sandbox,
+            git_common_dir: None,
+            persistent_shell,
         };
 
         let handle = backend::resolve_backend().spawn(&spawn_config)?;
@@ -228,6 +300,22 @@ fn update_drone_status_resume(status_path: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// Record the model and agent limit of this run so `hive resume` can reuse them.
+fn record_launch_settings(
+    status_path: &std::path::Path,
+    model: &str,
+    max_agents: usize,
+) -> Result<()> {
+    if let Ok(contents) = fs::read_to_string(status_path) {
+        if let Ok(mut status) = serde_json::from_str::<DroneStatus>(&contents) {
+            status.model = Some(model.to_string());
+            status.max_agents = Some(max_agents);
+            fs::write(status_path, serde_json::to_string_pretty(&status)?)?;
+        }
+    }
+    Ok(())
+}
+
 fn create_drone_status(
     name: &str,
     prd_path: &std::path::Path,
@@ -257,6 +345,8 @@ fn create_drone_status(
         title: Some(plan.title().to_string()),
         description: plan.description(),
         lead_model: Some("opus".to_string()),
+        model: None,
+        max_agents: None,
         active_agents: Default::default(),
         phase: None,
     };
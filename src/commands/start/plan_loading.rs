@@ -2,7 +2,7 @@ use anyhow::{bail, Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::types::{LegacyJsonPlan, Plan};
+use crate::types::{BudgetLimits, LegacyJsonPlan, Plan, SandboxConfig};
 
 pub fn find_plan(name: &str, project_root: &Path) -> Result<PathBuf> {
     // Search in .hive/plans/ first, fall back to .hive/prds/ for backwards compat
@@ -73,18 +73,22 @@ pub fn load_plan(path: &Path) -> Result<Plan> {
                 .unwrap_or("unknown")
                 .to_string();
 
-            // Parse YAML frontmatter for target_branch/base_branch
-            let (target_branch, base_branch, content) = parse_frontmatter(&contents);
+            // Parse YAML frontmatter for branches and budgets
+            let (frontmatter, content) = parse_frontmatter(&contents)?;
 
             // Parse structured tasks from ## Tasks section
             let structured_tasks = crate::plan_parser::parse_tasks(&content);
+            let quality_gates = crate::plan_parser::parse_quality_gates(&content);
 
             Plan {
                 id,
                 content,
-                target_branch,
-                base_branch,
+                target_branch: frontmatter.target_branch,
+                base_branch: frontmatter.base_branch,
                 structured_tasks,
+                budget: frontmatter.budget,
+                quality_gates,
+                sandbox: frontmatter.sandbox,
             }
         }
         "json" => {
@@ -104,12 +108,22 @@ pub fn load_plan(path: &Path) -> Result<Plan> {
     Ok(plan)
 }
 
+/// Settings read from a markdown plan's YAML frontmatter.
+#[derive(Debug, Default)]
+pub struct Frontmatter {
+    pub target_branch: Option<String>,
+    pub base_branch: Option<String>,
+    pub budget: BudgetLimits,
+    pub sandbox: SandboxConfig,
+}
+
 /// Parse optional YAML frontmatter from markdown content.
-/// Returns (target_branch, base_branch, content_without_frontmatter).
-pub fn parse_frontmatter(raw: &str) -> (Option<String>, Option<String>, String) {
+/// Returns (frontmatter, content_without_frontmatter). Fails on a sandbox
+/// setting it can't read, so a typo never runs the drone unsandboxed.
+pub fn parse_frontmatter(raw: &str) -> Result<(Frontmatter, String)> {
     let trimmed = raw.trim_start();
     if !trimmed.starts_with("---") {
-        return (None, None, raw.to_string());
+        return Ok((Frontmatter::default(), raw.to_string()));
     }
 
     // Find the closing ---
@@ -118,22 +132,60 @@ pub fn parse_frontmatter(raw: &str) -> (Option<String>, Option<String>, String)
         let frontmatter = &after_opening[..end];
         let rest = &after_opening[end + 4..]; // skip \n---
 
-        let mut target_branch = None;
-        let mut base_branch = None;
+        let mut fm = Frontmatter::default();
 
         for line in frontmatter.lines() {
-            let line = line.trim();
-            if let Some(value) = line.strip_prefix("target_branch:") {
-                target_branch = Some(value.trim().to_string());
-            } else if let Some(value) = line.strip_prefix("base_branch:") {
-                base_branch = Some(value.trim().to_string());
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "target_branch" => fm.target_branch = Some(value.to_string()),
+                "base_branch" => fm.base_branch = Some(value.to_string()),
+                "budget_usd" => fm.budget.drone_usd = parse_amount(value),
+                "budget_tokens" => fm.budget.drone_tokens = parse_amount(value),
+                "task_budget_usd" => fm.budget.task_usd = parse_amount(value),
+                "task_budget_tokens" => fm.budget.task_tokens = parse_amount(value),
+                "sandbox" => {
+                    fm.sandbox.mode = Some(
+                        value
+                            .parse()
+                            .map_err(|e| anyhow::anyhow!("Invalid plan frontmatter: {e}"))?,
+                    )
+                }
+                "sandbox_network" => {
+                    fm.sandbox.network = Some(value.parse().with_context(|| {
+                        format!("Invalid plan frontmatter: sandbox_network '{value}' (expected true or false)")
+                    })?)
+                }
+                "sandbox_cpu_secs" => fm.sandbox.cpu_secs = parse_amount(value),
+                "sandbox_memory_mb" => fm.sandbox.memory_mb = parse_amount(value),
+                "sandbox_max_processes" => fm.sandbox.max_processes = parse_amount(value),
+                "sandbox_writable" => {
+                    fm.sandbox.writable = value
+                        .split(',')
+                        .map(|p| p.trim().to_string())
+                        .filter(|p| !p.is_empty())
+                        .collect()
+                }
+                _ => {}
             }
         }
 
         // Strip leading newline from rest
         let content = rest.strip_prefix('\n').unwrap_or(rest);
-        (target_branch, base_branch, content.to_string())
+        Ok((fm, content.to_string()))
     } else {
-        (None, None, raw.to_string())
+        Ok((Frontmatter::default(), raw.to_string()))
     }
 }
+
+/// Parse a budget amount, ignoring a leading `$` and `_`/`,` separators.
+fn parse_amount<T: std::str::FromStr>(value: &str) -> Option<T> {
+    let cleaned: String = value
+        .trim_start_matches('$')
+        .chars()
+        .filter(|c| *c != '_' && *c != ',')
+        .collect();
+    cleaned.parse().ok()
+}
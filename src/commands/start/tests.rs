@@ -109,3 +109,74 @@ fn test_write_hooks_config_async_and_timeout() {
         }
     }
 }
+
+#[test]
+fn test_frontmatter_budgets() {
+    let raw = "---\ntarget_branch: feat/x\nbudget_usd: $12.50\ntask_budget_tokens: 2_000_000\n---\n# Plan\n";
+    let (fm, content) = parse_frontmatter(raw).unwrap();
+    assert_eq!(fm.target_branch.as_deref(), Some("feat/x"));
+    assert_eq!(fm.budget.drone_usd, Some(12.5));
+    assert_eq!(fm.budget.task_tokens, Some(2_000_000));
+    assert_eq!(fm.budget.drone_tokens, None);
+    assert_eq!(content, "# Plan\n");
+}
+
+#[test]
+fn test_frontmatter_sandbox() {
+    let raw = "---\nsandbox: bwrap\nsandbox_network: false\nsandbox_memory_mb: 4_096\nsandbox_writable: ~/.cargo, ~/.npm\n---\n# Plan\n";
+    let (fm, _) = parse_frontmatter(raw).unwrap();
+    assert_eq!(fm.sandbox.mode, Some(crate::types::SandboxMode::Bwrap));
+    assert!(!fm.sandbox.network());
+    assert_eq!(fm.sandbox.memory_mb, Some(4096));
+    assert_eq!(fm.sandbox.cpu_secs, None);
+    assert_eq!(fm.sandbox.writable, vec!["~/.cargo", "~/.npm"]);
+}
+
+#[test]
+fn test_frontmatter_rejects_invalid_sandbox_settings() {
+    let err = parse_frontmatter("---\nsandbox: bwarp\n---\n# Plan\n").unwrap_err();
+    assert!(
+        err.to_string().contains("unknown sandbox mode 'bwarp'"),
+        "{err}"
+    );
+    assert!(parse_frontmatter("---\nsandbox_network: no\n---\n# Plan\n").is_err());
+
+    let dir = TempDir::new().unwrap();
+    let prd_path = dir.path().join("plan.md");
+    fs::write(&prd_path, "---\nsandbox: bwarp\n---\n# Plan\n").unwrap();
+    assert!(load_plan(&prd_path).is_err());
+}
+
+#[test]
+fn test_drone_status_records_model_and_max_agents() {
+    let dir = TempDir::new().unwrap();
+    let prd_path = dir.path().join("plan.md");
+    fs::write(&prd_path, "# Plan\n").unwrap();
+    let plan = load_plan(&prd_path).unwrap();
+    let status_path = dir.path().join("status.json");
+    let read = || -> DroneStatus {
+        serde_json::from_str(&fs::read_to_string(&status_path).unwrap()).unwrap()
+    };
+
+    create_drone_status(
+        "d",
+        &prd_path,
+        "hive/d",
+        dir.path(),
+        false,
+        &status_path,
+        &plan,
+    )
+    .unwrap();
+    record_launch_settings(&status_path, "opus", 5).unwrap();
+    let status = read();
+    assert_eq!(status.model.as_deref(), Some("opus"));
+    assert_eq!(status.max_agents, Some(5));
+
+    update_drone_status_resume(&status_path).unwrap();
+    record_launch_settings(&status_path, "haiku", 2).unwrap();
+    let status = read();
+    assert_eq!(status.status, DroneState::Resuming);
+    assert_eq!(status.model.as_deref(), Some("haiku"));
+    assert_eq!(status.max_agents, Some(2));
+}
@@ -91,6 +91,12 @@ pub fn format_drone(
         DroneState::Error => Some(format!(
             "{YELLOW}\u{1F41D} {name}{RESET} {BRIGHT_RED}\u{2717}{RESET} {completed}/{total}"
         )),
+        DroneState::BudgetExceeded => Some(format!(
+            "{YELLOW}\u{1F41D} {name}{RESET} {BRIGHT_RED}\u{26A0} budget{RESET} {completed}/{total}"
+        )),
+        DroneState::Paused => Some(format!(
+            "{YELLOW}\u{1F41D} {name}{RESET} {GRAY}\u{23F8} paused{RESET} {completed}/{total}"
+        )),
         _ => None,
     }
 }
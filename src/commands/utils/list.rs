@@ -2,7 +2,7 @@ use anyhow::Result;
 use colored::Colorize;
 
 use super::super::common::agent_teams_progress;
-use super::super::common::cost::parse_cost_from_log;
+use super::super::common::cost::{parse_cost_from_log, CostSummary};
 use crate::types::{DroneState, DroneStatus};
 
 /// List all drones with compact output
@@ -46,6 +46,8 @@ fn print_drone_row(name: &str, status: &DroneStatus) {
         DroneState::Stopped => "stopped".bright_black(),
         DroneState::Cleaning => "cleaning".bright_black(),
         DroneState::Zombie => "zombie".magenta(),
+        DroneState::BudgetExceeded => "over budget".red().bold(),
+        DroneState::Paused => "paused".yellow(),
     };
 
     // Get progress from Agent Teams task list (read-only, no write-back)
@@ -84,6 +86,26 @@ fn print_drone_row(name: &str, status: &DroneStatus) {
         format!("{} ({}%)", progress, percentage).bright_white(),
         cost_str.bright_black(),
     );
+
+    print_cost_breakdown(&cost);
+}
+
+/// Per-worker cost lines under a native-team drone, with the task each
+/// worker ran.
+fn print_cost_breakdown(cost: &CostSummary) {
+    for (worker, entry) in &cost.by_worker {
+        let task = entry
+            .task
+            .as_ref()
+            .map(|id| format!("task {id}"))
+            .unwrap_or_default();
+        println!(
+            "   {:<17} {:<31} {:<10}",
+            format!("└ {}", worker).bright_black(),
+            task.bright_black(),
+            format!("${:.2}", entry.cost_usd).bright_black(),
+        );
+    }
 }
 
 fn list_drones() -> Result<Vec<(String, DroneStatus)>> {
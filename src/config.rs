@@ -1,4 +1,4 @@
-use crate::types::HiveConfig;
+use crate::types::{ApiLimits, HiveConfig};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -197,9 +197,32 @@ pub fn get_model() -> String {
     "sonnet".to_string()
 }
 
+/// Get the machine-wide API limits with priority: ENV > global > default
+pub fn get_api_limits() -> ApiLimits {
+    let mut limits = load_global_config()
+        .map(|c| c.api_limits)
+        .unwrap_or_default();
+    if let Some(n) = env_number("HIVE_API_MAX_CONCURRENT") {
+        limits.max_concurrent = Some(n as usize);
+    }
+    if let Some(n) = env_number("HIVE_API_TOKENS_PER_MINUTE") {
+        limits.tokens_per_minute = Some(n);
+    }
+    limits
+}
+
+fn env_number(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.trim().parse().ok()
+}
+
 /// Load local config from .hive/config.json
 pub fn load_local_config() -> Result<HiveConfig> {
-    let config_path = PathBuf::from(".hive").join("config.json");
+    load_local_config_at(Path::new("."))
+}
+
+/// Load local config from `<project_root>/.hive/config.json`
+pub fn load_local_config_at(project_root: &Path) -> Result<HiveConfig> {
+    let config_path = project_root.join(".hive").join("config.json");
     let contents = std::fs::read_to_string(&config_path).context("Failed to read local config")?;
     let config: HiveConfig =
         serde_json::from_str(&contents).context("Failed to parse local config")?;
@@ -87,6 +87,24 @@ pub enum HiveEvent {
     QualityGateResult {
         ts: String,
         task_id: String,
+        /// Gate name from the plan or config (e.g. "clippy")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gate: Option<String>,
+        /// Gate command that was run
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        command: Option<String>,
+        passed: bool,
+        /// An advisory gate's failure doesn't block the task
+        #[serde(default)]
+        advisory: bool,
+        #[serde(default)]
+        output: String,
+    },
+    /// A setup task's `run:` command finished
+    SetupCommand {
+        ts: String,
+        task_id: String,
+        command: String,
         passed: bool,
         #[serde(default)]
         output: String,
@@ -97,6 +115,9 @@ pub enum HiveEvent {
         task_id: String,
         #[serde(default)]
         error_message: String,
+        /// How the failure was classified (`transient`, `credentials`, ...)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_class: Option<String>,
     },
     /// Phase transition in the coordinator lifecycle
     PhaseTransition {
@@ -104,6 +125,67 @@ pub enum HiveEvent {
         from_phase: String,
         to_phase: String,
     },
+    /// A worker tool call tried to write outside the task's `files:` patterns
+    FileOwnershipViolation {
+        ts: String,
+        task_id: String,
+        #[serde(default)]
+        agent: String,
+        tool: String,
+        path: String,
+    },
+    /// A worker's branch conflicted when merged back into the drone worktree
+    MergeConflict {
+        ts: String,
+        task_id: String,
+        branch: String,
+        #[serde(default)]
+        files: Vec<String>,
+        /// "resolved", "retrying" or "failed"
+        #[serde(default)]
+        resolution: String,
+    },
+    /// A drone or task budget was reached and its work was stopped
+    BudgetExceeded {
+        ts: String,
+        /// Absent for the drone-wide budget
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+        limit: String,
+        #[serde(default)]
+        spent_usd: f64,
+        #[serde(default)]
+        spent_tokens: u64,
+    },
+    /// Work is parked until a reviewer approves or rejects it
+    ApprovalRequested {
+        ts: String,
+        /// Absent for the gate before the PR phase
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+        title: String,
+    },
+    /// A reviewer approved or rejected parked work
+    ApprovalDecided {
+        ts: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+        approved: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    /// The triage agent decided what to do with a task that used up its retries
+    TriageDecision {
+        ts: String,
+        task_id: String,
+        /// "split", "rewrite", "skip" or "fail"
+        action: String,
+        #[serde(default)]
+        reason: String,
+        /// Tasks created by a split
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        new_tasks: Vec<String>,
+    },
 }
 
 /// A single todo item from Claude Code's TodoWrite tool.
@@ -132,8 +214,15 @@ impl HiveEvent {
             HiveEvent::ToolDone { ts, .. } => ts,
             HiveEvent::TodoSnapshot { ts, .. } => ts,
             HiveEvent::QualityGateResult { ts, .. } => ts,
+            HiveEvent::SetupCommand { ts, .. } => ts,
             HiveEvent::WorkerError { ts, .. } => ts,
             HiveEvent::PhaseTransition { ts, .. } => ts,
+            HiveEvent::FileOwnershipViolation { ts, .. } => ts,
+            HiveEvent::MergeConflict { ts, .. } => ts,
+            HiveEvent::BudgetExceeded { ts, .. } => ts,
+            HiveEvent::ApprovalRequested { ts, .. } => ts,
+            HiveEvent::ApprovalDecided { ts, .. } => ts,
+            HiveEvent::TriageDecision { ts, .. } => ts,
         }
     }
 }
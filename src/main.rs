@@ -13,6 +13,9 @@ struct Cli {
     #[arg(long, env = "HIVE_PORT", default_value = "3333")]
     port: u16,
 
+    #[command(flatten)]
+    serve: ServeArgs,
+
     /// Print version details
     #[arg(long = "version-info")]
     version_info: bool,
@@ -51,6 +54,8 @@ enum Commands {
         /// Port for the web dashboard
         #[arg(long, env = "HIVE_PORT", default_value = "3333")]
         port: u16,
+        #[command(flatten)]
+        serve: ServeArgs,
     },
 
     /// View drone activity logs
@@ -71,6 +76,54 @@ enum Commands {
         name: String,
     },
 
+    /// Pause a drone, saving each worker's conversation
+    Pause {
+        /// Drone name
+        name: String,
+    },
+
+    /// Resume a paused drone where its workers stopped
+    Resume {
+        /// Drone name
+        name: String,
+        /// Model to use (default: the model the drone was started with)
+        #[arg(long)]
+        model: Option<String>,
+        /// Maximum concurrent agents (default: the drone's original limit)
+        #[arg(long)]
+        max_agents: Option<usize>,
+    },
+
+    /// Show work awaiting approval, with its diff and worker notes
+    Review {
+        /// Drone name
+        name: String,
+    },
+
+    /// Approve a task's changes, or the PR when no task is given
+    Approve {
+        /// Drone name
+        name: String,
+        /// Plan task number
+        #[arg(long)]
+        task: Option<usize>,
+        /// Comment for the record
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
+    /// Reject a task's changes, or the PR when no task is given
+    Reject {
+        /// Drone name
+        name: String,
+        /// Plan task number
+        #[arg(long)]
+        task: Option<usize>,
+        /// Feedback for the worker; without it the work is dropped
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
     /// Remove worktree and clean up drone artifacts
     Clean {
         /// Drone name
@@ -86,6 +139,12 @@ enum Commands {
     /// Self-update via GitHub releases
     Update,
 
+    /// Work with plans
+    Plan {
+        #[command(subcommand)]
+        command: PlanCommands,
+    },
+
     /// Manage Claude wrapper profiles
     Profile {
         #[command(subcommand)]
@@ -125,6 +184,60 @@ enum Commands {
     Statusline,
 }
 
+/// Network and access options for the web dashboard.
+#[derive(clap::Args)]
+struct ServeArgs {
+    /// Address to bind the web dashboard to (use 0.0.0.0 to expose on the network)
+    #[arg(long, env = "HIVE_BIND", default_value = "127.0.0.1")]
+    bind: std::net::IpAddr,
+
+    /// Require this bearer token on all /api routes
+    #[arg(long, env = "HIVE_AUTH_TOKEN", conflicts_with = "auth_password")]
+    auth_token: Option<String>,
+
+    /// Generate a password at startup and require it on all /api routes
+    #[arg(long)]
+    auth_password: bool,
+
+    /// Additional origin allowed by CORS (repeatable)
+    #[arg(
+        long = "allow-origin",
+        env = "HIVE_ALLOWED_ORIGINS",
+        value_delimiter = ','
+    )]
+    allowed_origins: Vec<String>,
+}
+
+impl ServeArgs {
+    fn into_options(self, port: u16) -> hive_lib::webui::ServerOptions {
+        use hive_lib::webui::access::{generate_password, AccessMode};
+
+        let access = match (
+            self.auth_token.filter(|t| !t.is_empty()),
+            self.auth_password,
+        ) {
+            (Some(token), _) => AccessMode::Token(token),
+            (None, true) => AccessMode::Password(generate_password()),
+            (None, false) => AccessMode::Open,
+        };
+        hive_lib::webui::ServerOptions {
+            port,
+            bind: self.bind,
+            access,
+            allowed_origins: self.allowed_origins,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum PlanCommands {
+    /// Check a plan's task graph and metadata without launching it
+    Validate {
+        /// Plan name (as passed to `hive start`)
+        name: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum ProfileCommands {
     /// List available profiles
@@ -171,7 +284,7 @@ fn main() {
 
     // If no subcommand, launch web dashboard
     if cli.command.is_none() {
-        if let Err(e) = hive_lib::webui::run_server(cli.port) {
+        if let Err(e) = hive_lib::webui::run_server(cli.serve.into_options(cli.port)) {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
@@ -197,8 +310,12 @@ fn main() {
                 std::process::exit(1);
             }
         }
-        Commands::Monitor { name: _, port } => {
-            if let Err(e) = hive_lib::webui::run_server(port) {
+        Commands::Monitor {
+            name: _,
+            port,
+            serve,
+        } => {
+            if let Err(e) = hive_lib::webui::run_server(serve.into_options(port)) {
                 eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
@@ -219,6 +336,48 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Pause { name } => {
+            if let Err(e) = commands::pause::pause(name) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Resume {
+            name,
+            model,
+            max_agents,
+        } => {
+            if let Err(e) = commands::pause::resume(name, model, max_agents) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Review { name } => {
+            if let Err(e) = commands::approval::review(name) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Approve {
+            name,
+            task,
+            comment,
+        } => {
+            if let Err(e) = commands::approval::approve(name, task, comment) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Reject {
+            name,
+            task,
+            comment,
+        } => {
+            if let Err(e) = commands::approval::reject(name, task, comment) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::Clean { name, force } => {
             if let Err(e) = commands::kill_clean::clean(name, force) {
                 eprintln!("Error: {}", e);
@@ -237,6 +396,15 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Plan { command } => {
+            let result = match command {
+                PlanCommands::Validate { name } => commands::plan::validate(name),
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::Profile { command } => {
             let result = match command {
                 ProfileCommands::List => commands::profile::list(),
@@ -2,6 +2,7 @@ use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::backend::native_team::control::{self, ControlRequest};
 use crate::commands::common::{agent_teams_progress, list_drones};
 
 #[derive(Debug, Serialize)]
@@ -79,6 +80,41 @@ pub fn list_tools() -> Vec<ToolInfo> {
                 "required": ["drone_name"]
             }),
         },
+        ToolInfo {
+            name: "hive_control_drone".to_string(),
+            description: "Send a command to a running native-team drone: status, abort_drone, abort_task, retry_task, set_max_agents, pause, resume, pause_drone, approve, reject or inject_message.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "drone_name": {
+                        "type": "string",
+                        "description": "Name of the drone to control"
+                    },
+                    "cmd": {
+                        "type": "string",
+                        "enum": ["status", "abort_drone", "abort_task", "retry_task", "set_max_agents", "pause", "resume", "pause_drone", "approve", "reject", "inject_message"],
+                        "description": "Command to send"
+                    },
+                    "task": {
+                        "type": "integer",
+                        "description": "Plan task number (abort_task, retry_task, inject_message; approve and reject, where omitting it targets the PR)"
+                    },
+                    "comment": {
+                        "type": "string",
+                        "description": "Reviewer comment (approve, reject). A rejection without one drops the work"
+                    },
+                    "max_agents": {
+                        "type": "integer",
+                        "description": "New concurrency limit (set_max_agents)"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "Message for the task's worker (inject_message)"
+                    }
+                },
+                "required": ["drone_name", "cmd"]
+            }),
+        },
     ]
 }
 
@@ -88,6 +124,7 @@ pub fn call_tool(name: &str, arguments: &Value) -> ToolResult {
         "hive_drone_status" => tool_drone_status(arguments),
         "hive_drone_progress" => tool_drone_progress(arguments),
         "hive_team_status" => tool_team_status(arguments),
+        "hive_control_drone" => tool_control_drone(arguments),
         _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
     };
 
@@ -214,3 +251,28 @@ fn tool_team_status(args: &Value) -> Result<String> {
         "tasks": task_details,
     }))?)
 }
+
+fn tool_control_drone(args: &Value) -> Result<String> {
+    let drone_name = args
+        .get("drone_name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required parameter: drone_name"))?;
+
+    // The remaining arguments are the request itself, tagged by `cmd`
+    let mut request = args.clone();
+    if let Some(fields) = request.as_object_mut() {
+        fields.remove("drone_name");
+    }
+    let request: ControlRequest = serde_json::from_value(request)
+        .map_err(|e| anyhow::anyhow!("Invalid control command: {}", e))?;
+
+    let drone_dir = std::path::PathBuf::from(".hive/drones").join(drone_name);
+    let response = control::send(&drone_dir, &request)?;
+    if let Some(error) = response.error {
+        anyhow::bail!(error);
+    }
+    if response.data.is_null() {
+        return Ok("ok".to_string());
+    }
+    Ok(serde_json::to_string_pretty(&response.data)?)
+}
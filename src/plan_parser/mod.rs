@@ -1,33 +1,20 @@
-use crate::types::{StructuredTask, TaskType};
+use crate::types::{
+    ErrorClass, QualityGateSpec, RetryPolicy, StructuredTask, TaskLimits, TaskType,
+};
+
+pub mod validate;
+
+pub use validate::{validate_plan, Diagnostic, Severity};
 
 /// Parse the `## Tasks` section from a markdown plan into structured tasks.
 ///
 /// Returns an empty vec if no `## Tasks` section or `### N. Title` subsections found.
 pub fn parse_tasks(content: &str) -> Vec<StructuredTask> {
     let lines: Vec<&str> = content.lines().collect();
-
-    // Find the `## Tasks` heading
-    let Some(tasks_start) = lines
-        .iter()
-        .position(|line| line.trim().eq_ignore_ascii_case("## tasks"))
-    else {
+    let Some(task_lines) = section(&lines, "## tasks") else {
         return Vec::new();
     };
 
-    // Find the end of the ## Tasks section (next ## heading or EOF)
-    let tasks_end = lines
-        .iter()
-        .enumerate()
-        .skip(tasks_start + 1)
-        .find(|(_, line)| {
-            let trimmed = line.trim();
-            trimmed.starts_with("## ") && !trimmed.starts_with("### ")
-        })
-        .map(|(i, _)| i)
-        .unwrap_or(lines.len());
-
-    let task_lines = &lines[tasks_start + 1..tasks_end];
-
     // Split on ### N. headings
     let mut task_ranges: Vec<(usize, &str)> = Vec::new();
 
@@ -62,6 +49,83 @@ pub fn parse_tasks(content: &str) -> Vec<StructuredTask> {
     tasks
 }
 
+/// Parse the `## Quality Gates` section into gate specs, in order.
+///
+/// Each gate is one bullet: ``- name: `command` (timeout: 300, advisory)``.
+/// The options in parentheses are optional; gates are required and time
+/// out after 120s by default. Bullets without a backticked command are
+/// ignored.
+pub fn parse_quality_gates(content: &str) -> Vec<QualityGateSpec> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(gate_lines) = section(&lines, "## quality gates") else {
+        return Vec::new();
+    };
+    gate_lines
+        .iter()
+        .filter_map(|line| parse_gate_line(line))
+        .collect()
+}
+
+/// Lines between a `## Heading` (matched case-insensitively) and the next
+/// `## ` heading or EOF.
+fn section<'a>(lines: &'a [&'a str], heading: &str) -> Option<&'a [&'a str]> {
+    let start = lines
+        .iter()
+        .position(|line| line.trim().eq_ignore_ascii_case(heading))?;
+    let end = lines
+        .iter()
+        .enumerate()
+        .skip(start + 1)
+        .find(|(_, line)| line.trim().starts_with("## "))
+        .map(|(i, _)| i)
+        .unwrap_or(lines.len());
+    Some(&lines[start + 1..end])
+}
+
+/// Parse a ``- name: `command` (options)`` gate bullet.
+fn parse_gate_line(line: &str) -> Option<QualityGateSpec> {
+    let rest = line.trim().strip_prefix("- ")?;
+    let (name, rest) = rest.split_once(':')?;
+    let rest = rest.trim().strip_prefix('`')?;
+    let (command, options) = rest.split_once('`')?;
+
+    let mut gate = QualityGateSpec {
+        name: name.trim().to_string(),
+        command: command.trim().to_string(),
+        timeout_secs: 120,
+        required: true,
+    };
+    if gate.name.is_empty() || gate.command.is_empty() {
+        return None;
+    }
+
+    let options = options.trim().trim_start_matches('(').trim_end_matches(')');
+    for option in options.split(',').map(str::trim) {
+        match option.split_once(':') {
+            Some((key, value)) if key.trim().eq_ignore_ascii_case("timeout") => {
+                if let Some(secs) = parse_duration_secs(value.trim()) {
+                    gate.timeout_secs = secs;
+                }
+            }
+            _ if option.eq_ignore_ascii_case("advisory") => gate.required = false,
+            _ if option.eq_ignore_ascii_case("required") => gate.required = true,
+            _ => {}
+        }
+    }
+    Some(gate)
+}
+
+/// Parse `300`, `300s`, `5m` or `1h` into seconds.
+fn parse_duration_secs(value: &str) -> Option<u64> {
+    if let Some(hours) = value.strip_suffix('h') {
+        return hours.trim().parse::<u64>().ok().map(|h| h * 3600);
+    }
+    if let Some(mins) = value.strip_suffix('m') {
+        return mins.trim().parse::<u64>().ok().map(|m| m * 60);
+    }
+    value.strip_suffix('s').unwrap_or(value).trim().parse().ok()
+}
+
 /// Parse a `### N. Title` heading, returning (number, title) or None.
 fn parse_task_heading(line: &str) -> Option<(usize, String)> {
     let trimmed = line.trim();
@@ -88,6 +152,13 @@ fn parse_single_task(number: usize, title: String, lines: &[&str]) -> Structured
     let mut parallel = true;
     let mut files = Vec::new();
     let mut depends_on = Vec::new();
+    let mut priority = 0;
+    let mut resource = None;
+    let mut run = Vec::new();
+    let mut verify = Vec::new();
+    let mut approval = false;
+    let mut retry = RetryPolicy::default();
+    let mut limits = TaskLimits::default();
     let mut body_lines = Vec::new();
     let mut in_metadata = true;
 
@@ -142,6 +213,68 @@ fn parse_single_task(number: usize, title: String, lines: &[&str]) -> Structured
                                 .collect();
                             continue;
                         }
+                        "priority" => {
+                            if let Ok(p) = value.parse::<i32>() {
+                                priority = p;
+                                continue;
+                            }
+                        }
+                        "run" | "verify" => {
+                            let command = value.trim_matches('`').trim();
+                            if !command.is_empty() {
+                                let list = if key == "run" { &mut run } else { &mut verify };
+                                list.push(command.to_string());
+                            }
+                            continue;
+                        }
+                        "resource" => {
+                            if !value.is_empty() {
+                                resource = Some(value.to_lowercase());
+                            }
+                            continue;
+                        }
+                        "approval" => {
+                            approval = value.eq_ignore_ascii_case("required");
+                            continue;
+                        }
+                        "max_attempts" => {
+                            if let Ok(n) = value.parse::<usize>() {
+                                if n >= 1 {
+                                    retry.max_attempts = Some(n);
+                                    continue;
+                                }
+                            }
+                        }
+                        "backoff" => {
+                            if let Some(secs) = parse_duration_secs(value) {
+                                retry.backoff_secs = Some(secs);
+                                continue;
+                            }
+                        }
+                        "escalate_model" => {
+                            if !value.is_empty() {
+                                retry.escalate_model = Some(value.to_string());
+                            }
+                            continue;
+                        }
+                        "timeout" => {
+                            if let Some(secs) = parse_duration_secs(value) {
+                                limits.timeout_secs = Some(secs);
+                                continue;
+                            }
+                        }
+                        "max_turns" => {
+                            if let Ok(n) = value.parse::<usize>() {
+                                if n >= 1 {
+                                    limits.max_turns = Some(n);
+                                    continue;
+                                }
+                            }
+                        }
+                        "permanent_errors" => {
+                            retry.permanent = Some(parse_error_classes(value));
+                            continue;
+                        }
                         _ => {} // Not a recognized metadata key — treat as body
                     }
                 }
@@ -166,8 +299,25 @@ fn parse_single_task(number: usize, title: String, lines: &[&str]) -> Structured
         parallel,
         files,
         depends_on,
+        priority,
+        resource,
+        run,
+        verify,
+        approval,
+        retry,
+        limits,
     }
 }
 
+/// Parse a comma-separated list of error classes; `none` gives an empty list.
+/// Unknown names are dropped here and reported by validation.
+pub(crate) fn parse_error_classes(value: &str) -> Vec<ErrorClass> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter_map(|s| ErrorClass::parse(&s))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests;
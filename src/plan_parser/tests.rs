@@ -207,3 +207,183 @@ fn test_parse_mixed_metadata_and_body_bullets() {
     assert!(tasks[0].body.contains("Install the dependency"));
     assert!(tasks[0].body.contains("Handle edge cases"));
 }
+
+#[test]
+fn test_parse_priority_and_resource() {
+    let content = r#"## Tasks
+
+### 1. Migrate users table
+- priority: 2
+- resource: DB
+
+### 2. Migrate orders table
+- resource: db
+- priority: soon
+"#;
+    let tasks = parse_tasks(content);
+    assert_eq!(tasks[0].priority, 2);
+    assert_eq!(tasks[0].resource.as_deref(), Some("db"));
+    assert_eq!(tasks[1].resource, tasks[0].resource);
+    // An unparseable priority is left in the body
+    assert_eq!(tasks[1].priority, 0);
+    assert!(tasks[1].body.contains("priority: soon"));
+}
+
+#[test]
+fn test_parse_quality_gates() {
+    let content = r#"# Plan
+
+## Quality Gates
+
+- fmt: `cargo fmt --check`
+- clippy: `cargo clippy --all-targets -- -D warnings` (timeout: 5m)
+- unit: `npx vitest run {files}` (timeout: 600s, required)
+- links: `./scripts/check-links.sh` (advisory)
+- not a gate
+Some prose.
+
+## Tasks
+
+### 1. Do it
+- files: src/a.rs
+"#;
+    let gates = parse_quality_gates(content);
+    assert_eq!(gates.len(), 4);
+    assert_eq!(gates[0].name, "fmt");
+    assert_eq!(gates[0].command, "cargo fmt --check");
+    assert_eq!(gates[0].timeout_secs, 120);
+    assert!(gates[0].required);
+    assert_eq!(gates[1].timeout_secs, 300);
+    assert_eq!(gates[2].command, "npx vitest run {files}");
+    assert_eq!(gates[2].timeout_secs, 600);
+    assert!(gates[2].required);
+    assert_eq!(gates[3].name, "links");
+    assert!(!gates[3].required);
+
+    // The tasks section is unaffected
+    assert_eq!(parse_tasks(content).len(), 1);
+    assert!(parse_quality_gates("# Plan\n\n## Tasks\n").is_empty());
+}
+
+#[test]
+fn test_parse_setup_run_commands() {
+    let content = r#"## Tasks
+
+### 1. Environment setup
+- type: setup
+- run: `pnpm install --frozen-lockfile`
+- run: cp .env.example .env
+- run: pnpm build
+
+Make sure the baseline builds.
+"#;
+    let tasks = parse_tasks(content);
+    assert_eq!(tasks[0].task_type, TaskType::Setup);
+    assert_eq!(
+        tasks[0].run,
+        vec![
+            "pnpm install --frozen-lockfile",
+            "cp .env.example .env",
+            "pnpm build"
+        ]
+    );
+    assert_eq!(tasks[0].body, "Make sure the baseline builds.");
+}
+
+#[test]
+fn test_parse_verify_commands() {
+    let content = r#"## Tasks
+
+### 2. Auth API
+- files: src/auth.rs
+- verify: `cargo test -p auth`
+- verify: ./scripts/check_api.sh
+
+Build it.
+"#;
+    let tasks = parse_tasks(content);
+    assert_eq!(
+        tasks[0].verify,
+        vec!["cargo test -p auth", "./scripts/check_api.sh"]
+    );
+    assert!(tasks[0].run.is_empty());
+}
+
+#[test]
+fn test_parse_approval_gate() {
+    let content = r#"## Tasks
+
+### 1. Rotate signing keys
+- files: src/crypto/keys.rs
+- approval: required
+
+Swap in the new key pair.
+
+### 2. Open PR
+- type: pr
+- approval: Required
+
+### 3. Docs
+- approval: none
+"#;
+    let tasks = parse_tasks(content);
+    assert!(tasks[0].approval);
+    assert!(tasks[1].approval);
+    assert!(!tasks[2].approval);
+    assert_eq!(tasks[0].body, "Swap in the new key pair.");
+}
+
+#[test]
+fn test_parse_retry_policy() {
+    let content = r#"## Tasks
+
+### 1. Migrate schema
+- max_attempts: 5
+- backoff: 2m
+- escalate_model: opus
+- permanent_errors: credentials, Blocked
+
+Add the column.
+
+### 2. Docs
+- permanent_errors: none
+
+### 3. Tests
+"#;
+    let tasks = parse_tasks(content);
+    let retry = &tasks[0].retry;
+    assert_eq!(retry.max_attempts, Some(5));
+    assert_eq!(retry.backoff_secs, Some(120));
+    assert_eq!(retry.escalate_model.as_deref(), Some("opus"));
+    assert_eq!(
+        retry.permanent,
+        Some(vec![ErrorClass::Credentials, ErrorClass::Blocked])
+    );
+    assert_eq!(tasks[0].body, "Add the column.");
+    assert_eq!(tasks[1].retry.permanent, Some(Vec::new()));
+    assert!(tasks[2].retry.is_default());
+}
+
+#[test]
+fn test_parse_task_limits() {
+    let content = r#"## Tasks
+
+### 1. Port the parser
+- timeout: 1h
+- max_turns: 80
+
+### 2. Docs
+- timeout: 90s
+
+### 3. Tests
+- max_turns: zero
+"#;
+    let tasks = parse_tasks(content);
+    assert_eq!(tasks[0].limits.timeout_secs, Some(3600));
+    assert_eq!(tasks[0].limits.max_turns, Some(80));
+    assert_eq!(tasks[1].limits.timeout_secs, Some(90));
+    assert_eq!(tasks[1].limits.max_turns, None);
+    // Bad values fall through to the body, where validation reports them
+    assert!(tasks[2].limits.is_default());
+    assert_eq!(tasks[2].body, "- max_turns: zero");
+}
@@ -0,0 +1,574 @@
+//! Structural and metadata checks for markdown plans.
+//!
+//! `parse_tasks` is lenient: bad metadata values are coerced and unknown
+//! dependencies are treated as external. `validate_plan` reports those
+//! problems instead, with 1-based line numbers into the plan file.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::{parse_duration_secs, parse_task_heading, parse_tasks};
+use crate::types::{ErrorClass, StructuredTask, TaskType};
+use crate::webui::anthropic::model::is_known_model;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The plan would misbehave if launched
+    Error,
+    /// Likely a mistake, but the plan can run
+    Warning,
+}
+
+/// One problem found in a plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line in the plan file, if the problem has one
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Metadata keys understood by `parse_single_task`.
+const METADATA_KEYS: &[&str] = &[
+    "type",
+    "model",
+    "parallel",
+    "files",
+    "depends_on",
+    "priority",
+    "resource",
+    "run",
+    "verify",
+    "approval",
+    "max_attempts",
+    "backoff",
+    "escalate_model",
+    "permanent_errors",
+    "timeout",
+    "max_turns",
+];
+
+/// Where a task's heading and metadata bullets sit in the file.
+struct TaskSite {
+    number: usize,
+    heading: usize,
+    /// key → (line, raw value)
+    metadata: HashMap<String, (usize, String)>,
+}
+
+impl TaskSite {
+    fn is_setup(&self) -> bool {
+        self.has_type("setup")
+    }
+
+    fn is_pr(&self) -> bool {
+        self.has_type("pr")
+    }
+
+    fn has_type(&self, task_type: &str) -> bool {
+        self.metadata
+            .get("type")
+            .is_some_and(|(_, value)| value.eq_ignore_ascii_case(task_type))
+    }
+
+    fn line_of(&self, key: &str) -> usize {
+        self.metadata
+            .get(key)
+            .map(|(line, _)| *line)
+            .unwrap_or(self.heading)
+    }
+}
+
+/// Validate a markdown plan (including any frontmatter, so that line
+/// numbers match the file). Diagnostics are sorted by line.
+pub fn validate_plan(content: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = content.lines().collect();
+    let tasks = parse_tasks(content);
+    let sites = scan_task_sites(&lines);
+    let mut out = Vec::new();
+
+    if tasks.is_empty() {
+        out.push(warning(
+            None,
+            "no structured tasks found (expected `### N. Title` headings under `## Tasks`)",
+        ));
+        return out;
+    }
+
+    // First site per task number; later ones are duplicates
+    let mut by_number: HashMap<usize, &TaskSite> = HashMap::new();
+    for site in &sites {
+        if let Some(first) = by_number.get(&site.number) {
+            out.push(error(
+                Some(site.heading),
+                format!(
+                    "duplicate task number {} (first defined on line {})",
+                    site.number, first.heading
+                ),
+            ));
+        } else {
+            by_number.insert(site.number, site);
+        }
+        check_metadata_values(site, &mut out);
+    }
+
+    check_dependencies(&tasks, &by_number, &mut out);
+    check_task_types(&tasks, &lines, &mut out);
+    check_file_overlaps(&tasks, &by_number, &mut out);
+
+    out.sort_by_key(|d| d.line.unwrap_or(0));
+    out
+}
+
+/// Find each task heading and the metadata bullets that follow it, using
+/// the same boundaries as `parse_tasks`.
+fn scan_task_sites(lines: &[&str]) -> Vec<TaskSite> {
+    let mut sites: Vec<TaskSite> = Vec::new();
+    let mut in_tasks = false;
+    let mut in_metadata = false;
+
+    for (idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("## ") {
+            in_tasks = trimmed.eq_ignore_ascii_case("## tasks");
+            in_metadata = false;
+            continue;
+        }
+        if !in_tasks {
+            continue;
+        }
+        if let Some((number, _)) = parse_task_heading(line) {
+            sites.push(TaskSite {
+                number,
+                heading: idx + 1,
+                metadata: HashMap::new(),
+            });
+            in_metadata = true;
+            continue;
+        }
+        if !in_metadata {
+            continue;
+        }
+        let bullet = trimmed
+            .strip_prefix("- ")
+            .and_then(|rest| rest.split_once(':'))
+            .map(|(key, value)| (key.trim().to_lowercase(), value.trim()))
+            .filter(|(key, _)| METADATA_KEYS.contains(&key.as_str()));
+        match (bullet, sites.last_mut()) {
+            (Some((key, value)), Some(site)) => {
+                site.metadata.insert(key, (idx + 1, value.to_string()));
+            }
+            _ => in_metadata = false,
+        }
+    }
+    sites
+}
+
+fn check_metadata_values(site: &TaskSite, out: &mut Vec<Diagnostic>) {
+    for (key, (line, value)) in &site.metadata {
+        let line = Some(*line);
+        let task = site.number;
+        match key.as_str() {
+            "type" if !matches!(value.to_lowercase().as_str(), "setup" | "pr" | "work") => {
+                out.push(error(
+                    line,
+                    format!("task {task}: unknown type `{value}` (expected setup, pr or work)"),
+                ));
+            }
+            "parallel" if !matches!(value.to_lowercase().as_str(), "true" | "false") => {
+                out.push(error(
+                    line,
+                    format!("task {task}: `parallel: {value}` must be true or false"),
+                ));
+            }
+            "priority" if value.parse::<i32>().is_err() => {
+                out.push(error(
+                    line,
+                    format!("task {task}: `priority: {value}` is not an integer"),
+                ));
+            }
+            "model" if !is_known_model(value) => {
+                out.push(error(
+                    line,
+                    format!("task {task}: unknown model `{value}` (use opus, sonnet or haiku)"),
+                ));
+            }
+            "run" if !site.is_setup() => {
+                out.push(warning(
+                    line,
+                    format!(
+                        "task {task}: `run:` commands are only executed for `type: setup` tasks"
+                    ),
+                ));
+            }
+            "verify" if site.is_setup() || site.is_pr() => {
+                out.push(warning(
+                    line,
+                    format!("task {task}: `verify:` commands are only executed for work tasks"),
+                ));
+            }
+            "approval" if !matches!(value.to_lowercase().as_str(), "required" | "none") => {
+                out.push(error(
+                    line,
+                    format!("task {task}: `approval: {value}` must be required or none"),
+                ));
+            }
+            "approval" if site.is_setup() => {
+                out.push(warning(
+                    line,
+                    format!("task {task}: `approval:` only gates work tasks and the PR task"),
+                ));
+            }
+            "max_attempts" if !value.parse::<usize>().is_ok_and(|n| n >= 1) => {
+                out.push(error(
+                    line,
+                    format!(
+                        "task {task}: `max_attempts: {value}` must be a whole number of at least 1"
+                    ),
+                ));
+            }
+            "max_turns" if !value.parse::<usize>().is_ok_and(|n| n >= 1) => {
+                out.push(error(
+                    line,
+                    format!(
+                        "task {task}: `max_turns: {value}` must be a whole number of at least 1"
+                    ),
+                ));
+            }
+            "timeout" if parse_duration_secs(value).is_none_or(|secs| secs == 0) => {
+                out.push(error(
+                    line,
+                    format!("task {task}: `timeout: {value}` is not a duration (e.g. 30m or 1h)"),
+                ));
+            }
+            "backoff" if parse_duration_secs(value).is_none() => {
+                out.push(error(
+                    line,
+                    format!("task {task}: `backoff: {value}` is not a duration (e.g. 30s or 2m)"),
+                ));
+            }
+            "escalate_model" if !is_known_model(value) => {
+                out.push(error(
+                    line,
+                    format!(
+                        "task {task}: unknown escalate_model `{value}` (use opus, sonnet or haiku)"
+                    ),
+                ));
+            }
+            "permanent_errors" if !value.eq_ignore_ascii_case("none") => {
+                for class in value.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+                    if ErrorClass::parse(&class.to_lowercase()).is_none() {
+                        let known: Vec<&str> = ErrorClass::ALL.iter().map(|c| c.as_str()).collect();
+                        out.push(error(
+                            line,
+                            format!(
+                                "task {task}: unknown error class `{class}` in permanent_errors (expected {})",
+                                known.join(", ")
+                            ),
+                        ));
+                    }
+                }
+            }
+            "depends_on" => {
+                for dep in value.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+                    if dep.parse::<usize>().is_err() {
+                        out.push(error(
+                            line,
+                            format!("task {task}: `{dep}` in depends_on is not a task number"),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_dependencies(
+    tasks: &[StructuredTask],
+    sites: &HashMap<usize, &TaskSite>,
+    out: &mut Vec<Diagnostic>,
+) {
+    let known: HashSet<usize> = tasks.iter().map(|t| t.number).collect();
+    let line_of = |number: usize, key: &str| sites.get(&number).map(|s| s.line_of(key));
+
+    for task in tasks {
+        for dep in &task.depends_on {
+            if !known.contains(dep) {
+                out.push(error(
+                    line_of(task.number, "depends_on"),
+                    format!("task {} depends on unknown task {dep}", task.number),
+                ));
+            }
+        }
+    }
+
+    for cycle in find_cycles(tasks) {
+        let path: Vec<String> = cycle.iter().map(|n| n.to_string()).collect();
+        out.push(error(
+            line_of(cycle[0], "depends_on"),
+            format!("dependency cycle: {} → {}", path.join(" → "), cycle[0]),
+        ));
+    }
+}
+
+/// Each dependency cycle once, as the task numbers along it.
+fn find_cycles(tasks: &[StructuredTask]) -> Vec<Vec<usize>> {
+    // Duplicate numbers are reported separately; follow the first definition
+    let mut deps: HashMap<usize, &[usize]> = HashMap::new();
+    for task in tasks {
+        deps.entry(task.number).or_insert(&task.depends_on);
+    }
+
+    fn visit(
+        number: usize,
+        deps: &HashMap<usize, &[usize]>,
+        stack: &mut Vec<usize>,
+        done: &mut HashSet<usize>,
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        if let Some(pos) = stack.iter().position(|&n| n == number) {
+            cycles.push(stack[pos..].to_vec());
+            return;
+        }
+        if !done.insert(number) {
+            return;
+        }
+        stack.push(number);
+        for &dep in deps.get(&number).copied().unwrap_or_default() {
+            visit(dep, deps, stack, done, cycles);
+        }
+        stack.pop();
+    }
+
+    let mut cycles = Vec::new();
+    let mut done = HashSet::new();
+    for task in tasks {
+        visit(task.number, &deps, &mut Vec::new(), &mut done, &mut cycles);
+    }
+    cycles
+}
+
+fn check_task_types(tasks: &[StructuredTask], lines: &[&str], out: &mut Vec<Diagnostic>) {
+    let tasks_heading = lines
+        .iter()
+        .position(|l| l.trim().eq_ignore_ascii_case("## tasks"))
+        .map(|i| i + 1);
+    if !tasks.iter().any(|t| t.task_type == TaskType::Setup) {
+        out.push(warning(
+            tasks_heading,
+            "no `type: setup` task (plans should start with one)",
+        ));
+    }
+    if !tasks.iter().any(|t| t.task_type == TaskType::Pr) {
+        out.push(warning(
+            tasks_heading,
+            "no `type: pr` task (plans should end with one)",
+        ));
+    }
+}
+
+/// Warn when two work tasks that may run at the same time own the same
+/// file (or one owns a directory containing the other's file).
+fn check_file_overlaps(
+    tasks: &[StructuredTask],
+    sites: &HashMap<usize, &TaskSite>,
+    out: &mut Vec<Diagnostic>,
+) {
+    let work: Vec<&StructuredTask> = tasks
+        .iter()
+        .filter(|t| t.task_type == TaskType::Work && t.parallel && !t.files.is_empty())
+        .collect();
+    let ancestors: HashMap<usize, HashSet<usize>> = work
+        .iter()
+        .map(|t| (t.number, ancestors_of(t.number, tasks)))
+        .collect();
+
+    for (i, a) in work.iter().enumerate() {
+        for b in &work[i + 1..] {
+            let ordered = ancestors[&a.number].contains(&b.number)
+                || ancestors[&b.number].contains(&a.number);
+            let serialized = a.resource.is_some() && a.resource == b.resource;
+            if ordered || serialized {
+                continue;
+            }
+            let shared: Vec<&str> = a
+                .files
+                .iter()
+                .filter(|fa| b.files.iter().any(|fb| paths_overlap(fa, fb)))
+                .map(String::as_str)
+                .collect();
+            if !shared.is_empty() {
+                out.push(warning(
+                    sites.get(&b.number).map(|s| s.line_of("files")),
+                    format!(
+                        "tasks {} and {} may run in parallel but both own {}; add depends_on or a shared resource",
+                        a.number,
+                        b.number,
+                        shared.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Every task `number` transitively depends on.
+fn ancestors_of(number: usize, tasks: &[StructuredTask]) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![number];
+    while let Some(n) = stack.pop() {
+        let Some(task) = tasks.iter().find(|t| t.number == n) else {
+            continue;
+        };
+        for &dep in &task.depends_on {
+            if seen.insert(dep) {
+                stack.push(dep);
+            }
+        }
+    }
+    seen
+}
+
+fn paths_overlap(a: &str, b: &str) -> bool {
+    let a = a.trim_end_matches('/');
+    let b = b.trim_end_matches('/');
+    a == b || b.starts_with(&format!("{a}/")) || a.starts_with(&format!("{b}/"))
+}
+
+fn error(line: Option<usize>, message: impl Into<String>) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        line,
+        message: message.into(),
+    }
+}
+
+fn warning(line: Option<usize>, message: impl Into<String>) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        line,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(diags: &[Diagnostic]) -> Vec<String> {
+        diags.iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_plan_has_no_diagnostics() {
+        let content = "# Plan\n\n## Tasks\n\n### 1. Setup\n- type: setup\n\n\
+### 2. Build\n- files: src/a.rs\n\n### 3. Test\n- files: src/a.rs\n- depends_on: 2\n\n\
+### 4. PR\n- type: pr\n- depends_on: 2, 3\n";
+        assert_eq!(validate_plan(content), Vec::new());
+    }
+
+    #[test]
+    fn test_reports_dag_problems_with_lines() {
+        let content = "---\nbudget_usd: 5\n---\n## Tasks\n\n\
+### 1. Setup\n- type: setup\n\n\
+### 2. One\n- depends_on: 3\n\n\
+### 3. Two\n- depends_on: 2, 9\n\n\
+### 3. Again\n\n\
+### 4. PR\n- type: pr\n";
+        let diags = validate_plan(content);
+        assert!(has_errors(&diags));
+        let msgs = messages(&diags);
+        assert!(
+            msgs.contains(&"line 10: dependency cycle: 2 → 3 → 2".to_string()),
+            "{msgs:?}"
+        );
+        assert!(
+            msgs.contains(&"line 13: task 3 depends on unknown task 9".to_string()),
+            "{msgs:?}"
+        );
+        assert!(
+            msgs.contains(
+                &"line 15: duplicate task number 3 (first defined on line 12)".to_string()
+            ),
+            "{msgs:?}"
+        );
+    }
+
+    #[test]
+    fn test_reports_bad_metadata_values() {
+        let content = "## Tasks\n\n### 1. Work\n- parallel: yes\n- model: gpt-4\n- priority: high\n- depends_on: one\n- approval: maybe\n\
+- max_attempts: 0\n- backoff: soon\n- escalate_model: gpt-5\n- permanent_errors: blocked, flaky\n\
+- timeout: forever\n- max_turns: -3\n";
+        let msgs = messages(&validate_plan(content));
+        assert!(msgs.contains(&"line 4: task 1: `parallel: yes` must be true or false".to_string()));
+        assert!(msgs
+            .iter()
+            .any(|m| m.starts_with("line 5: task 1: unknown model `gpt-4`")));
+        assert!(msgs.contains(&"line 6: task 1: `priority: high` is not an integer".to_string()));
+        assert!(
+            msgs.contains(&"line 7: task 1: `one` in depends_on is not a task number".to_string())
+        );
+        assert!(msgs
+            .contains(&"line 8: task 1: `approval: maybe` must be required or none".to_string()));
+        assert!(msgs.contains(
+            &"line 9: task 1: `max_attempts: 0` must be a whole number of at least 1".to_string()
+        ));
+        assert!(msgs
+            .iter()
+            .any(|m| m.starts_with("line 10: task 1: `backoff: soon`")));
+        assert!(msgs
+            .iter()
+            .any(|m| m.starts_with("line 11: task 1: unknown escalate_model `gpt-5`")));
+        assert!(msgs
+            .iter()
+            .any(|m| m.starts_with("line 12: task 1: unknown error class `flaky`")));
+        assert!(!msgs.iter().any(|m| m.contains("`blocked`")));
+        assert!(msgs.contains(
+            &"line 13: task 1: `timeout: forever` is not a duration (e.g. 30m or 1h)".to_string()
+        ));
+        assert!(msgs
+            .iter()
+            .any(|m| m.starts_with("line 14: task 1: `max_turns: -3`")));
+        // Missing setup and PR tasks are warnings on the ## Tasks heading
+        assert_eq!(
+            msgs.iter()
+                .filter(|m| m.starts_with("line 1: no `type:"))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_overlapping_files_only_for_parallel_tasks() {
+        let content = "## Tasks\n\n### 1. Setup\n- type: setup\n\n\
+### 2. API\n- files: src/api\n\n\
+### 3. Handler\n- files: src/api/users.rs\n\n\
+### 4. Migration A\n- files: db/schema.sql\n- resource: db\n\n\
+### 5. Migration B\n- files: db/schema.sql\n- resource: db\n\n\
+### 6. Serial\n- parallel: false\n- files: src/api/users.rs\n\n\
+### 7. PR\n- type: pr\n";
+        let diags = validate_plan(content);
+        assert!(!has_errors(&diags));
+        assert_eq!(diags.len(), 1, "{:?}", messages(&diags));
+        assert_eq!(diags[0].line, Some(10));
+        assert!(diags[0]
+            .message
+            .starts_with("tasks 2 and 3 may run in parallel"));
+    }
+}
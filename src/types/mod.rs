@@ -3,13 +3,14 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Task type for structured plans.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum TaskType {
     /// Environment setup (install deps, verify build) — handled by Hive before launch
     Setup,
     /// PR/MR creation — handled by Hive after all work tasks complete
     Pr,
     /// Implementation work — dispatched to teammates
+    #[default]
     Work,
 }
 
@@ -24,7 +25,7 @@ impl std::fmt::Display for TaskType {
 }
 
 /// A structured task parsed from a plan's `## Tasks` section.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct StructuredTask {
     /// Task number (from `### N. Title`)
     pub number: usize,
@@ -42,6 +43,25 @@ pub struct StructuredTask {
     pub files: Vec<String>,
     /// Task numbers this task depends on
     pub depends_on: Vec<usize>,
+    /// Scheduling priority; higher runs first among ready tasks (default 0)
+    pub priority: i32,
+    /// Concurrency class: tasks sharing a resource (e.g. "db") never run
+    /// at the same time
+    pub resource: Option<String>,
+    /// Shell commands from `- run:` bullets, executed in order by Hive for
+    /// setup tasks
+    pub run: Vec<String>,
+    /// Acceptance commands from `- verify:` bullets; a work task is only
+    /// complete once they all pass
+    pub verify: Vec<String>,
+    /// `- approval: required`: a reviewer signs off on a work task's changes
+    /// before they are merged, or on the drone's work before the PR phase
+    pub approval: bool,
+    /// Retry settings from `- max_attempts:`, `- backoff:`,
+    /// `- escalate_model:` and `- permanent_errors:` bullets
+    pub retry: RetryPolicy,
+    /// Wall-clock and turn budgets from `- timeout:` and `- max_turns:`
+    pub limits: TaskLimits,
 }
 
 impl StructuredTask {
@@ -131,6 +151,12 @@ pub struct Plan {
     pub base_branch: Option<String>,
     /// Structured tasks parsed from `## Tasks` section
     pub structured_tasks: Vec<StructuredTask>,
+    /// Spending caps from the plan's frontmatter
+    pub budget: BudgetLimits,
+    /// Quality gates parsed from `## Quality Gates` section
+    pub quality_gates: Vec<QualityGateSpec>,
+    /// Process sandbox settings from the plan's frontmatter
+    pub sandbox: SandboxConfig,
 }
 
 impl Plan {
@@ -195,6 +221,9 @@ impl From<LegacyJsonPlan> for Plan {
             target_branch: legacy.target_branch,
             base_branch: legacy.base_branch,
             structured_tasks: Vec::new(),
+            budget: BudgetLimits::default(),
+            quality_gates: Vec::new(),
+            sandbox: SandboxConfig::default(),
         }
     }
 }
@@ -236,6 +265,12 @@ pub struct DroneStatus {
     /// Model used for the team lead (e.g. "opus", "sonnet")
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub lead_model: Option<String>,
+    /// Worker model the drone was started with, reused by `hive resume`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Concurrent agent limit the drone was started with, reused by `hive resume`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_agents: Option<usize>,
     /// Active agents and their current task (for Agent Teams mode)
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub active_agents: HashMap<String, String>,
@@ -267,6 +302,10 @@ pub enum DroneState {
     Stopped,
     Cleaning,
     Zombie,
+    /// Stopped by the coordinator after a spend or token cap was hit
+    BudgetExceeded,
+    /// Stopped by `hive pause`; `hive resume` continues its workers
+    Paused,
 }
 
 /// Drone execution mode (always AgentTeam; Worktree kept as alias for backwards compat)
@@ -314,6 +353,8 @@ impl std::fmt::Display for DroneState {
             DroneState::Stopped => write!(f, "stopped"),
             DroneState::Cleaning => write!(f, "cleaning"),
             DroneState::Zombie => write!(f, "zombie"),
+            DroneState::BudgetExceeded => write!(f, "budget_exceeded"),
+            DroneState::Paused => write!(f, "paused"),
         }
     }
 }
@@ -326,8 +367,47 @@ pub struct HiveConfig {
     pub worktree_base: Option<String>,
     pub default_model: Option<String>,
     pub timestamp: String,
+    /// Default spending caps for drones in this project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetLimits>,
+    /// Default quality gates, used when a plan declares none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quality_gates: Vec<QualityGateSpec>,
+    /// How many follow-up tasks workers may propose per drone (0 disables
+    /// `ProposeTask`); defaults to `DEFAULT_MAX_PROPOSED_TASKS`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_proposed_tasks: Option<usize>,
+    /// Let a triage agent split, rewrite or skip tasks that used up their
+    /// retries instead of failing the drone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub triage: bool,
+    /// Default retry policy for every task; plan bullets override it
+    #[serde(default, skip_serializing_if = "RetryPolicy::is_default")]
+    pub retry: RetryPolicy,
+    /// Default wall-clock and turn budgets for every task; plan bullets
+    /// override them
+    #[serde(default, skip_serializing_if = "TaskLimits::is_default")]
+    pub task_limits: TaskLimits,
+    /// Machine-wide caps on model API traffic, shared by every drone using
+    /// the same credentials (read from the global config)
+    #[serde(default, skip_serializing_if = "ApiLimits::is_default")]
+    pub api_limits: ApiLimits,
+    /// Allow, ask and deny rules for tool calls in chat sessions and drones
+    #[serde(default, skip_serializing_if = "PermissionSettings::is_empty")]
+    pub permissions: PermissionSettings,
+    /// Default process sandbox for drones' Bash calls and quality gates;
+    /// plan frontmatter overrides it
+    #[serde(default, skip_serializing_if = "SandboxConfig::is_default")]
+    pub sandbox: SandboxConfig,
+    /// Run each drone worker's Bash calls in one long-lived shell, so `cd`
+    /// and exported variables carry over between calls
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub persistent_shell: bool,
 }
 
+/// Per-drone cap on worker-proposed tasks when the config sets none.
+pub const DEFAULT_MAX_PROPOSED_TASKS: usize = 5;
+
 impl Default for HiveConfig {
     fn default() -> Self {
         Self {
@@ -336,9 +416,401 @@ impl Default for HiveConfig {
             worktree_base: None,
             default_model: Some("sonnet".to_string()),
             timestamp: Utc::now().to_rfc3339(),
+            budget: None,
+            quality_gates: Vec::new(),
+            max_proposed_tasks: None,
+            triage: false,
+            retry: RetryPolicy::default(),
+            task_limits: TaskLimits::default(),
+            api_limits: ApiLimits::default(),
+            permissions: PermissionSettings::default(),
+            sandbox: SandboxConfig::default(),
+            persistent_shell: false,
+        }
+    }
+}
+
+/// A named check run, in declaration order, before a task or the drone's
+/// verification phase is accepted. `{files}` in the command is replaced
+/// with the task's files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityGateSpec {
+    pub name: String,
+    pub command: String,
+    #[serde(default = "default_gate_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Advisory gates are reported but never block completion
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+fn default_gate_timeout_secs() -> u64 {
+    120
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Spending caps for a drone and for each of its tasks, in USD and/or
+/// tokens (input + output + cache reads and writes). Unset limits are not
+/// enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drone_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drone_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_tokens: Option<u64>,
+}
+
+impl BudgetLimits {
+    /// Take each limit from `self`, falling back to `other` where unset.
+    pub fn or(self, other: BudgetLimits) -> BudgetLimits {
+        BudgetLimits {
+            drone_usd: self.drone_usd.or(other.drone_usd),
+            drone_tokens: self.drone_tokens.or(other.drone_tokens),
+            task_usd: self.task_usd.or(other.task_usd),
+            task_tokens: self.task_tokens.or(other.task_tokens),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        *self == BudgetLimits::default()
+    }
+}
+
+impl std::fmt::Display for BudgetLimits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let caps = |usd: Option<f64>, tokens: Option<u64>| {
+            let mut parts = Vec::new();
+            if let Some(usd) = usd {
+                parts.push(format!("${usd:.2}"));
+            }
+            if let Some(tokens) = tokens {
+                parts.push(format!("{tokens} tokens"));
+            }
+            parts.join(" / ")
+        };
+        let drone = caps(self.drone_usd, self.drone_tokens);
+        let task = caps(self.task_usd, self.task_tokens);
+        match (drone.is_empty(), task.is_empty()) {
+            (true, true) => write!(f, "unlimited"),
+            (false, true) => write!(f, "{drone}"),
+            (true, false) => write!(f, "{task} per task"),
+            (false, false) => write!(f, "{drone}, {task} per task"),
+        }
+    }
+}
+
+/// Attempts a task gets (the first run included) unless configured.
+pub const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// Kinds of worker failure, used to decide whether a task is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Rate limits, overloaded or 5xx API responses and network failures
+    Transient,
+    /// Missing, expired or rejected credentials
+    Credentials,
+    /// Any other API rejection, e.g. an invalid request
+    Api,
+    /// Stopped by the operator, a budget or the drone shutting down
+    Aborted,
+    /// Completion checks still failing when the worker ran out of iterations
+    Gates,
+    /// The model reported the task as blocked
+    Blocked,
+    /// The worker ran past the task's timeout or turn budget
+    Timeout,
+    /// A panic or other internal error
+    Internal,
+}
+
+impl ErrorClass {
+    pub const ALL: [ErrorClass; 8] = [
+        ErrorClass::Transient,
+        ErrorClass::Credentials,
+        ErrorClass::Api,
+        ErrorClass::Aborted,
+        ErrorClass::Gates,
+        ErrorClass::Blocked,
+        ErrorClass::Timeout,
+        ErrorClass::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorClass::Transient => "transient",
+            ErrorClass::Credentials => "credentials",
+            ErrorClass::Api => "api",
+            ErrorClass::Aborted => "aborted",
+            ErrorClass::Gates => "gates",
+            ErrorClass::Blocked => "blocked",
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Internal => "internal",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<ErrorClass> {
+        ErrorClass::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+}
+
+/// How a task's failed attempts are retried. Unset fields fall back to the
+/// `.hive/config.json` policy, then to the defaults. Transient errors never
+/// use up attempts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts including the first run (default `DEFAULT_MAX_ATTEMPTS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<usize>,
+    /// Delay before the first retry, doubled for each further one (default 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_secs: Option<u64>,
+    /// Model used for retries, e.g. "opus"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalate_model: Option<String>,
+    /// Error classes that fail the task without a retry (default: credentials)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permanent: Option<Vec<ErrorClass>>,
+}
+
+impl RetryPolicy {
+    /// Take each setting from `self`, falling back to `other` where unset.
+    pub fn or(self, other: &RetryPolicy) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts.or(other.max_attempts),
+            backoff_secs: self.backoff_secs.or(other.backoff_secs),
+            escalate_model: self.escalate_model.or(other.escalate_model.clone()),
+            permanent: self.permanent.or(other.permanent.clone()),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == RetryPolicy::default()
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1)
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry).
+    pub fn backoff(&self, retry: usize) -> std::time::Duration {
+        let base = self.backoff_secs.unwrap_or(0);
+        let factor = 1u64 << retry.saturating_sub(1).min(10);
+        std::time::Duration::from_secs(base.saturating_mul(factor))
+    }
+
+    /// Whether errors of this class fail the task without a retry. Aborts
+    /// are always final; transient errors never are.
+    pub fn is_permanent(&self, class: ErrorClass) -> bool {
+        match class {
+            ErrorClass::Aborted => true,
+            ErrorClass::Transient => false,
+            _ => match &self.permanent {
+                Some(classes) => classes.contains(&class),
+                None => class == ErrorClass::Credentials,
+            },
         }
     }
 }
 
+/// Agentic turns a worker may take on a task unless configured.
+pub const DEFAULT_MAX_TURNS: usize = 250;
+
+/// How long, and for how many agentic turns, a worker may run on a task.
+/// Unset fields fall back to the `.hive/config.json` limits, then to the
+/// defaults. A worker over either limit fails as timed out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskLimits {
+    /// Wall-clock limit per attempt (default none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Agentic turns per attempt (default `DEFAULT_MAX_TURNS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<usize>,
+}
+
+impl TaskLimits {
+    /// Take each limit from `self`, falling back to `other` where unset.
+    pub fn or(self, other: &TaskLimits) -> TaskLimits {
+        TaskLimits {
+            timeout_secs: self.timeout_secs.or(other.timeout_secs),
+            max_turns: self.max_turns.or(other.max_turns),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == TaskLimits::default()
+    }
+
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_secs.map(std::time::Duration::from_secs)
+    }
+
+    pub fn max_turns(&self) -> usize {
+        self.max_turns.unwrap_or(DEFAULT_MAX_TURNS).max(1)
+    }
+}
+
+/// Concurrent API requests allowed per credential when none is configured.
+pub const DEFAULT_API_MAX_CONCURRENT: usize = 6;
+
+/// Caps on model API traffic across all hive processes on the machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiLimits {
+    /// Requests in flight at once (default `DEFAULT_API_MAX_CONCURRENT`;
+    /// 0 turns the limiter off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    /// Input plus output tokens over the last minute before new requests
+    /// wait (default none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u64>,
+}
+
+impl ApiLimits {
+    pub fn is_default(&self) -> bool {
+        *self == ApiLimits::default()
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent.unwrap_or(DEFAULT_API_MAX_CONCURRENT)
+    }
+}
+
+/// What to do with a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecision {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// Tool permission rules such as `Bash(git push:*)`, `Write(src/**)` or
+/// `Read(~/.ssh/**)`. Deny rules win over ask rules, which win over allow
+/// rules; calls no rule matches get `default`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionRules {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ask: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Decision for calls no rule matches (default allow)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<PermissionDecision>,
+}
+
+impl PermissionRules {
+    pub fn is_empty(&self) -> bool {
+        *self == PermissionRules::default()
+    }
+}
+
+/// The `permissions` section of a config file: rules for every agent, plus
+/// rules that only apply to interactive chat sessions or to drone workers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionSettings {
+    #[serde(flatten)]
+    pub rules: PermissionRules,
+    #[serde(default, skip_serializing_if = "PermissionRules::is_empty")]
+    pub chat: PermissionRules,
+    #[serde(default, skip_serializing_if = "PermissionRules::is_empty")]
+    pub drone: PermissionRules,
+}
+
+impl PermissionSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == PermissionSettings::default()
+    }
+}
+
+/// How drones run shell commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxMode {
+    /// With the user's full privileges
+    #[default]
+    Off,
+    /// Inside a bubblewrap (`bwrap`) sandbox; Linux only
+    Bwrap,
+}
+
+impl std::str::FromStr for SandboxMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "none" => Ok(SandboxMode::Off),
+            "bwrap" | "bubblewrap" => Ok(SandboxMode::Bwrap),
+            other => Err(format!(
+                "unknown sandbox mode '{other}' (expected off or bwrap)"
+            )),
+        }
+    }
+}
+
+/// Process sandbox for drones' Bash calls and quality gates. In a sandbox,
+/// commands can only write inside the worktree, the temp dirs and
+/// `writable`, and run under the resource limits set here.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SandboxMode>,
+    /// Allow network access (default true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<bool>,
+    /// CPU seconds per command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_secs: Option<u64>,
+    /// Address space per process, in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// Processes the user may have running at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+    /// More paths commands may write to, e.g. `~/.cargo`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable: Vec<String>,
+}
+
+impl SandboxConfig {
+    /// Take each setting from `self`, falling back to `other` where unset.
+    pub fn or(self, other: &SandboxConfig) -> SandboxConfig {
+        SandboxConfig {
+            mode: self.mode.or(other.mode),
+            network: self.network.or(other.network),
+            cpu_secs: self.cpu_secs.or(other.cpu_secs),
+            memory_mb: self.memory_mb.or(other.memory_mb),
+            max_processes: self.max_processes.or(other.max_processes),
+            writable: if self.writable.is_empty() {
+                other.writable.clone()
+            } else {
+                self.writable
+            },
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == SandboxConfig::default()
+    }
+
+    pub fn mode(&self) -> SandboxMode {
+        self.mode.unwrap_or_default()
+    }
+
+    pub fn network(&self) -> bool {
+        self.network.unwrap_or(true)
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -8,6 +8,9 @@ fn test_plan_from_markdown() {
         target_branch: Some("feature/my-feature".to_string()),
         base_branch: Some("main".to_string()),
         structured_tasks: Vec::new(),
+        budget: BudgetLimits::default(),
+        quality_gates: Vec::new(),
+        sandbox: SandboxConfig::default(),
     };
 
     assert_eq!(plan.id, "my-feature");
@@ -23,6 +26,9 @@ fn test_plan_title_fallback() {
         target_branch: None,
         base_branch: None,
         structured_tasks: Vec::new(),
+        budget: BudgetLimits::default(),
+        quality_gates: Vec::new(),
+        sandbox: SandboxConfig::default(),
     };
 
     // Falls back to id when no heading is present
@@ -41,12 +47,7 @@ fn test_structured_task_defaults() {
     let task = StructuredTask {
         number: 1,
         title: "Test task".to_string(),
-        body: String::new(),
-        task_type: TaskType::Work,
-        model: None,
-        parallel: false,
-        files: Vec::new(),
-        depends_on: Vec::new(),
+        ..Default::default()
     };
 
     assert_eq!(task.number, 1);
@@ -60,12 +61,7 @@ fn test_worker_name_generation() {
     let make = |title: &str| StructuredTask {
         number: 1,
         title: title.to_string(),
-        body: String::new(),
-        task_type: TaskType::Work,
-        model: None,
-        parallel: false,
-        files: Vec::new(),
-        depends_on: Vec::new(),
+        ..Default::default()
     };
 
     // Stops at word boundary when would exceed 20 chars
@@ -175,6 +171,8 @@ fn test_drone_state_display() {
     assert_eq!(DroneState::Stopped.to_string(), "stopped");
     assert_eq!(DroneState::Cleaning.to_string(), "cleaning");
     assert_eq!(DroneState::Zombie.to_string(), "zombie");
+    assert_eq!(DroneState::BudgetExceeded.to_string(), "budget_exceeded");
+    assert_eq!(DroneState::Paused.to_string(), "paused");
 }
 
 #[test]
@@ -184,3 +182,50 @@ fn test_default_config() {
     assert_eq!(config.default_model, Some("sonnet".to_string()));
     assert!(config.project.is_none());
 }
+
+#[test]
+fn test_retry_policy_defaults_and_fallback() {
+    let config: RetryPolicy = serde_json::from_str(
+        r#"{"max_attempts": 4, "backoff_secs": 10, "permanent": ["blocked"]}"#,
+    )
+    .unwrap();
+    let task = RetryPolicy {
+        max_attempts: Some(2),
+        escalate_model: Some("opus".to_string()),
+        ..Default::default()
+    };
+    let policy = task.or(&config);
+    assert_eq!(policy.max_attempts(), 2);
+    assert_eq!(policy.escalate_model.as_deref(), Some("opus"));
+    assert_eq!(policy.backoff(1).as_secs(), 10);
+    assert_eq!(policy.backoff(3).as_secs(), 40);
+    assert!(policy.is_permanent(ErrorClass::Blocked));
+    assert!(!policy.is_permanent(ErrorClass::Credentials));
+
+    let default = RetryPolicy::default();
+    assert_eq!(default.max_attempts(), DEFAULT_MAX_ATTEMPTS);
+    assert!(default.backoff(2).is_zero());
+    assert!(default.is_permanent(ErrorClass::Credentials));
+    assert!(default.is_permanent(ErrorClass::Aborted));
+    assert!(!default.is_permanent(ErrorClass::Transient));
+    assert!(!serde_json::to_string(&HiveConfig::default())
+        .unwrap()
+        .contains("retry"));
+}
+
+#[test]
+fn test_task_limits_fall_back_to_config() {
+    let config = TaskLimits {
+        timeout_secs: Some(1800),
+        max_turns: Some(100),
+    };
+    let task = TaskLimits {
+        max_turns: Some(40),
+        ..Default::default()
+    };
+    let limits = task.or(&config);
+    assert_eq!(limits.timeout(), Some(std::time::Duration::from_secs(1800)));
+    assert_eq!(limits.max_turns(), 40);
+    assert_eq!(TaskLimits::default().max_turns(), DEFAULT_MAX_TURNS);
+    assert_eq!(TaskLimits::default().timeout(), None);
+}
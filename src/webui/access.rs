@@ -0,0 +1,320 @@
+//! Access control for the dashboard server: bind address, optional
+//! token/password authentication on `/api/*`, and the CORS allow-list.
+//!
+//! Credentials are accepted as `Authorization: Bearer <token>`, as a
+//! `hive_token` cookie, or as a `?token=` query parameter (needed for
+//! `EventSource`, which can't set headers). A valid query token also sets the
+//! cookie, so opening the printed URL once is enough for the browser.
+//! Password mode additionally accepts HTTP Basic auth with any username.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+const COOKIE_NAME: &str = "hive_token";
+
+/// How `/api/*` requests are authenticated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessMode {
+    /// No authentication (only sensible on a loopback bind).
+    Open,
+    /// Bearer token supplied by the operator.
+    Token(String),
+    /// Password generated at startup and printed to the terminal.
+    Password(String),
+}
+
+impl AccessMode {
+    fn secret(&self) -> Option<&str> {
+        match self {
+            AccessMode::Open => None,
+            AccessMode::Token(s) | AccessMode::Password(s) => Some(s),
+        }
+    }
+}
+
+/// Network-facing settings for the dashboard server.
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub port: u16,
+    pub bind: IpAddr,
+    pub access: AccessMode,
+    /// Extra origins allowed by CORS, on top of the server's own.
+    pub allowed_origins: Vec<String>,
+}
+
+impl ServerOptions {
+    /// Loopback-only, unauthenticated server (desktop app, local dev).
+    pub fn local(port: u16) -> Self {
+        Self {
+            port,
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            access: AccessMode::Open,
+            allowed_origins: Vec::new(),
+        }
+    }
+
+    /// URL to print at startup, carrying the token when auth is enabled.
+    pub fn display_url(&self, host: &str) -> String {
+        match self.access.secret() {
+            Some(secret) => format!("http://{host}:{}/?token={secret}", self.port),
+            None => format!("http://{host}:{}", self.port),
+        }
+    }
+
+    /// Origins allowed to make cross-origin requests: the server itself,
+    /// the Vite dev server, and anything passed via `--allow-origin`.
+    pub fn cors_origins(&self) -> Vec<String> {
+        let mut origins = vec![
+            format!("http://localhost:{}", self.port),
+            format!("http://127.0.0.1:{}", self.port),
+            "http://localhost:5173".to_string(),
+            "http://127.0.0.1:5173".to_string(),
+        ];
+        if !self.bind.is_unspecified() && !self.bind.is_loopback() {
+            origins.push(format!("http://{}:{}", self.bind, self.port));
+        }
+        for origin in &self.allowed_origins {
+            let origin = origin.trim().trim_end_matches('/');
+            if !origin.is_empty() && !origins.iter().any(|o| o == origin) {
+                origins.push(origin.to_string());
+            }
+        }
+        origins
+    }
+
+    pub fn cors_layer(&self) -> CorsLayer {
+        let origins: Vec<HeaderValue> = self
+            .cors_origins()
+            .iter()
+            .filter_map(|o| HeaderValue::from_str(o).ok())
+            .collect();
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .allow_credentials(true)
+    }
+}
+
+/// Random secret for `--auth-password`.
+pub fn generate_password() -> String {
+    use rand::distributions::Alphanumeric;
+    use rand::Rng;
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect()
+}
+
+/// Axum middleware enforcing `AccessMode` on `/api/*`.
+pub async fn require_auth(
+    State(access): State<Arc<AccessMode>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(secret) = access.secret() else {
+        return next.run(request).await;
+    };
+
+    let query_token = query_param(request.uri().query().unwrap_or(""), "token");
+    let query_ok = query_token.is_some_and(|t| constant_time_eq(&t, secret));
+    let allow_basic = matches!(*access, AccessMode::Password(_));
+    let authorized = query_ok || header_credentials_match(request.headers(), secret, allow_basic);
+
+    if request.uri().path().starts_with("/api/") && !authorized {
+        let mut response = (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "error": "Authentication required" })),
+        )
+            .into_response();
+        if allow_basic {
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static("Basic realm=\"hive\""),
+            );
+        }
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    if query_ok {
+        let cookie = format!("{COOKIE_NAME}={secret}; Path=/; HttpOnly; SameSite=Strict");
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+/// Check the bearer header, the session cookie and (optionally) Basic auth.
+fn header_credentials_match(headers: &HeaderMap, secret: &str, allow_basic: bool) -> bool {
+    for value in headers.get_all(header::AUTHORIZATION) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            if constant_time_eq(token.trim(), secret) {
+                return true;
+            }
+        }
+        if allow_basic {
+            if let Some(password) = value.strip_prefix("Basic ").and_then(basic_password) {
+                if constant_time_eq(&password, secret) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(name, value)| name == COOKIE_NAME && constant_time_eq(value, secret))
+}
+
+/// Password part of a base64 `user:password` Basic credential.
+fn basic_password(encoded: &str) -> Option<String> {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    let decoded = STANDARD.decode(encoded.trim()).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    decoded.split_once(':').map(|(_, p)| p.to_string())
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, v)| urlencoding::decode(v).ok())
+        .map(|v| v.into_owned())
+}
+
+/// Compare secrets without short-circuiting on the first differing byte.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+
+    async fn serve(access: AccessMode) -> String {
+        let app = Router::new()
+            .route("/", get(|| async { "index" }))
+            .route("/api/ping", get(|| async { "pong" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(access),
+                require_auth,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_token_required_on_api_routes() {
+        let base = serve(AccessMode::Token("s3cret".into())).await;
+        let client = reqwest::Client::new();
+
+        let res = client.get(format!("{base}/api/ping")).send().await.unwrap();
+        assert_eq!(res.status(), 401);
+        let res = client.get(format!("{base}/")).send().await.unwrap();
+        assert_eq!(res.status(), 200);
+
+        let res = client
+            .get(format!("{base}/api/ping"))
+            .bearer_auth("s3cret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+        let res = client
+            .get(format!("{base}/api/ping"))
+            .bearer_auth("wrong")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn test_query_token_sets_cookie() {
+        let base = serve(AccessMode::Token("s3cret".into())).await;
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("{base}/?token=s3cret"))
+            .send()
+            .await
+            .unwrap();
+        let cookie = res.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(cookie.starts_with("hive_token=s3cret;"));
+
+        let res = client
+            .get(format!("{base}/api/ping"))
+            .header(header::COOKIE, "theme=dark; hive_token=s3cret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_password_mode_accepts_basic_auth() {
+        let base = serve(AccessMode::Password("pw".into())).await;
+        let client = reqwest::Client::new();
+
+        let res = client.get(format!("{base}/api/ping")).send().await.unwrap();
+        assert_eq!(res.status(), 401);
+        assert!(res.headers().contains_key(header::WWW_AUTHENTICATE));
+
+        let res = client
+            .get(format!("{base}/api/ping"))
+            .basic_auth("anyone", Some("pw"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+    }
+
+    #[test]
+    fn test_cors_origins_include_extra_and_dedupe() {
+        let mut opts = ServerOptions::local(4000);
+        opts.allowed_origins = vec![
+            "https://hive.example.com/".into(),
+            "http://localhost:4000".into(),
+        ];
+        let origins = opts.cors_origins();
+        assert!(origins.contains(&"https://hive.example.com".to_string()));
+        assert_eq!(
+            origins
+                .iter()
+                .filter(|o| *o == "http://localhost:4000")
+                .count(),
+            1
+        );
+    }
+}
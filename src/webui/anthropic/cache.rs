@@ -0,0 +1,144 @@
+//! Prompt-cache breakpoints.
+//!
+//! The API caches the request prefix up to each `cache_control` marker, in
+//! the order tools → system → messages, with at most four markers per
+//! request. Hive uses them as:
+//!
+//! 1. the last tool definition,
+//! 2. the stable part of the system prompt (volatile project context is sent
+//!    as a second, unmarked block),
+//! 3. the last block of the latest message, and
+//! 4. the last block of the previous user message, so the prefix written on
+//!    the previous turn is read back even after a long tool-use exchange.
+
+use super::types::{CacheControl, SystemBlock, SystemPrompt, ToolDefinition};
+
+/// Build a system prompt whose `stable` part is cached and whose `volatile`
+/// suffix (if any) is not.
+pub fn cached_system(stable: String, volatile: Option<String>) -> SystemPrompt {
+    let mut blocks = vec![SystemBlock::text(stable, Some(CacheControl::ephemeral()))];
+    if let Some(v) = volatile.filter(|v| !v.trim().is_empty()) {
+        blocks.push(SystemBlock::text(v, None));
+    }
+    SystemPrompt::Blocks(blocks)
+}
+
+/// Mark the last tool definition as a cache breakpoint.
+pub fn mark_last_tool(mut tools: Vec<ToolDefinition>) -> Vec<ToolDefinition> {
+    for tool in tools.iter_mut() {
+        tool.cache_control = None;
+    }
+    if let Some(last) = tools.last_mut() {
+        last.cache_control = Some(CacheControl::ephemeral());
+    }
+    tools
+}
+
+/// Add the rolling conversation breakpoints (3 and 4 above) to a serialized
+/// request body. Operates on JSON so that breakpoints never leak into the
+/// session's stored message history.
+pub fn mark_conversation(body: &mut serde_json::Value) {
+    let Some(messages) = body.get_mut("messages").and_then(|m| m.as_array_mut()) else {
+        return;
+    };
+    let Some(last) = messages.len().checked_sub(1) else {
+        return;
+    };
+
+    let previous_user = messages[..last]
+        .iter()
+        .rposition(|m| m.get("role").and_then(|r| r.as_str()) == Some("user"));
+
+    mark_message(&mut messages[last]);
+    if let Some(idx) = previous_user {
+        mark_message(&mut messages[idx]);
+    }
+}
+
+/// Put `cache_control` on the last cacheable block of a message.
+fn mark_message(message: &mut serde_json::Value) {
+    let Some(content) = message.get_mut("content") else {
+        return;
+    };
+
+    if let Some(text) = content.as_str() {
+        if text.is_empty() {
+            return;
+        }
+        *content = serde_json::json!([{ "type": "text", "text": text }]);
+    }
+
+    let Some(blocks) = content.as_array_mut() else {
+        return;
+    };
+    // Thinking blocks can't carry cache_control, nor can empty text blocks
+    let target = blocks.iter_mut().rev().find(|b| {
+        let kind = b.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        let empty_text = kind == "text" && b.get("text").and_then(|t| t.as_str()) == Some("");
+        !matches!(kind, "thinking" | "redacted_thinking") && !empty_text
+    });
+    if let Some(obj) = target.and_then(|b| b.as_object_mut()) {
+        obj.insert(
+            "cache_control".to_string(),
+            serde_json::json!({ "type": "ephemeral" }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakpoints(body: &serde_json::Value) -> usize {
+        body.to_string().matches("cache_control").count()
+    }
+
+    #[test]
+    fn test_marks_latest_and_previous_user_message() {
+        let mut body = serde_json::json!({
+            "messages": [
+                {"role": "user", "content": "first"},
+                {"role": "assistant", "content": [{"type": "text", "text": "ok"}]},
+                {"role": "user", "content": "second"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "hmm"},
+                    {"type": "tool_use", "id": "t1", "name": "Read", "input": {}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "t1", "content": "data"}
+                ]}
+            ]
+        });
+        mark_conversation(&mut body);
+
+        let msgs = body["messages"].as_array().unwrap();
+        assert_eq!(msgs[4]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(msgs[2]["content"][0]["text"], "second");
+        assert_eq!(msgs[2]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(breakpoints(&body), 2);
+    }
+
+    #[test]
+    fn test_skips_thinking_blocks() {
+        let mut message = serde_json::json!({"role": "assistant", "content": [
+            {"type": "text", "text": "answer"},
+            {"type": "thinking", "thinking": "hmm"}
+        ]});
+        mark_message(&mut message);
+        assert!(message["content"][1].get("cache_control").is_none());
+        assert_eq!(message["content"][0]["cache_control"]["type"], "ephemeral");
+    }
+
+    #[test]
+    fn test_system_and_tools_breakpoints() {
+        let system = cached_system("base".into(), Some("\n## Git\nclean".into()));
+        let json = serde_json::to_value(&system).unwrap();
+        assert_eq!(json[0]["cache_control"]["type"], "ephemeral");
+        assert!(json[1].get("cache_control").is_none());
+
+        let tools = mark_last_tool(crate::webui::tools::definitions::builtin_tool_definitions());
+        let marked: Vec<_> = tools.iter().filter(|t| t.cache_control.is_some()).collect();
+        assert_eq!(marked.len(), 1);
+        assert_eq!(marked[0].name, tools.last().unwrap().name);
+    }
+}
@@ -100,11 +100,9 @@ pub(crate) fn process_event(
                 }
             }
         }
-        "message_stop" => {
-            if acc.stop_reason != "tool_use" {
-                let ev = serde_json::json!({"type":"result","subtype":"success","result":"","is_error":false});
-                let _ = tx.send(ev.to_string());
-            }
+        "message_stop" if acc.stop_reason != "tool_use" => {
+            let ev = serde_json::json!({"type":"result","subtype":"success","result":"","is_error":false});
+            let _ = tx.send(ev.to_string());
         }
         "error" => {
             if let Ok(err_val) = serde_json::from_str::<serde_json::Value>(event_data) {
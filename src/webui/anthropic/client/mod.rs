@@ -1,6 +1,6 @@
 pub(crate) mod event_processor;
 mod request;
-mod sse_parser;
+pub(crate) mod sse_parser;
 
 use anyhow::{Context, Result};
 use std::sync::atomic::AtomicBool;
@@ -10,6 +10,7 @@ use tracing::{debug, error, info, warn};
 
 use super::types::{ContentBlock, Message, MessageContent, MessagesRequest, UsageStats};
 use crate::webui::auth::credentials::Credentials;
+use crate::webui::provider::ApiError;
 
 use request::build_request;
 use sse_parser::parse_sse_stream;
@@ -32,7 +33,11 @@ pub async fn call_messages(
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Anthropic API error ({status}): {body}");
+        return Err(ApiError {
+            status: status.as_u16(),
+            message: format!("Anthropic API error ({status}): {body}"),
+        }
+        .into());
     }
 
     let body: serde_json::Value = response
@@ -162,7 +167,11 @@ pub async fn stream_messages(
                 "is_error": true
             });
             let _ = tx.send(error_event.to_string());
-            anyhow::bail!("{last_error}");
+            return Err(ApiError {
+                status: status.as_u16(),
+                message: last_error,
+            }
+            .into());
         }
 
         info!("Anthropic API response OK, starting SSE parse");
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 
+use super::super::cache;
 use super::super::types::MessagesRequest;
 use crate::webui::auth::credentials::{self, Credentials};
 
@@ -33,6 +34,9 @@ pub(super) async fn build_request(
         .is_some_and(|t| t.thinking_type == "enabled");
 
     let mut body = serde_json::to_value(request).context("Serializing request")?;
+    if request.cache_conversation {
+        cache::mark_conversation(&mut body);
+    }
 
     // When thinking is enabled, the API requires no temperature
     if thinking_enabled {
@@ -56,15 +60,16 @@ pub(super) async fn build_request(
         }
     }
 
+    let base_url = credentials::anthropic_base_url();
     let url = if is_oauth {
-        "https://api.anthropic.com/v1/messages?beta=true"
+        format!("{base_url}/v1/messages?beta=true")
     } else {
-        "https://api.anthropic.com/v1/messages"
+        format!("{base_url}/v1/messages")
     };
 
     let client = reqwest::Client::new();
     let mut req_builder = client
-        .post(url)
+        .post(&url)
         .header("anthropic-version", "2023-06-01")
         .header("content-type", "application/json")
         .header(auth_header_name, &auth_header_value);
@@ -30,28 +30,7 @@ pub(super) async fn parse_sse_stream(
         };
         let chunk_str = String::from_utf8_lossy(&chunk);
         buffer.push_str(&chunk_str);
-
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event_block = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
-
-            let mut event_type = String::new();
-            let mut event_data = String::new();
-
-            for line in event_block.lines() {
-                if let Some(t) = line.strip_prefix("event: ") {
-                    event_type = t.to_string();
-                } else if let Some(d) = line.strip_prefix("data: ") {
-                    event_data = d.to_string();
-                }
-            }
-
-            if event_data.is_empty() {
-                continue;
-            }
-
-            process_event(&event_type, &event_data, tx, &mut acc);
-        }
+        drain_sse_events(&mut buffer, tx, &mut acc);
     }
 
     if abort_flag.load(Ordering::Relaxed) {
@@ -67,6 +46,36 @@ pub(super) async fn parse_sse_stream(
     Ok(acc.into_result())
 }
 
+/// Process every complete (`\n\n`-terminated) SSE event in `buffer`, leaving
+/// any trailing partial event in place.
+pub(crate) fn drain_sse_events(
+    buffer: &mut String,
+    tx: &broadcast::Sender<String>,
+    acc: &mut EventAccumulator,
+) {
+    while let Some(event_end) = buffer.find("\n\n") {
+        let event_block = buffer[..event_end].to_string();
+        *buffer = buffer[event_end + 2..].to_string();
+
+        let mut event_type = String::new();
+        let mut event_data = String::new();
+
+        for line in event_block.lines() {
+            if let Some(t) = line.strip_prefix("event: ") {
+                event_type = t.to_string();
+            } else if let Some(d) = line.strip_prefix("data: ") {
+                event_data = d.to_string();
+            }
+        }
+
+        if event_data.is_empty() {
+            continue;
+        }
+
+        process_event(&event_type, &event_data, tx, acc);
+    }
+}
+
 /// Poll the abort flag at 50ms intervals, returning when it becomes `true`.
 async fn abort_notified(flag: &AtomicBool) {
     loop {
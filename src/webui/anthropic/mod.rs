@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod client;
 pub mod model;
+pub mod pricing;
 pub mod types;
@@ -9,6 +9,28 @@ pub fn resolve_model(short: &str) -> &str {
     }
 }
 
+/// Whether `resolve_model` (or the Bedrock equivalent) recognizes `short`
+/// rather than falling back to Sonnet.
+pub fn is_known_model(short: &str) -> bool {
+    let lower = short.to_lowercase();
+    matches!(
+        lower.as_str(),
+        "sonnet"
+            | "claude-sonnet"
+            | "sonnet-4.5"
+            | "opus"
+            | "claude-opus"
+            | "opus-4"
+            | "opus-4.6"
+            | "claude-opus-4.6"
+            | "haiku"
+            | "claude-haiku"
+            | "haiku-4.5"
+    ) || ["claude-", "anthropic.", "us.anthropic.", "eu.anthropic."]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+}
+
 /// Maximum output tokens allowed by the Anthropic API for a given model.
 /// With extended thinking enabled, Sonnet 4.5 supports up to 128K output.
 /// Opus models are capped at 32K. Haiku at 16K.
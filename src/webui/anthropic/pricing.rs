@@ -0,0 +1,135 @@
+//! Per-model token pricing, keyed by the IDs returned from
+//! `model::resolve_model` and `bedrock::model::resolve_bedrock_model`.
+
+use super::types::UsageStats;
+
+/// USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+const SONNET_4_5: ModelPricing = ModelPricing {
+    input: 3.0,
+    output: 15.0,
+    cache_write: 3.75,
+    cache_read: 0.30,
+};
+
+const OPUS_4: ModelPricing = ModelPricing {
+    input: 15.0,
+    output: 75.0,
+    cache_write: 18.75,
+    cache_read: 1.50,
+};
+
+/// Also the price of Opus 4.5.
+const OPUS_4_6: ModelPricing = ModelPricing {
+    input: 5.0,
+    output: 25.0,
+    cache_write: 6.25,
+    cache_read: 0.50,
+};
+
+const HAIKU_4_5: ModelPricing = ModelPricing {
+    input: 1.0,
+    output: 5.0,
+    cache_write: 1.25,
+    cache_read: 0.10,
+};
+
+const HAIKU_3_5: ModelPricing = ModelPricing {
+    input: 0.80,
+    output: 4.0,
+    cache_write: 1.0,
+    cache_read: 0.08,
+};
+
+/// Pricing for a resolved model ID. Bedrock inference profile IDs
+/// (`us.anthropic.<id>-v1:0`) map to the same entry as the Anthropic ID.
+/// Unknown Sonnet and Haiku models get their family's highest price;
+/// anything else is priced as Opus 4 so budgets are never under-counted.
+pub fn pricing_for(model_id: &str) -> ModelPricing {
+    match normalize(model_id) {
+        "claude-sonnet-4-5-20250929"
+        | "claude-sonnet-4-20250514"
+        | "claude-3-7-sonnet-20250219" => SONNET_4_5,
+        "claude-opus-4-20250514" | "claude-opus-4-1-20250805" => OPUS_4,
+        "claude-opus-4-5-20251101" | "claude-opus-4-6-20260213" => OPUS_4_6,
+        "claude-haiku-4-5-20251001" => HAIKU_4_5,
+        "claude-3-5-haiku-20241022" => HAIKU_3_5,
+        other if other.contains("haiku") => HAIKU_4_5,
+        other if other.contains("sonnet") => SONNET_4_5,
+        _ => OPUS_4,
+    }
+}
+
+/// USD cost of one or more API calls.
+pub fn cost_usd(model_id: &str, usage: &UsageStats) -> f64 {
+    let p = pricing_for(model_id);
+    (usage.input_tokens as f64 * p.input
+        + usage.output_tokens as f64 * p.output
+        + usage.cache_creation_input_tokens as f64 * p.cache_write
+        + usage.cache_read_input_tokens as f64 * p.cache_read)
+        / 1_000_000.0
+}
+
+/// Strip the Bedrock region prefix, `anthropic.` namespace and version suffix.
+fn normalize(model_id: &str) -> &str {
+    let id = model_id
+        .strip_prefix("us.")
+        .or_else(|| model_id.strip_prefix("eu."))
+        .unwrap_or(model_id);
+    let id = id.strip_prefix("anthropic.").unwrap_or(id);
+    match id.rfind("-v") {
+        Some(idx) if id[idx + 2..].contains(':') => &id[..idx],
+        _ => id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webui::anthropic::model::resolve_model;
+    use crate::webui::bedrock::model::resolve_bedrock_model;
+
+    #[test]
+    fn test_bedrock_ids_share_anthropic_pricing() {
+        for alias in ["sonnet", "opus", "opus-4.6", "haiku"] {
+            assert_eq!(
+                pricing_for(resolve_model(alias)),
+                pricing_for(resolve_bedrock_model(alias)),
+                "{alias}"
+            );
+        }
+        assert_eq!(pricing_for(resolve_model("opus")), OPUS_4);
+        assert_eq!(
+            pricing_for("eu.anthropic.claude-haiku-4-5-20251001-v1:0"),
+            HAIKU_4_5
+        );
+    }
+
+    #[test]
+    fn test_unknown_models_are_never_under_priced() {
+        assert_eq!(pricing_for("claude-opus-4-1-20250805"), OPUS_4);
+        assert_eq!(pricing_for("claude-opus-5-20270101"), OPUS_4);
+        assert_eq!(pricing_for("claude-next"), OPUS_4);
+        assert_eq!(pricing_for("claude-sonnet-5-20270101"), SONNET_4_5);
+    }
+
+    #[test]
+    fn test_cost_includes_cache_tokens() {
+        let usage = UsageStats {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: 200_000,
+            cache_read_input_tokens: 1_000_000,
+        };
+        // 3.00 + 1.50 + 0.75 + 0.30
+        let cost = cost_usd("claude-sonnet-4-5-20250929", &usage);
+        assert!((cost - 5.55).abs() < 1e-9, "{cost}");
+    }
+}
@@ -0,0 +1,408 @@
+//! Machine-wide limiter for model API requests.
+//!
+//! Each drone runs its own coordinator process, so an in-process semaphore
+//! can't see the other drones' load. Instead every hive process shares a
+//! small state file per credential under `~/.hive/api-limits/`, guarded by
+//! `flock`. A request takes a ticket, waits until it is first in line and a
+//! slot is free (and the last minute's tokens are under the cap), then holds
+//! the slot until its response has streamed. Tickets held by processes that
+//! died are dropped, so a crash can't wedge the queue.
+
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use nix::fcntl::{Flock, FlockArg};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use tracing::warn;
+
+use crate::types::ApiLimits;
+use crate::webui::auth::credentials::Credentials;
+
+/// How often a queued request checks whether it may go.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Window for `tokens_per_minute`.
+const USAGE_WINDOW_MS: i64 = 60_000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LimiterState {
+    next_ticket: u64,
+    /// Requests waiting for a slot, in arrival order
+    waiting: Vec<Holder>,
+    /// Requests in flight
+    active: Vec<Holder>,
+    /// Tokens used by finished requests within the window
+    usage: Vec<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Holder {
+    ticket: u64,
+    pid: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Usage {
+    /// Unix time in milliseconds
+    at: i64,
+    tokens: u64,
+}
+
+impl LimiterState {
+    /// Drop tickets of dead processes and usage older than the window.
+    fn prune(&mut self, now: i64) {
+        self.waiting.retain(|h| pid_alive(h.pid));
+        self.active.retain(|h| pid_alive(h.pid));
+        self.usage.retain(|u| now - u.at < USAGE_WINDOW_MS);
+    }
+
+    /// Move `ticket` from the queue to the active set if it is first in line
+    /// and the limits allow another request.
+    fn try_grant(&mut self, ticket: u64, limits: &ApiLimits) -> bool {
+        if self.waiting.first().map(|h| h.ticket) != Some(ticket) {
+            return false;
+        }
+        if self.active.len() >= limits.max_concurrent() {
+            return false;
+        }
+        if let Some(cap) = limits.tokens_per_minute {
+            if self.usage.iter().map(|u| u.tokens).sum::<u64>() >= cap {
+                return false;
+            }
+        }
+        let holder = self.waiting.remove(0);
+        self.active.push(holder);
+        true
+    }
+
+    fn release(&mut self, ticket: u64) {
+        self.waiting.retain(|h| h.ticket != ticket);
+        self.active.retain(|h| h.ticket != ticket);
+    }
+}
+
+/// The shared queue for one credential.
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    state_path: PathBuf,
+    lock_path: PathBuf,
+    limits: ApiLimits,
+}
+
+impl Limiter {
+    pub fn new(dir: &Path, key: &str, limits: ApiLimits) -> Self {
+        Self {
+            state_path: dir.join(format!("{key}.json")),
+            lock_path: dir.join(format!("{key}.lock")),
+            limits,
+        }
+    }
+
+    /// Wait for a slot. The returned permit holds it until released; a permit
+    /// dropped while still queued gives up its place in line. Fails with
+    /// "Aborted" if `abort_flag` is set while waiting.
+    pub async fn acquire(&self, abort_flag: &AtomicBool) -> Result<Permit> {
+        let pid = std::process::id();
+        // The permit is built on the blocking pool too, so a caller that
+        // stops waiting meanwhile still drops it and leaves the queue
+        let limiter = self.clone();
+        let permit = tokio::task::spawn_blocking(move || {
+            let ticket = limiter.update(|state| {
+                state.next_ticket += 1;
+                let ticket = state.next_ticket;
+                state.waiting.push(Holder { ticket, pid });
+                ticket
+            })?;
+            anyhow::Ok(Permit {
+                limiter,
+                ticket,
+                tokens: 0,
+                released: false,
+            })
+        })
+        .await??;
+
+        loop {
+            if abort_flag.load(Ordering::Relaxed) {
+                anyhow::bail!("Aborted");
+            }
+            let (limiter, limits, ticket) = (self.clone(), self.limits, permit.ticket);
+            let granted = tokio::task::spawn_blocking(move || {
+                limiter.update(|state| {
+                    state.prune(now_ms());
+                    state.try_grant(ticket, &limits)
+                })
+            })
+            .await??;
+            if granted {
+                return Ok(permit);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Run `f` on the state file while holding its lock. Blocks, so async
+    /// callers run it on the blocking pool.
+    fn update<T>(&self, f: impl FnOnce(&mut LimiterState) -> T) -> Result<T> {
+        if let Some(dir) = self.state_path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.lock_path)
+            .with_context(|| format!("Failed to open {}", self.lock_path.display()))?;
+        let _lock = Flock::lock(lock_file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| errno)
+            .context("Failed to lock the API limiter")?;
+
+        let mut state: LimiterState = std::fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let result = f(&mut state);
+        let tmp = self.state_path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&state)?)?;
+        std::fs::rename(&tmp, &self.state_path)?;
+        Ok(result)
+    }
+}
+
+/// A slot in the machine-wide queue. Give it back with `release`; a permit
+/// that is dropped instead releases its slot on the blocking thread pool.
+#[derive(Debug)]
+pub struct Permit {
+    limiter: Limiter,
+    ticket: u64,
+    tokens: u64,
+    released: bool,
+}
+
+impl Permit {
+    /// Count a finished request's tokens against `tokens_per_minute`.
+    pub fn record(&mut self, tokens: u64) {
+        self.tokens += tokens;
+    }
+
+    /// Give the slot back, without blocking the async runtime on the lock.
+    pub async fn release(mut self) {
+        self.released = true;
+        let (limiter, ticket, tokens) = (self.limiter.clone(), self.ticket, self.tokens);
+        let _ = tokio::task::spawn_blocking(move || release_slot(&limiter, ticket, tokens)).await;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        let (limiter, ticket, tokens) = (self.limiter.clone(), self.ticket, self.tokens);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(move || release_slot(&limiter, ticket, tokens));
+            }
+            Err(_) => release_slot(&limiter, ticket, tokens),
+        }
+    }
+}
+
+fn release_slot(limiter: &Limiter, ticket: u64, tokens: u64) {
+    let released = limiter.update(|state| {
+        state.release(ticket);
+        if tokens > 0 {
+            state.usage.push(Usage {
+                at: now_ms(),
+                tokens,
+            });
+        }
+    });
+    if let Err(e) = released {
+        warn!(error = %e, "Could not release API limiter slot");
+    }
+}
+
+/// Wait for a machine-wide slot for a request made with `creds`. Returns
+/// `None` when the limiter is off, doesn't apply to these credentials, or
+/// its state can't be used; only an abort while waiting is an error.
+pub async fn acquire(creds: &Credentials, abort_flag: &AtomicBool) -> Result<Option<Permit>> {
+    let (Some(key), Some(home)) = (credential_key(creds), dirs::home_dir()) else {
+        return Ok(None);
+    };
+    let limits = crate::config::get_api_limits();
+    if limits.max_concurrent() == 0 {
+        return Ok(None);
+    }
+    let limiter = Limiter::new(&home.join(".hive").join("api-limits"), &key, limits);
+    match limiter.acquire(abort_flag).await {
+        Ok(permit) => Ok(Some(permit)),
+        Err(e) if abort_flag.load(Ordering::Relaxed) => Err(e),
+        Err(e) => {
+            warn!(error = %e, "API limiter unavailable, sending request without it");
+            Ok(None)
+        }
+    }
+}
+
+/// Name of the queue shared by everyone using these credentials. Secrets
+/// are hashed so they never reach the file system. Scripted mock runs are
+/// not limited.
+fn credential_key(creds: &Credentials) -> Option<String> {
+    let key = match creds {
+        Credentials::ApiKey { api_key } => format!("api-key-{}", short_hash(api_key)),
+        // One OAuth login per machine; its tokens rotate on refresh
+        Credentials::OAuth { .. } => "oauth".to_string(),
+        Credentials::Bedrock {
+            region,
+            access_key_id,
+            ..
+        } => format!("bedrock-{region}-{}", short_hash(access_key_id)),
+        Credentials::BedrockProfile {
+            region,
+            aws_profile,
+        } => format!("bedrock-{region}-{}", short_hash(aws_profile)),
+        Credentials::Mock { .. } => return None,
+    };
+    Some(key)
+}
+
+fn short_hash(value: &str) -> String {
+    sha2::Sha256::digest(value.as_bytes())
+        .iter()
+        .take(6)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn pid_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    match nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid as i32), None) {
+        Ok(()) | Err(Errno::EPERM) => true,
+        Err(_) => false,
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(dir: &Path, max_concurrent: usize, tokens_per_minute: Option<u64>) -> Limiter {
+        Limiter::new(
+            dir,
+            "test",
+            ApiLimits {
+                max_concurrent: Some(max_concurrent),
+                tokens_per_minute,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_waiters_get_slots_in_arrival_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), 1, None);
+        let abort = AtomicBool::new(false);
+
+        let first = limiter.acquire(&abort).await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for n in 0..2 {
+            let limiter = limiter.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let permit = limiter.acquire(&AtomicBool::new(false)).await.unwrap();
+                tx.send(n).unwrap();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                drop(permit);
+            });
+            // Let the first waiter take its ticket before the second
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL * 3).await;
+        assert!(rx.try_recv().is_err(), "a waiter went past the held slot");
+        drop(first);
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_token_cap_holds_requests_and_abort_leaves_the_queue() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), 4, Some(1_000));
+        let abort = AtomicBool::new(false);
+
+        let mut permit = limiter.acquire(&abort).await.unwrap();
+        permit.record(1_200);
+        permit.release().await;
+
+        // Over the cap: the next request waits, and gives up its ticket
+        // when the caller stops waiting
+        let held = tokio::time::timeout(POLL_INTERVAL * 3, limiter.acquire(&abort)).await;
+        assert!(held.is_err());
+
+        abort.store(true, Ordering::Relaxed);
+        let err = limiter.acquire(&abort).await.unwrap_err();
+        assert_eq!(err.to_string(), "Aborted");
+        // Dropped permits release their tickets on the blocking pool
+        let mut state = (usize::MAX, usize::MAX, 0);
+        for _ in 0..50 {
+            state = limiter
+                .update(|s| (s.waiting.len(), s.active.len(), s.usage.len()))
+                .unwrap();
+            if state == (0, 0, 1) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(state, (0, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_slots_of_dead_processes_are_reclaimed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let limiter = limiter(tmp.path(), 1, None);
+        limiter
+            .update(|s| {
+                s.next_ticket = 1;
+                s.active.push(Holder {
+                    ticket: 1,
+                    pid: i32::MAX as u32,
+                });
+            })
+            .unwrap();
+
+        let permit = tokio::time::timeout(
+            Duration::from_secs(2),
+            limiter.acquire(&AtomicBool::new(false)),
+        )
+        .await
+        .expect("slot of a dead process was not reclaimed");
+        assert!(permit.is_ok());
+    }
+
+    #[test]
+    fn test_credential_keys_hide_secrets() {
+        let key = credential_key(&Credentials::ApiKey {
+            api_key: "sk-ant-secret".to_string(),
+        })
+        .unwrap();
+        assert!(key.starts_with("api-key-"));
+        assert!(!key.contains("secret"));
+        assert_eq!(
+            credential_key(&Credentials::Mock {
+                transcripts: PathBuf::from("/tmp")
+            }),
+            None
+        );
+    }
+}
@@ -26,6 +26,9 @@ pub enum Credentials {
     },
     #[serde(rename = "bedrock_profile")]
     BedrockProfile { region: String, aws_profile: String },
+    /// Replays scripted SSE transcripts from disk (see `webui::mock`).
+    #[serde(rename = "mock")]
+    Mock { transcripts: PathBuf },
 }
 
 pub fn credentials_path() -> PathBuf {
@@ -170,16 +173,25 @@ pub async fn get_auth_header(creds: &Credentials) -> Result<(&'static str, Strin
         Credentials::Bedrock { .. } | Credentials::BedrockProfile { .. } => {
             anyhow::bail!("Bedrock credentials use SigV4 signing, not auth headers")
         }
+        Credentials::Mock { .. } => {
+            anyhow::bail!("Mock credentials replay transcripts, not auth headers")
+        }
     }
 }
 
 /// Resolve credentials from the active profile, falling back to global file.
 ///
 /// Priority:
+/// 0. `HIVE_MOCK_TRANSCRIPTS` environment variable → `Credentials::Mock`
 /// 1. Active profile's bedrock config → `Credentials::Bedrock`
 /// 2. Per-profile credentials file (`~/.config/hive/profiles/{name}.credentials.json`)
 /// 3. Global credentials file (`~/.config/hive/credentials.json`)
 pub fn resolve_credentials() -> Result<Option<Credentials>> {
+    if let Some(dir) = std::env::var_os("HIVE_MOCK_TRANSCRIPTS").filter(|d| !d.is_empty()) {
+        return Ok(Some(Credentials::Mock {
+            transcripts: PathBuf::from(dir),
+        }));
+    }
     let active_name = profile::get_active_profile().unwrap_or_default();
     if let Ok(active) = profile::load_active_profile() {
         if active.provider == Provider::Bedrock {
@@ -207,6 +219,28 @@ pub fn resolve_credentials() -> Result<Option<Credentials>> {
     load_credentials_for_profile(&active_name)
 }
 
+/// Default endpoint for the Anthropic API.
+pub const DEFAULT_ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Resolve the Anthropic API base URL (without a trailing slash).
+///
+/// Priority:
+/// 1. `ANTHROPIC_BASE_URL` environment variable
+/// 2. Active profile's `anthropic_base_url`
+/// 3. `https://api.anthropic.com`
+pub fn anthropic_base_url() -> String {
+    let url = std::env::var("ANTHROPIC_BASE_URL")
+        .ok()
+        .filter(|u| !u.trim().is_empty())
+        .or_else(|| {
+            profile::load_active_profile()
+                .ok()
+                .and_then(|p| p.anthropic_base_url)
+        })
+        .unwrap_or_else(|| DEFAULT_ANTHROPIC_BASE_URL.to_string());
+    url.trim().trim_end_matches('/').to_string()
+}
+
 /// Resolve the active provider from the active profile.
 pub fn resolve_provider() -> Provider {
     profile::load_active_profile()
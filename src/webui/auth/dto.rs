@@ -90,6 +90,9 @@ pub struct CreateProfileRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[garde(skip)]
     pub aws_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[garde(skip)]
+    pub anthropic_base_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -34,6 +34,7 @@ pub async fn auth_status() -> ApiResult<Json<AuthStatusResponse>> {
                 Credentials::Bedrock { .. } | Credentials::BedrockProfile { .. } => {
                     ("bedrock".to_string(), false)
                 }
+                Credentials::Mock { .. } => ("mock".to_string(), false),
             };
             Ok(Json(AuthStatusResponse {
                 configured: true,
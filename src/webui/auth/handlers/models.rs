@@ -92,7 +92,10 @@ async fn fetch_models_from_api(
 ) -> Result<Vec<ModelInfo>, ()> {
     let client = reqwest::Client::new();
     let mut req = client
-        .get("https://api.anthropic.com/v1/models?limit=100")
+        .get(format!(
+            "{}/v1/models?limit=100",
+            credentials::anthropic_base_url()
+        ))
         .header(header_name, header_value)
         .header("anthropic-version", "2023-06-01");
 
@@ -16,7 +16,7 @@ pub async fn setup_api_key(
     // Validate the API key by calling GET /v1/models
     let client = reqwest::Client::new();
     let validate_res = client
-        .get("https://api.anthropic.com/v1/models")
+        .get(format!("{}/v1/models", credentials::anthropic_base_url()))
         .header("x-api-key", &api_key)
         .header("anthropic-version", "2023-06-01")
         .send()
@@ -132,6 +132,7 @@ pub async fn create_profile(
         environment: None,
         provider,
         bedrock,
+        anthropic_base_url: body.anthropic_base_url.filter(|u| !u.trim().is_empty()),
         created: now.clone(),
         updated: now,
     };
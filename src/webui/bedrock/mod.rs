@@ -17,6 +17,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::webui::anthropic::types::{Message, MessagesRequest, UsageStats};
 use crate::webui::auth::credentials::Credentials;
+use crate::webui::provider::ApiError;
 
 use aws_resolve::AwsCredentialError;
 use request::build_bedrock_request;
@@ -147,7 +148,11 @@ pub async fn stream_messages(
                 "is_error": true
             });
             let _ = tx.send(error_event.to_string());
-            anyhow::bail!("{last_error}");
+            return Err(ApiError {
+                status: status.as_u16(),
+                message: last_error,
+            }
+            .into());
         }
 
         info!("Bedrock API response OK, starting EventStream parse");
@@ -9,6 +9,7 @@ use aws_sigv4::sign::v4;
 use std::time::SystemTime;
 use tracing::{debug, info, warn};
 
+use crate::webui::anthropic::cache;
 use crate::webui::anthropic::types::MessagesRequest;
 use crate::webui::auth::credentials::Credentials;
 
@@ -124,6 +125,9 @@ pub(super) async fn build_bedrock_request(
 /// - Uses `anthropic_version: "bedrock-2023-05-31"`
 fn build_bedrock_body(request: &MessagesRequest) -> Result<serde_json::Value> {
     let mut body = serde_json::to_value(request).context("Serializing request")?;
+    if request.cache_conversation {
+        cache::mark_conversation(&mut body);
+    }
 
     if let Some(obj) = body.as_object_mut() {
         obj.remove("model");
@@ -21,6 +21,14 @@ pub struct CreateSessionRequest {
     #[serde(default)]
     #[garde(skip)]
     pub max_turns: Option<usize>,
+    /// Send prompt-cache breakpoints (default: true)
+    #[serde(default)]
+    #[garde(skip)]
+    pub prompt_caching: Option<bool>,
+    /// Run Bash calls in one long-lived shell (default: false)
+    #[serde(default)]
+    #[garde(skip)]
+    pub persistent_shell: Option<bool>,
 }
 
 fn default_model() -> String {
@@ -81,4 +89,17 @@ pub struct UpdateSessionRequest {
     #[serde(default)]
     #[garde(skip)]
     pub system_prompt: Option<String>,
+    #[serde(default)]
+    #[garde(skip)]
+    pub prompt_caching: Option<bool>,
+    #[serde(default)]
+    #[garde(skip)]
+    pub persistent_shell: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct PermissionAnswerRequest {
+    /// Let the tool call run (true) or refuse it (false)
+    #[garde(skip)]
+    pub allow: bool,
 }
@@ -3,7 +3,7 @@ use axum::{
     Json,
 };
 
-use crate::webui::anthropic::{self, types::*};
+use crate::webui::anthropic::types::*;
 use crate::webui::auth::credentials;
 use crate::webui::error::{ApiError, ApiResult};
 use crate::webui::provider;
@@ -95,9 +95,10 @@ pub async fn compact_session(
         tool_choice: None,
         thinking: None,
         temperature: Some(0.5),
+        cache_conversation: false,
     };
 
-    let (assistant_msg, usage) = anthropic::client::call_messages(&creds, &request)
+    let (assistant_msg, usage) = provider::call_messages(&creds, &request)
         .await
         .map_err(|e| ApiError::Internal(e.context("Compact API call failed")))?;
 
@@ -19,7 +19,7 @@ use crate::webui::error::{ApiError, ApiResult};
 use crate::webui::extractors::ValidJson;
 use crate::webui::provider;
 
-use super::super::dto::SendMessageRequest;
+use super::super::dto::{PermissionAnswerRequest, SendMessageRequest};
 use super::super::persistence::{
     append_event, extract_title, read_meta, update_meta_status, write_meta,
 };
@@ -212,6 +212,10 @@ pub async fn send_message(
     let max_turns = session.max_turns;
     let mcp_pool = session.mcp_pool.clone();
     let deferred_tools_active = session.deferred_tools_active;
+    let prompt_caching = session.prompt_caching;
+    let permission_requests = session.permission_requests.clone();
+    let shell = session.shell.clone();
+    let processes = session.processes.clone();
     let messages_snapshot = session.messages.clone();
     let session_cwd = session.cwd.clone();
     let tx = session.tx.clone();
@@ -246,6 +250,10 @@ pub async fn send_message(
         max_turns,
         mcp_pool,
         deferred_tools_active,
+        prompt_caching,
+        permission_requests,
+        shell,
+        processes,
     });
 
     Ok(Json(serde_json::json!({"ok": true})))
@@ -266,3 +274,23 @@ pub async fn abort_session(
 
     Ok(Json(serde_json::json!({"ok": true})))
 }
+
+/// Answer a `permission.request` event: allow or refuse the tool call.
+pub async fn answer_permission(
+    State(store): State<SessionStore>,
+    Path((id, request_id)): Path<(String, String)>,
+    ValidJson(body): ValidJson<PermissionAnswerRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let sessions = store.lock().await;
+    let session = sessions
+        .get(&id)
+        .ok_or_else(|| ApiError::NotFound(format!("Session '{id}' not found")))?;
+
+    if !session.permission_requests.answer(&request_id, body.allow) {
+        return Err(ApiError::NotFound(format!(
+            "Permission request '{request_id}' is not pending"
+        )));
+    }
+
+    Ok(Json(serde_json::json!({"ok": true})))
+}
@@ -7,8 +7,10 @@ mod spawner;
 mod system_prompt;
 
 pub use compact::compact_session;
-pub use messaging::{abort_session, send_message, stream_session};
-pub use plans::{archive_plan, delete_plan, dispatch_plan, get_plan, list_plans, unarchive_plan};
+pub use messaging::{abort_session, answer_permission, send_message, stream_session};
+pub use plans::{
+    archive_plan, delete_plan, dispatch_plan, get_plan, list_plans, unarchive_plan, validate_plan,
+};
 pub use sessions::{
     create_session, delete_session, list_sessions, session_history, update_session,
 };
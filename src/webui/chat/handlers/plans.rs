@@ -4,6 +4,7 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::plan_parser::validate::{self, Diagnostic};
 use crate::webui::error::{ApiError, ApiResult};
 
 // ── Types ──────────────────────────────────────────────────────────────────
@@ -37,6 +38,12 @@ pub struct PlanTask {
     pub depends_on: Vec<usize>,
 }
 
+#[derive(Serialize)]
+pub struct PlanValidation {
+    pub valid: bool,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 #[derive(Deserialize)]
 pub struct DispatchRequest {
     #[serde(alias = "droneName")]
@@ -225,6 +232,25 @@ pub async fn get_plan(
     }))
 }
 
+/// GET /api/plans/{id}/validate — check a plan's task graph and metadata
+pub async fn validate_plan(
+    Path(id): Path<String>,
+    Query(q): Query<ProjectQuery>,
+) -> ApiResult<Json<PlanValidation>> {
+    let path = plans_dir_for(q.project_path.as_deref()).join(format!("{id}.md"));
+    if !path.is_file() {
+        return Err(ApiError::NotFound(format!("Plan '{id}' not found")));
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Cannot read plan: {e}")))?;
+
+    let diagnostics = validate::validate_plan(&content);
+    Ok(Json(PlanValidation {
+        valid: !validate::has_errors(&diagnostics),
+        diagnostics,
+    }))
+}
+
 /// DELETE /api/plans/{id} — delete a plan (checks both active and archived)
 pub async fn delete_plan(
     Path(id): Path<String>,
@@ -307,6 +333,21 @@ pub async fn dispatch_plan(
         return Err(ApiError::NotFound(format!("Plan '{id}' not found")));
     }
 
+    // Reject broken plans here; `start::run` would only log the failure
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Cannot read plan: {e}")))?;
+    let errors: Vec<String> = validate::validate_plan(&content)
+        .into_iter()
+        .filter(|d| d.severity == validate::Severity::Error)
+        .map(|d| d.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Plan '{id}' is invalid: {}",
+            errors.join("; ")
+        )));
+    }
+
     let drone_name = body.drone_name;
     let model = body.model;
     let project_root = q.project_path.map(std::path::PathBuf::from);
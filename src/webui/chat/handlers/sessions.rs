@@ -10,7 +10,7 @@ use tokio::sync::broadcast;
 use crate::webui::error::{ApiError, ApiResult};
 use crate::webui::extractors::ValidJson;
 use crate::webui::mcp_client::pool::McpPool;
-use crate::webui::tools;
+use crate::webui::tools::{self, PersistentShell};
 
 use super::super::agents;
 use super::super::dto::{
@@ -70,6 +70,8 @@ pub async fn create_session(
         system_prompt: system_prompt.clone(),
         total_input_tokens: 0,
         total_output_tokens: 0,
+        prompt_caching: body.prompt_caching.unwrap_or(true),
+        persistent_shell: body.persistent_shell.unwrap_or(false),
     };
     write_meta(&meta);
 
@@ -107,6 +109,12 @@ pub async fn create_session(
         mcp_pool: Some(mcp_pool),
         agent: body.agent.clone(),
         deferred_tools_active: false,
+        prompt_caching: meta.prompt_caching,
+        permission_requests: Default::default(),
+        shell: meta
+            .persistent_shell
+            .then(|| Arc::new(PersistentShell::new(&cwd, None))),
+        processes: Default::default(),
     };
 
     store.lock().await.insert(id.clone(), session);
@@ -175,6 +183,12 @@ pub(super) async fn restore_session_from_disk(store: &SessionStore, id: &str) ->
         mcp_pool: Some(mcp_pool),
         agent: None,
         deferred_tools_active: false,
+        prompt_caching: meta.prompt_caching,
+        permission_requests: Default::default(),
+        shell: meta
+            .persistent_shell
+            .then(|| Arc::new(PersistentShell::new(&cwd, None))),
+        processes: Default::default(),
     };
 
     let id_owned = id.to_string();
@@ -289,6 +303,20 @@ pub async fn update_session(
         if let Some(ref system_prompt) = body.system_prompt {
             session.system_prompt = Some(system_prompt.clone());
         }
+        if let Some(prompt_caching) = body.prompt_caching {
+            session.prompt_caching = prompt_caching;
+        }
+        match body.persistent_shell {
+            Some(true) if session.shell.is_none() => {
+                session.shell = Some(Arc::new(PersistentShell::new(&session.cwd, None)));
+            }
+            Some(false) => {
+                if let Some(shell) = session.shell.take() {
+                    shell.shutdown();
+                }
+            }
+            _ => {}
+        }
     }
     drop(sessions);
 
@@ -300,6 +328,12 @@ pub async fn update_session(
     if let Some(ref system_prompt) = body.system_prompt {
         meta.system_prompt = Some(system_prompt.clone());
     }
+    if let Some(prompt_caching) = body.prompt_caching {
+        meta.prompt_caching = prompt_caching;
+    }
+    if let Some(persistent_shell) = body.persistent_shell {
+        meta.persistent_shell = persistent_shell;
+    }
     meta.updated_at = chrono::Utc::now().to_rfc3339();
     write_meta(&meta);
 
@@ -322,6 +356,10 @@ pub async fn delete_session(
             let mut pool = pool.lock().await;
             pool.shutdown_all().await;
         }
+        if let Some(shell) = &session.shell {
+            shell.shutdown();
+        }
+        session.processes.shutdown();
         let _ = tokio::fs::remove_dir_all(&dir).await;
         return Ok(Json(serde_json::json!({"ok": true})));
     }
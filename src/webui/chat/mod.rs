@@ -29,6 +29,10 @@ pub fn routes(sessions: SessionStore) -> Router {
             "/api/chat/sessions/{id}/abort",
             post(handlers::abort_session),
         )
+        .route(
+            "/api/chat/sessions/{id}/permissions/{request_id}",
+            post(handlers::answer_permission),
+        )
         .route(
             "/api/chat/sessions/{id}",
             delete(handlers::delete_session).patch(handlers::update_session),
@@ -47,6 +51,7 @@ pub fn routes(sessions: SessionStore) -> Router {
             "/api/plans/{id}",
             get(handlers::get_plan).delete(handlers::delete_plan),
         )
+        .route("/api/plans/{id}/validate", get(handlers::validate_plan))
         .route("/api/plans/{id}/dispatch", post(handlers::dispatch_plan))
         .route("/api/plans/{id}/archive", post(handlers::archive_plan))
         .route("/api/plans/{id}/unarchive", post(handlers::unarchive_plan))
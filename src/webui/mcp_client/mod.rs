@@ -94,6 +94,7 @@ async fn discover_server_tools(
                 "type": "object",
                 "properties": {}
             })),
+            cache_control: None,
         })
         .collect();
 
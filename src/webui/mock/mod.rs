@@ -0,0 +1,145 @@
+//! Scripted provider for offline runs and deterministic tests.
+//!
+//! `Credentials::Mock { transcripts }` points at a directory of Anthropic SSE
+//! transcripts (`001.sse`, `002.sse`, ...). Each request replays the next
+//! file, sorted by name, through the same event processing as the live
+//! client. If `<transcripts>/<session_id>/` exists its files are used
+//! instead, so parallel workers can be scripted independently. A transcript
+//! starting with `HTTP <status>` fails the request with that status.
+
+pub mod script;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{bail, Context, Result};
+use tokio::sync::broadcast;
+use tracing::info;
+
+use super::anthropic::cache;
+use super::anthropic::client::event_processor::EventAccumulator;
+use super::anthropic::client::sse_parser::drain_sse_events;
+use super::anthropic::types::{Message, MessagesRequest, UsageStats};
+use super::provider::ApiError;
+
+/// Next transcript index per script directory.
+fn cursors() -> &'static Mutex<HashMap<PathBuf, usize>> {
+    static CURSORS: OnceLock<Mutex<HashMap<PathBuf, usize>>> = OnceLock::new();
+    CURSORS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Requests received per transcripts root, in arrival order.
+fn recorded() -> &'static Mutex<HashMap<PathBuf, Vec<serde_json::Value>>> {
+    static RECORDED: OnceLock<Mutex<HashMap<PathBuf, Vec<serde_json::Value>>>> = OnceLock::new();
+    RECORDED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Replay the next scripted transcript for this session.
+pub async fn stream_messages(
+    transcripts: &Path,
+    request: &MessagesRequest,
+    tx: &broadcast::Sender<String>,
+    session_id: &str,
+    abort_flag: &Arc<AtomicBool>,
+) -> Result<(Message, UsageStats, String)> {
+    if abort_flag.load(Ordering::Relaxed) {
+        bail!("Aborted");
+    }
+
+    // Record the body as the real request builders would send it
+    if let Ok(mut value) = serde_json::to_value(request) {
+        if request.cache_conversation {
+            cache::mark_conversation(&mut value);
+        }
+        if let Ok(mut map) = recorded().lock() {
+            map.entry(transcripts.to_path_buf())
+                .or_default()
+                .push(value);
+        }
+    }
+
+    let path = next_transcript(transcripts, session_id)?;
+    info!(provider = "mock", %session_id, transcript = %path.display(), "Replaying mock transcript");
+    let mut buffer = std::fs::read_to_string(&path)
+        .with_context(|| format!("Reading mock transcript {}", path.display()))?
+        .replace("\r\n", "\n");
+    if let Some(error) = buffer.strip_prefix("HTTP ") {
+        let (status, body) = error.split_once('\n').unwrap_or((error, ""));
+        let status: u16 = status
+            .trim()
+            .parse()
+            .with_context(|| format!("Bad status line in {}", path.display()))?;
+        return Err(ApiError {
+            status,
+            message: format!("Mock API error ({status}): {}", body.trim()),
+        }
+        .into());
+    }
+    if !buffer.ends_with("\n\n") {
+        buffer.push_str("\n\n");
+    }
+
+    let init_event = serde_json::json!({
+        "type": "system",
+        "subtype": "init",
+        "session_id": session_id
+    });
+    let _ = tx.send(init_event.to_string());
+
+    let mut acc = EventAccumulator::new();
+    drain_sse_events(&mut buffer, tx, &mut acc);
+    Ok(acc.into_result())
+}
+
+/// Every request the mock has received for a transcripts directory, as JSON.
+pub fn recorded_requests(transcripts: &Path) -> Vec<serde_json::Value> {
+    recorded()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(transcripts).cloned())
+        .unwrap_or_default()
+}
+
+/// Pick the session's own script directory if present, else the shared one,
+/// and advance its cursor.
+fn next_transcript(transcripts: &Path, session_id: &str) -> Result<PathBuf> {
+    let session_dir = transcripts.join(session_id);
+    let dir = if session_dir.is_dir() {
+        session_dir
+    } else {
+        transcripts.to_path_buf()
+    };
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Reading mock transcripts in {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "sse"))
+        .collect();
+    files.sort();
+
+    let index = {
+        let mut map = cursors()
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Mock cursor lock poisoned"))?;
+        let cursor = map.entry(dir.clone()).or_insert(0);
+        let index = *cursor;
+        *cursor += 1;
+        index
+    };
+
+    match files.get(index) {
+        Some(path) => Ok(path.clone()),
+        None => bail!(
+            "Mock transcripts exhausted in {} ({} file(s), request #{})",
+            dir.display(),
+            files.len(),
+            index + 1
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+//! Helpers for writing mock transcripts in the Anthropic SSE format.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// One assistant turn in a transcript.
+pub enum Turn {
+    /// Plain text reply ending with `end_turn`.
+    Text(String),
+    /// Optional preamble text followed by a single tool call (`tool_use` stop).
+    ToolUse {
+        text: Option<String>,
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    /// The API rejects the request with this HTTP status.
+    ApiError { status: u16, message: String },
+}
+
+impl Turn {
+    pub fn text(text: impl Into<String>) -> Self {
+        Turn::Text(text.into())
+    }
+
+    pub fn tool_use(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        input: serde_json::Value,
+    ) -> Self {
+        Turn::ToolUse {
+            text: None,
+            id: id.into(),
+            name: name.into(),
+            input,
+        }
+    }
+
+    pub fn api_error(status: u16, message: impl Into<String>) -> Self {
+        Turn::ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Render the turn as an SSE transcript (or, for an API error, the
+    /// `HTTP <status>` line the mock answers with an error).
+    pub fn to_sse(&self) -> String {
+        if let Turn::ApiError { status, message } = self {
+            return format!("HTTP {status}\n{message}\n");
+        }
+        let mut out = String::new();
+        event(
+            &mut out,
+            "message_start",
+            serde_json::json!({
+                "type": "message_start",
+                "message": {"role": "assistant", "usage": {"input_tokens": 10, "output_tokens": 1}}
+            }),
+        );
+
+        let (text, tool, stop_reason) = match self {
+            Turn::Text(text) => (Some(text.as_str()), None, "end_turn"),
+            Turn::ToolUse {
+                text,
+                id,
+                name,
+                input,
+            } => (text.as_deref(), Some((id, name, input)), "tool_use"),
+            Turn::ApiError { .. } => unreachable!("rendered above"),
+        };
+
+        let mut index = 0;
+        if let Some(text) = text {
+            event(
+                &mut out,
+                "content_block_start",
+                serde_json::json!({"index": index, "content_block": {"type": "text", "text": ""}}),
+            );
+            event(
+                &mut out,
+                "content_block_delta",
+                serde_json::json!({"index": index, "delta": {"type": "text_delta", "text": text}}),
+            );
+            event(
+                &mut out,
+                "content_block_stop",
+                serde_json::json!({ "index": index }),
+            );
+            index += 1;
+        }
+        if let Some((id, name, input)) = tool {
+            event(
+                &mut out,
+                "content_block_start",
+                serde_json::json!({
+                    "index": index,
+                    "content_block": {"type": "tool_use", "id": id, "name": name, "input": {}}
+                }),
+            );
+            event(
+                &mut out,
+                "content_block_delta",
+                serde_json::json!({
+                    "index": index,
+                    "delta": {"type": "input_json_delta", "partial_json": input.to_string()}
+                }),
+            );
+            event(
+                &mut out,
+                "content_block_stop",
+                serde_json::json!({ "index": index }),
+            );
+        }
+
+        event(
+            &mut out,
+            "message_delta",
+            serde_json::json!({"delta": {"stop_reason": stop_reason}, "usage": {"output_tokens": 5}}),
+        );
+        event(
+            &mut out,
+            "message_stop",
+            serde_json::json!({"type": "message_stop"}),
+        );
+        out
+    }
+}
+
+/// Write `turns` as `001.sse`, `002.sse`, ... into `dir` (created if needed).
+pub fn write_turns(dir: &Path, turns: &[Turn]) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Creating transcript dir {}", dir.display()))?;
+    for (i, turn) in turns.iter().enumerate() {
+        let path = dir.join(format!("{:03}.sse", i + 1));
+        std::fs::write(&path, turn.to_sse())
+            .with_context(|| format!("Writing transcript {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn event(out: &mut String, name: &str, data: serde_json::Value) {
+    out.push_str(&format!("event: {name}\ndata: {data}\n\n"));
+}
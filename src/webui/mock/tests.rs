@@ -0,0 +1,95 @@
+use super::script::{write_turns, Turn};
+use super::*;
+use crate::webui::anthropic::types::{ContentBlock, MessageContent};
+
+fn request() -> MessagesRequest {
+    MessagesRequest {
+        model: "mock".to_string(),
+        max_tokens: 1024,
+        messages: Vec::new(),
+        system: None,
+        stream: true,
+        metadata: None,
+        tools: None,
+        tool_choice: None,
+        thinking: None,
+        temperature: None,
+        cache_conversation: false,
+    }
+}
+
+async fn replay(dir: &Path, session: &str) -> Result<(Message, UsageStats, String)> {
+    let (tx, _rx) = broadcast::channel(64);
+    let abort = Arc::new(AtomicBool::new(false));
+    stream_messages(dir, &request(), &tx, session, &abort).await
+}
+
+#[tokio::test]
+async fn test_replays_turns_in_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_turns(
+        tmp.path(),
+        &[
+            Turn::tool_use("tu_1", "Read", serde_json::json!({"file_path": "a.txt"})),
+            Turn::text("All done"),
+        ],
+    )
+    .unwrap();
+
+    let (msg, usage, stop) = replay(tmp.path(), "s").await.unwrap();
+    assert_eq!(stop, "tool_use");
+    assert_eq!(usage.output_tokens, 5);
+    let MessageContent::Blocks(blocks) = msg.content else {
+        panic!("expected blocks");
+    };
+    assert!(matches!(
+        &blocks[0],
+        ContentBlock::ToolUse { name, input, .. } if name == "Read" && input["file_path"] == "a.txt"
+    ));
+
+    let (msg, _, stop) = replay(tmp.path(), "s").await.unwrap();
+    assert_eq!(stop, "end_turn");
+    assert!(matches!(
+        msg.content,
+        MessageContent::Blocks(ref b) if matches!(&b[0], ContentBlock::Text { text } if text == "All done")
+    ));
+
+    let err = replay(tmp.path(), "s").await.unwrap_err();
+    assert!(err.to_string().contains("exhausted"));
+    assert_eq!(recorded_requests(tmp.path()).len(), 3);
+}
+
+#[tokio::test]
+async fn test_session_dir_takes_precedence() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_turns(tmp.path(), &[Turn::text("shared")]).unwrap();
+    write_turns(&tmp.path().join("worker"), &[Turn::text("own")]).unwrap();
+
+    let (msg, _, _) = replay(tmp.path(), "worker").await.unwrap();
+    let (other, _, _) = replay(tmp.path(), "verifier").await.unwrap();
+    let text = |m: &Message| match &m.content {
+        MessageContent::Blocks(b) => match &b[0] {
+            ContentBlock::Text { text } => text.clone(),
+            _ => String::new(),
+        },
+        MessageContent::Text(t) => t.clone(),
+    };
+    assert_eq!(text(&msg), "own");
+    assert_eq!(text(&other), "shared");
+}
+
+#[tokio::test]
+async fn test_api_error_turn_fails_with_status() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_turns(
+        tmp.path(),
+        &[Turn::api_error(529, "Overloaded"), Turn::text("Recovered")],
+    )
+    .unwrap();
+
+    let err = replay(tmp.path(), "s").await.unwrap_err();
+    let api = err.downcast_ref::<ApiError>().expect("typed API error");
+    assert_eq!(api.status, 529);
+    assert!(api.message.contains("Overloaded"));
+    assert!(replay(tmp.path(), "s").await.is_ok());
+}
@@ -1,4 +1,6 @@
+pub mod access;
 pub mod anthropic;
+pub mod api_limiter;
 pub mod auth;
 pub mod bedrock;
 pub mod chat;
@@ -7,30 +9,36 @@ pub mod extractors;
 pub mod git;
 pub mod logs;
 pub mod mcp_client;
+pub mod mock;
 pub mod monitor;
 pub mod projects;
 pub mod provider;
 pub mod status;
 pub mod tools;
 
+use access::AccessMode;
 use anyhow::Result;
-use axum::{response::Html, routing::get, Router};
+use axum::{middleware, response::Html, routing::get, Router};
+use monitor::MonitorState;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use tower_http::cors::CorsLayer;
 
-use monitor::MonitorState;
+pub use access::ServerOptions;
 
 const EMBEDDED_HTML: &str = include_str!("../../web/dist/index.html");
 
-pub fn run_server(port: u16) -> Result<()> {
+/// How long open connections (e.g. event streams) may hold up shutdown.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+pub fn run_server(opts: ServerOptions) -> Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
-    rt.block_on(start_server_async(port))
+    rt.block_on(start_server_async(opts))
 }
 
 /// Async version of `run_server` for embedding in an existing tokio runtime (e.g. Tauri).
-pub async fn start_server_async(port: u16) -> Result<()> {
+pub async fn start_server_async(opts: ServerOptions) -> Result<()> {
     let (tx, _rx) = broadcast::channel::<String>(256);
     let chat_sessions: chat::SessionStore = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
 
@@ -52,28 +60,80 @@ pub async fn start_server_async(port: u16) -> Result<()> {
         .merge(projects::routes())
         .merge(git::routes())
         .fallback(get(serve_index))
-        .layer(CorsLayer::permissive());
+        .layer(middleware::from_fn_with_state(
+            Arc::new(opts.access.clone()),
+            access::require_auth,
+        ))
+        .layer(opts.cors_layer());
 
-    println!("Hive WebUI running at http://localhost:{}", port);
-    if let Some(ip) = local_ip() {
-        println!("  Network: http://{}:{}", ip, port);
-    }
+    print_banner(&opts);
 
-    let listener = bind_with_reuse(port).await?;
-    axum::serve(listener, app).await?;
+    let listener = bind_with_reuse(opts.bind, opts.port).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    tools::background::kill_all();
 
     Ok(())
 }
 
+/// Resolves on Ctrl-C or SIGTERM. Chat sessions' shells and background
+/// processes run in their own process groups, so they are killed here
+/// rather than left behind; event streams never finish on their own, so
+/// the process exits once `SHUTDOWN_GRACE` has passed.
+async fn shutdown_signal() {
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+    tools::background::kill_all();
+    tokio::spawn(async {
+        tokio::time::sleep(SHUTDOWN_GRACE).await;
+        std::process::exit(0);
+    });
+}
+
 async fn serve_index() -> Html<&'static str> {
     Html(EMBEDDED_HTML)
 }
 
+fn print_banner(opts: &ServerOptions) {
+    let host = if opts.bind.is_unspecified() || opts.bind.is_loopback() {
+        "localhost".to_string()
+    } else {
+        opts.bind.to_string()
+    };
+    println!("Hive WebUI running at {}", opts.display_url(&host));
+    if opts.bind.is_unspecified() {
+        if let Some(ip) = local_ip() {
+            println!("  Network: {}", opts.display_url(&ip.to_string()));
+        }
+    }
+    match &opts.access {
+        AccessMode::Password(password) => println!("  Password: {password}"),
+        AccessMode::Token(_) => println!("  Auth: bearer token required on /api/*"),
+        AccessMode::Open if !opts.bind.is_loopback() => eprintln!(
+            "  Warning: listening on {} without authentication; use --auth-token or --auth-password",
+            opts.bind
+        ),
+        AccessMode::Open => {}
+    }
+}
+
 /// Bind a TCP listener with SO_REUSEADDR so `cargo-watch` restarts reclaim the port instantly.
-async fn bind_with_reuse(port: u16) -> Result<tokio::net::TcpListener> {
-    let addr: std::net::SocketAddr = format!("0.0.0.0:{port}").parse()?;
+async fn bind_with_reuse(ip: std::net::IpAddr, port: u16) -> Result<tokio::net::TcpListener> {
+    let addr = std::net::SocketAddr::new(ip, port);
     let socket = socket2::Socket::new(
-        socket2::Domain::IPV4,
+        socket2::Domain::for_address(addr),
         socket2::Type::STREAM,
         Some(socket2::Protocol::TCP),
     )?;
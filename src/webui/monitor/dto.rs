@@ -28,6 +28,17 @@ pub struct DroneInfo {
     pub progress: (usize, usize),
     pub cost: CostInfo,
     pub liveness: String,
+    /// Work awaiting a reviewer; diffs are fetched from `/approvals`
+    pub approvals: Vec<ApprovalInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalInfo {
+    /// Absent for the gate before the PR phase
+    pub task: Option<usize>,
+    pub title: String,
+    pub notes: String,
+    pub requested: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -69,4 +80,19 @@ pub struct CostInfo {
     pub output_tokens: u64,
     pub cache_creation_tokens: u64,
     pub cache_read_tokens: u64,
+    /// Native team drones only: cost per task id and per worker
+    pub by_task: Vec<CostBreakdownInfo>,
+    pub by_worker: Vec<CostBreakdownInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostBreakdownInfo {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    pub total_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
 }
@@ -10,6 +10,8 @@ use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
+use crate::backend::native_team::approvals::{self, PendingApproval};
+use crate::backend::native_team::control::{self, ControlRequest, ControlResponse};
 use crate::webui::error::{ApiError, ApiResult};
 use crate::webui::extractors::ValidJson;
 
@@ -187,3 +189,100 @@ pub async fn stop_drone(
         Err(e) => Err(ApiError::Internal(anyhow::anyhow!("Failed to stop: {e:#}"))),
     }
 }
+
+/// POST /api/drones/{name}/pause — stop workers after their current turn and save them.
+pub async fn pause_drone(
+    State(_state): State<Arc<MonitorState>>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let result = tokio::task::spawn_blocking(move || crate::commands::pause::pause(name))
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Pause failed: {e}")))?;
+
+    match result {
+        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
+        Err(e) => Err(ApiError::Conflict(format!("{e:#}"))),
+    }
+}
+
+/// POST /api/drones/{name}/resume — restart a paused drone's saved workers.
+pub async fn resume_drone(
+    State(_state): State<Arc<MonitorState>>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let result =
+        tokio::task::spawn_blocking(move || crate::commands::pause::resume(name, None, None))
+            .await
+            .map_err(|e| ApiError::Internal(anyhow::anyhow!("Resume failed: {e}")))?;
+
+    match result {
+        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
+        Err(e) => Err(ApiError::Conflict(format!("{e:#}"))),
+    }
+}
+
+/// GET /api/drones/{name}/approvals — work awaiting a reviewer, with diffs and notes.
+pub async fn drone_approvals(
+    State(_state): State<Arc<MonitorState>>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<Vec<PendingApproval>>> {
+    let drone_dir = std::path::PathBuf::from(".hive/drones").join(&name);
+    if !drone_dir.exists() {
+        return Err(ApiError::NotFound(format!("Drone '{name}' not found")));
+    }
+    Ok(Json(approvals::load(&drone_dir)))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApprovalDecisionRequest {
+    /// Plan task number; omit for the gate before the PR phase
+    #[serde(default)]
+    #[garde(skip)]
+    pub task: Option<usize>,
+    #[serde(default)]
+    #[garde(length(max = 20000))]
+    pub comment: Option<String>,
+}
+
+/// POST /api/drones/{name}/approve — approve a parked task or the PR.
+pub async fn approve_drone(
+    state: State<Arc<MonitorState>>,
+    path: Path<String>,
+    ValidJson(body): ValidJson<ApprovalDecisionRequest>,
+) -> ApiResult<Json<ControlResponse>> {
+    let request = ControlRequest::Approve {
+        task: body.task,
+        comment: body.comment,
+    };
+    control_drone(state, path, Json(request)).await
+}
+
+/// POST /api/drones/{name}/reject — send parked work back, or drop it without a comment.
+pub async fn reject_drone(
+    state: State<Arc<MonitorState>>,
+    path: Path<String>,
+    ValidJson(body): ValidJson<ApprovalDecisionRequest>,
+) -> ApiResult<Json<ControlResponse>> {
+    let request = ControlRequest::Reject {
+        task: body.task,
+        comment: body.comment,
+    };
+    control_drone(state, path, Json(request)).await
+}
+
+/// POST /api/drones/{name}/control — send a command to a running coordinator.
+pub async fn control_drone(
+    State(_state): State<Arc<MonitorState>>,
+    Path(name): Path<String>,
+    Json(request): Json<ControlRequest>,
+) -> ApiResult<Json<ControlResponse>> {
+    let drone_dir = std::path::PathBuf::from(".hive/drones").join(&name);
+    if !drone_dir.exists() {
+        return Err(ApiError::NotFound(format!("Drone '{name}' not found")));
+    }
+    let response = tokio::task::spawn_blocking(move || control::send(&drone_dir, &request))
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Control failed: {e}")))?
+        .map_err(|e| ApiError::Conflict(format!("{e:#}")))?;
+    Ok(Json(response))
+}
@@ -31,7 +31,9 @@ pub fn has_success_result(activity_log_path: &Path) -> bool {
 pub fn determine_liveness(project_root: &Path, drone_name: &str, status: &DroneState) -> String {
     match status {
         DroneState::Completed => "completed".to_string(),
-        DroneState::Stopped => "stopped".to_string(),
+        DroneState::Stopped | DroneState::BudgetExceeded | DroneState::Paused => {
+            "stopped".to_string()
+        }
         DroneState::Zombie => "dead".to_string(),
         DroneState::InProgress | DroneState::Starting | DroneState::Resuming => {
             let pid_alive = read_drone_pid_at(project_root, drone_name)
@@ -17,6 +17,15 @@ pub fn routes(state: Arc<MonitorState>) -> Router {
         .route("/api/drones", get(handlers::api_drones))
         .route("/api/drones/launch", post(handlers::launch_drone))
         .route("/api/drones/{name}/stop", post(handlers::stop_drone))
+        .route("/api/drones/{name}/control", post(handlers::control_drone))
+        .route("/api/drones/{name}/pause", post(handlers::pause_drone))
+        .route("/api/drones/{name}/resume", post(handlers::resume_drone))
+        .route(
+            "/api/drones/{name}/approvals",
+            get(handlers::drone_approvals),
+        )
+        .route("/api/drones/{name}/approve", post(handlers::approve_drone))
+        .route("/api/drones/{name}/reject", post(handlers::reject_drone))
         .route("/api/drones/{name}/clean", post(handlers::clean_drone))
         .route("/api/drones/{name}", get(handlers::api_drone_detail))
         .route("/api/events", get(handlers::api_events_sse))
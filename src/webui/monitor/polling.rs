@@ -1,14 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
 use crate::agent_teams::snapshot::TaskSnapshotStore;
 use crate::agent_teams::task_sync;
-use crate::commands::common::cost::{parse_cost_from_log, parse_cost_from_log_at, CostSummary};
+use crate::backend::native_team::approvals;
+use crate::commands::common::cost::{
+    parse_cost_from_log, parse_cost_from_log_at, CostBreakdown, CostSummary,
+};
 use crate::commands::common::{list_drones, list_drones_at};
 use crate::config;
 
-use super::dto::{CostInfo, DroneInfo, MemberInfo, MessageInfo, ProjectInfo, TaskInfo};
+use super::dto::{
+    ApprovalInfo, CostBreakdownInfo, CostInfo, DroneInfo, MemberInfo, MessageInfo, ProjectInfo,
+    TaskInfo,
+};
 use super::liveness::{
     compute_elapsed, compute_task_duration, determine_liveness, determine_member_liveness,
 };
@@ -122,6 +128,15 @@ pub fn poll_all_projects(snapshot_stores: &SnapshotStores) -> Vec<ProjectInfo> {
                 .collect();
 
             let messages = collect_messages(name);
+            let approvals = approvals::load(&root.join(".hive/drones").join(name))
+                .into_iter()
+                .map(|a| ApprovalInfo {
+                    task: a.task,
+                    title: a.title,
+                    notes: a.notes,
+                    requested: a.requested,
+                })
+                .collect();
             let elapsed = compute_elapsed(&status.started);
 
             drone_infos.push(DroneInfo {
@@ -142,6 +157,7 @@ pub fn poll_all_projects(snapshot_stores: &SnapshotStores) -> Vec<ProjectInfo> {
                 progress: snapshot.progress,
                 cost: cost_to_info(&cost),
                 liveness,
+                approvals,
             });
         }
 
@@ -195,5 +211,22 @@ fn cost_to_info(cost: &CostSummary) -> CostInfo {
         output_tokens: cost.output_tokens,
         cache_creation_tokens: cost.cache_creation_tokens,
         cache_read_tokens: cost.cache_read_tokens,
+        by_task: breakdown_to_info(&cost.by_task),
+        by_worker: breakdown_to_info(&cost.by_worker),
     }
 }
+
+fn breakdown_to_info(entries: &BTreeMap<String, CostBreakdown>) -> Vec<CostBreakdownInfo> {
+    entries
+        .iter()
+        .map(|(name, c)| CostBreakdownInfo {
+            name: name.clone(),
+            task: c.task.clone(),
+            total_usd: c.cost_usd,
+            input_tokens: c.input_tokens,
+            output_tokens: c.output_tokens,
+            cache_creation_tokens: c.cache_creation_tokens,
+            cache_read_tokens: c.cache_read_tokens,
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
-//! Provider dispatch — routes API calls to the correct backend (Anthropic or Bedrock).
+//! Provider dispatch — routes API calls to the correct backend (Anthropic, Bedrock,
+//! or the scripted mock).
 
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -10,19 +11,80 @@ use tracing::{debug, info};
 use super::anthropic::types::{Message, MessagesRequest, UsageStats};
 use crate::webui::auth::credentials::Credentials;
 
-/// Stream a Messages API request through the appropriate provider.
+/// A provider answered with an unsuccessful HTTP status (after its own
+/// retries). Returned through `anyhow` so callers can downcast it and tell
+/// transient failures from permanent ones.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Stream a Messages API request through the appropriate provider, after
+/// waiting for a slot in the machine-wide API limiter.
 pub async fn stream_messages(
     creds: &Credentials,
     request: &MessagesRequest,
     tx: &broadcast::Sender<String>,
     session_id: &str,
     abort_flag: &Arc<AtomicBool>,
+) -> Result<(Message, UsageStats, String)> {
+    let permit = super::api_limiter::acquire(creds, abort_flag).await?;
+    let result = dispatch_stream(creds, request, tx, session_id, abort_flag).await;
+    if let Some(mut permit) = permit {
+        if let Ok((_, usage, _)) = &result {
+            permit.record(usage_tokens(usage));
+        }
+        permit.release().await;
+    }
+    result
+}
+
+/// Send a non-streaming Messages API request to Anthropic, after waiting for
+/// a slot in the machine-wide API limiter.
+pub async fn call_messages(
+    creds: &Credentials,
+    request: &MessagesRequest,
+) -> Result<(Message, UsageStats)> {
+    let permit = super::api_limiter::acquire(creds, &AtomicBool::new(false)).await?;
+    let result = super::anthropic::client::call_messages(creds, request).await;
+    if let Some(mut permit) = permit {
+        if let Ok((_, usage)) = &result {
+            permit.record(usage_tokens(usage));
+        }
+        permit.release().await;
+    }
+    result
+}
+
+/// Tokens a request counts against the limiter's `tokens_per_minute`.
+fn usage_tokens(usage: &UsageStats) -> u64 {
+    usage.input_tokens + usage.cache_creation_input_tokens + usage.output_tokens
+}
+
+async fn dispatch_stream(
+    creds: &Credentials,
+    request: &MessagesRequest,
+    tx: &broadcast::Sender<String>,
+    session_id: &str,
+    abort_flag: &Arc<AtomicBool>,
 ) -> Result<(Message, UsageStats, String)> {
     match creds {
         Credentials::Bedrock { .. } | Credentials::BedrockProfile { .. } => {
             info!(provider = "bedrock", model = %request.model, %session_id, "Routing to Bedrock provider");
             super::bedrock::stream_messages(creds, request, tx, session_id, abort_flag).await
         }
+        Credentials::Mock { transcripts } => {
+            super::mock::stream_messages(transcripts, request, tx, session_id, abort_flag).await
+        }
         _ => {
             info!(provider = "anthropic", model = %request.model, %session_id, "Routing to Anthropic provider");
             super::anthropic::client::stream_messages(creds, request, tx, session_id, abort_flag)
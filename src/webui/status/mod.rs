@@ -104,6 +104,11 @@ fn build_auth_summary() -> AuthStatusSummary {
                 auth_type: Some("bedrock".to_string()),
                 expired: false,
             },
+            credentials::Credentials::Mock { .. } => AuthStatusSummary {
+                configured: true,
+                auth_type: Some("mock".to_string()),
+                expired: false,
+            },
         },
         _ => AuthStatusSummary {
             configured: false,
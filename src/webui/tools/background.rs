@@ -0,0 +1,471 @@
+//! Background processes for the BashBackground, ReadProcessOutput and
+//! KillProcess tools.
+//!
+//! A `ProcessRegistry` belongs to a chat session, a worker or a single
+//! agentic loop. Each process runs `bash -c` in its own process group, with
+//! the last `BUFFER_BYTES` of its stdout and stderr kept in ring buffers
+//! until the model reads them. Dropping the registry kills every process it
+//! started, and `kill_all` does the same for the whole hive process when it
+//! is told to stop.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Child;
+
+use super::output;
+use super::sandbox::{self, ProcessSandbox};
+
+pub const START_TOOL: &str = "BashBackground";
+pub const READ_TOOL: &str = "ReadProcessOutput";
+pub const KILL_TOOL: &str = "KillProcess";
+
+/// Processes one registry may have running at once.
+const MAX_RUNNING: usize = 8;
+/// Output kept per stream; older bytes are dropped.
+const BUFFER_BYTES: usize = 64 * 1024;
+const MAX_WAIT_MS: u64 = 30_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Output returned by one ReadProcessOutput call, per stream.
+const MAX_OUTPUT_BYTES: usize = 30_000;
+
+/// Process groups started by any registry in this process.
+static LIVE_GROUPS: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+
+/// Kill every background process group this hive process started. For
+/// shutdown paths that exit without dropping their registries.
+pub fn kill_all() {
+    let groups = std::mem::take(&mut *LIVE_GROUPS.lock().unwrap_or_else(|e| e.into_inner()));
+    for pid in groups {
+        kill_group(pid);
+    }
+}
+
+pub(super) fn track_group(pid: u32) {
+    LIVE_GROUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(pid);
+}
+
+/// Stop tracking a group whose leader has been reaped, without signalling it.
+pub(super) fn untrack_group(pid: u32) {
+    LIVE_GROUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&pid);
+}
+
+/// SIGKILL the group. The leader may be gone already: a pid isn't reused
+/// while it is still a live process's group id, and ESRCH means the whole
+/// group has exited.
+pub(super) fn kill_group(pid: u32) {
+    LIVE_GROUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&pid);
+    let _ = nix::sys::signal::killpg(
+        nix::unistd::Pid::from_raw(pid as i32),
+        nix::sys::signal::Signal::SIGKILL,
+    );
+}
+
+/// Whether any process is left in group `pid`.
+fn group_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    match nix::sys::signal::killpg(nix::unistd::Pid::from_raw(pid as i32), None) {
+        Ok(()) | Err(Errno::EPERM) => true,
+        Err(_) => false,
+    }
+}
+
+/// The tail of one output stream.
+#[derive(Default)]
+struct OutputBuffer {
+    data: VecDeque<u8>,
+    /// Bytes written since the process started
+    total: u64,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend(bytes);
+        self.total += bytes.len() as u64;
+        let excess = self.data.len().saturating_sub(BUFFER_BYTES);
+        self.data.drain(..excess);
+    }
+
+    /// Output written after byte `pos`, and how much of it was dropped
+    /// before it could be read.
+    fn since(&self, pos: u64) -> (String, u64) {
+        let start = self.total - self.data.len() as u64;
+        let dropped = start.saturating_sub(pos);
+        let skip = pos.saturating_sub(start) as usize;
+        let bytes: Vec<u8> = self.data.iter().skip(skip).copied().collect();
+        (String::from_utf8_lossy(&bytes).into_owned(), dropped)
+    }
+}
+
+struct BackgroundProcess {
+    command: String,
+    pid: u32,
+    child: Child,
+    stdout: Arc<Mutex<OutputBuffer>>,
+    stderr: Arc<Mutex<OutputBuffer>>,
+    /// How far the model has read each stream
+    read: (u64, u64),
+    exit_code: Option<i32>,
+    /// Set once every process in the group has exited; its id may then be
+    /// reused, so it is never signalled again
+    group_gone: bool,
+}
+
+impl BackgroundProcess {
+    /// Exit code, once the process has exited.
+    fn poll_exit(&mut self) -> Option<i32> {
+        if self.exit_code.is_none() {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                self.exit_code = Some(status.code().unwrap_or(-1));
+            }
+        }
+        self.exit_code
+    }
+
+    /// Whether the process, or anything it started, is still running.
+    fn running(&mut self) -> bool {
+        if self.poll_exit().is_none() {
+            return true;
+        }
+        if !self.group_gone && !group_alive(self.pid) {
+            self.group_gone = true;
+            untrack_group(self.pid);
+        }
+        !self.group_gone
+    }
+
+    fn has_unread(&self) -> bool {
+        let total = |b: &Arc<Mutex<OutputBuffer>>| b.lock().map(|b| b.total).unwrap_or(0);
+        total(&self.stdout) > self.read.0 || total(&self.stderr) > self.read.1
+    }
+
+    fn status(&mut self) -> String {
+        match self.poll_exit() {
+            Some(code) if self.running() => {
+                format!("exited with code {code}, but processes it started are still running")
+            }
+            Some(code) => format!("exited with code {code}"),
+            None => "running".to_string(),
+        }
+    }
+
+    /// Take the output the model hasn't seen yet.
+    fn take_unread(&mut self) -> String {
+        let take = |buffer: &Arc<Mutex<OutputBuffer>>, pos: &mut u64| {
+            let buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+            let (text, dropped) = buffer.since(*pos);
+            *pos = buffer.total;
+            let text = output::truncate_output(&text, MAX_OUTPUT_BYTES);
+            match dropped {
+                0 => text,
+                n => format!("[{n} earlier bytes dropped]\n{text}"),
+            }
+        };
+        let stdout = take(&self.stdout, &mut self.read.0);
+        let stderr = take(&self.stderr, &mut self.read.1);
+
+        let mut result = stdout;
+        if !stderr.is_empty() {
+            if !result.is_empty() && !result.ends_with('\n') {
+                result.push('\n');
+            }
+            result.push_str("STDERR:\n");
+            result.push_str(&stderr);
+        }
+        if result.is_empty() {
+            result.push_str("(no new output)");
+        }
+        result
+    }
+}
+
+impl Drop for BackgroundProcess {
+    fn drop(&mut self) {
+        // Also after the leader exits: `nohup server &` leaves the server
+        // in the group
+        if !self.group_gone {
+            kill_group(self.pid);
+        }
+    }
+}
+
+/// Background processes started by one session, worker or agentic loop.
+#[derive(Default)]
+pub struct ProcessRegistry {
+    processes: Mutex<BTreeMap<usize, BackgroundProcess>>,
+    next_id: AtomicUsize,
+}
+
+impl ProcessRegistry {
+    /// Start `command` in the background and return its id.
+    pub fn start(
+        &self,
+        command: &str,
+        cwd: &Path,
+        sandbox: Option<&ProcessSandbox>,
+    ) -> Result<usize> {
+        let mut processes = self.lock();
+        // Forget processes that are gone and whose output has all been read
+        processes.retain(|_, p| p.running() || p.has_unread());
+        let running = processes
+            .values_mut()
+            .map(|p| p.running())
+            .filter(|&r| r)
+            .count();
+        if running >= MAX_RUNNING {
+            bail!(
+                "{running} background processes are already running. Kill one with {KILL_TOOL} first"
+            );
+        }
+
+        let mut child = sandbox::shell_command(sandbox, "bash", command, cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // Its own process group, so whatever it starts can be killed with it
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to spawn background bash process")?;
+        let pid = child
+            .id()
+            .context("Background process exited immediately")?;
+        track_group(pid);
+
+        let stdout = Arc::new(Mutex::new(OutputBuffer::default()));
+        let stderr = Arc::new(Mutex::new(OutputBuffer::default()));
+        if let Some(pipe) = child.stdout.take() {
+            tokio::spawn(pump(pipe, stdout.clone()));
+        }
+        if let Some(pipe) = child.stderr.take() {
+            tokio::spawn(pump(pipe, stderr.clone()));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        processes.insert(
+            id,
+            BackgroundProcess {
+                command: command.to_string(),
+                pid,
+                child,
+                stdout,
+                stderr,
+                read: (0, 0),
+                exit_code: None,
+                group_gone: false,
+            },
+        );
+        Ok(id)
+    }
+
+    /// New output of process `id` and its status. Waits up to `wait` for
+    /// output to appear or the process to exit.
+    pub async fn read(&self, id: usize, wait: Duration) -> Result<String> {
+        let deadline = tokio::time::Instant::now() + wait;
+        let exited = loop {
+            {
+                let mut processes = self.lock();
+                let Some(process) = processes.get_mut(&id) else {
+                    return Err(unknown(&processes, id));
+                };
+                let exited = process.poll_exit().is_some();
+                if exited || process.has_unread() || tokio::time::Instant::now() >= deadline {
+                    break exited;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+        if exited {
+            // Let the pipes drain before the last read
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let mut processes = self.lock();
+        let Some(process) = processes.get_mut(&id) else {
+            return Err(unknown(&processes, id));
+        };
+        let header = format!("Process {id} ({}): {}", process.status(), process.command);
+        Ok(format!("{header}\n{}", process.take_unread()))
+    }
+
+    /// Kill process `id` and everything it started, returning its unread
+    /// output.
+    pub fn kill(&self, id: usize) -> Result<String> {
+        let mut processes = self.lock();
+        let Some(mut process) = processes.remove(&id) else {
+            return Err(unknown(&processes, id));
+        };
+        drop(processes);
+        let status = process.status();
+        let output = process.take_unread();
+        drop(process);
+        Ok(format!(
+            "Killed process {id} (was {status}). Unread output:\n{output}"
+        ))
+    }
+
+    /// Kill every process in the registry.
+    pub fn shutdown(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<usize, BackgroundProcess>> {
+        self.processes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unknown(processes: &BTreeMap<usize, BackgroundProcess>, id: usize) -> anyhow::Error {
+    if processes.is_empty() {
+        return anyhow::anyhow!("No background process with id {id}. None were started");
+    }
+    let known: Vec<String> = processes
+        .iter()
+        .map(|(id, p)| format!("{id}: {}", p.command))
+        .collect();
+    anyhow::anyhow!(
+        "No background process with id {id}. Known processes:\n{}",
+        known.join("\n")
+    )
+}
+
+async fn pump(mut pipe: impl AsyncRead + Unpin, buffer: Arc<Mutex<OutputBuffer>>) {
+    let mut chunk = [0u8; 8192];
+    loop {
+        match pipe.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buffer
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(&chunk[..n]),
+        }
+    }
+}
+
+/// Run one of the background process tools.
+pub async fn execute(
+    registry: &ProcessRegistry,
+    name: &str,
+    input: &serde_json::Value,
+    cwd: &Path,
+    sandbox: Option<&ProcessSandbox>,
+) -> Result<String> {
+    let id = || {
+        input
+            .get("id")
+            .and_then(|v| v.as_u64())
+            .map(|id| id as usize)
+            .context("Missing required parameter: id")
+    };
+    match name {
+        START_TOOL => {
+            let command = input
+                .get("command")
+                .and_then(|v| v.as_str())
+                .context("Missing required parameter: command")?;
+            let id = registry.start(command, cwd, sandbox)?;
+            Ok(format!(
+                "Started background process {id}. Use {READ_TOOL} with id {id} to see its output \
+                 and {KILL_TOOL} to stop it."
+            ))
+        }
+        READ_TOOL => {
+            let wait_ms = input
+                .get("wait_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+                .min(MAX_WAIT_MS);
+            registry.read(id()?, Duration::from_millis(wait_ms)).await
+        }
+        KILL_TOOL => registry.kill(id()?),
+        _ => bail!("Unknown background process tool: {name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_buffer_keeps_the_tail() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push(b"hello ");
+        assert_eq!(buffer.since(0), ("hello ".to_string(), 0));
+        buffer.push(&vec![b'x'; BUFFER_BYTES]);
+        let (text, dropped) = buffer.since(3);
+        assert_eq!((text.len(), dropped), (BUFFER_BYTES, 3));
+        assert_eq!(buffer.since(buffer.total), (String::new(), 0));
+    }
+
+    #[tokio::test]
+    async fn test_start_read_and_kill() {
+        let tmp = tempfile::tempdir().unwrap();
+        let registry = ProcessRegistry::default();
+
+        let id = registry
+            .start("echo ready; echo oops >&2; sleep 30", tmp.path(), None)
+            .unwrap();
+        let out = registry.read(id, Duration::from_secs(5)).await.unwrap();
+        assert!(out.starts_with(&format!("Process {id} (running)")), "{out}");
+        assert!(out.contains("ready"), "{out}");
+
+        // Only new output is returned
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let out = registry.read(id, Duration::ZERO).await.unwrap();
+        assert!(!out.lines().skip(1).any(|l| l == "ready"), "{out}");
+
+        let out = registry.kill(id).unwrap();
+        assert!(out.starts_with(&format!("Killed process {id}")), "{out}");
+        let err = registry.read(id, Duration::ZERO).await.unwrap_err();
+        assert!(err.to_string().contains("No background process"));
+    }
+
+    #[tokio::test]
+    async fn test_exit_status_and_reaping_on_drop() {
+        let tmp = tempfile::tempdir().unwrap();
+        let registry = ProcessRegistry::default();
+
+        let id = registry
+            .start("echo done; exit 4", tmp.path(), None)
+            .unwrap();
+        let out = registry.read(id, Duration::from_secs(5)).await.unwrap();
+        assert!(out.contains("exited with code 4"), "{out}");
+        assert!(out.contains("done"), "{out}");
+        let pid = registry.lock()[&id].pid;
+
+        // A process the command left running dies with the registry, even
+        // though the command itself has exited
+        let marker = tmp.path().join("alive");
+        let script = format!(
+            "(while true; do touch {}; sleep 0.1; done) &",
+            marker.display()
+        );
+        let id = registry.start(&script, tmp.path(), None).unwrap();
+        // The exited, fully read process made way for it and its group,
+        // now empty, is never signalled again
+        assert_eq!(registry.lock().len(), 1);
+        assert!(!LIVE_GROUPS.lock().unwrap().contains(&pid));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let out = registry.read(id, Duration::ZERO).await.unwrap();
+        assert!(out.contains("still running"), "{out}");
+        drop(registry);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        std::fs::remove_file(&marker).unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!marker.exists(), "background process outlived its registry");
+    }
+}
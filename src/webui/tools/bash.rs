@@ -2,40 +2,68 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
+use super::background::{kill_group, track_group, untrack_group};
 use super::output;
-use super::sandbox;
+use super::sandbox::{self, ProcessSandbox};
+use super::shell::PersistentShell;
 
 const DEFAULT_TIMEOUT_MS: u64 = 120_000;
 const MAX_TIMEOUT_MS: u64 = 600_000;
 const MAX_OUTPUT_BYTES: usize = 30_000;
 
-pub async fn execute(input: &serde_json::Value, cwd: &Path) -> Result<String> {
+/// Run a command with `bash -c`, or in `shell` when the session keeps a
+/// persistent one.
+pub async fn execute(
+    input: &serde_json::Value,
+    cwd: &Path,
+    sandbox: Option<&ProcessSandbox>,
+    shell: Option<&PersistentShell>,
+) -> Result<String> {
     let command = input
         .get("command")
         .and_then(|v| v.as_str())
         .context("Missing required parameter: command")?;
 
-    sandbox::check_dangerous_command(command)?;
-
     let timeout_ms = input
         .get("timeout")
         .and_then(|v| v.as_u64())
         .unwrap_or(DEFAULT_TIMEOUT_MS)
         .min(MAX_TIMEOUT_MS);
+    let timeout_duration = std::time::Duration::from_millis(timeout_ms);
+
+    if let Some(shell) = shell {
+        let output = shell.run(command, timeout_duration).await?;
+        let Some(exit_code) = output.exit_code else {
+            let mut result = format_output(&output.stdout, &output.stderr, 0);
+            result.push_str(
+                "\nThe shell exited. The next command starts a fresh one in the original working directory.",
+            );
+            return Ok(result);
+        };
+        return Ok(format_output(&output.stdout, &output.stderr, exit_code));
+    }
 
-    let child = tokio::process::Command::new("bash")
-        .arg("-c")
-        .arg(command)
-        .current_dir(cwd)
+    let child = sandbox::shell_command(sandbox, "bash", command, cwd)
+        .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
+        // Its own process group, so a timeout kills everything it started
+        .process_group(0)
+        .kill_on_drop(true)
         .spawn()
         .context("Failed to spawn bash process")?;
+    // Also fires when the caller drops this future, e.g. a worker timing out
+    let mut group = GroupGuard(child.id());
+    if let Some(pid) = group.0 {
+        track_group(pid);
+    }
 
-    let timeout_duration = std::time::Duration::from_millis(timeout_ms);
     let output_result = match tokio::time::timeout(timeout_duration, child.wait_with_output()).await
     {
-        Ok(result) => result.context("Command execution failed")?,
+        Ok(result) => {
+            group.release();
+            result.context("Command execution failed")?
+        }
         Err(_) => {
             anyhow::bail!("Command timed out after {}ms", timeout_ms);
         }
@@ -44,11 +72,33 @@ pub async fn execute(input: &serde_json::Value, cwd: &Path) -> Result<String> {
     let stdout = String::from_utf8_lossy(&output_result.stdout);
     let stderr = String::from_utf8_lossy(&output_result.stderr);
     let exit_code = output_result.status.code().unwrap_or(-1);
+    Ok(format_output(&stdout, &stderr, exit_code))
+}
+
+/// Kills the command's process group unless released after it exited.
+struct GroupGuard(Option<u32>);
+
+impl GroupGuard {
+    fn release(&mut self) {
+        if let Some(pid) = self.0.take() {
+            untrack_group(pid);
+        }
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0.take() {
+            kill_group(pid);
+        }
+    }
+}
 
+fn format_output(stdout: &str, stderr: &str, exit_code: i32) -> String {
     let mut result = String::new();
 
     if !stdout.is_empty() {
-        let truncated_stdout = output::truncate_output(&stdout, MAX_OUTPUT_BYTES);
+        let truncated_stdout = output::truncate_output(stdout, MAX_OUTPUT_BYTES);
         result.push_str(&truncated_stdout);
     }
 
@@ -56,7 +106,7 @@ pub async fn execute(input: &serde_json::Value, cwd: &Path) -> Result<String> {
         if !result.is_empty() {
             result.push('\n');
         }
-        let truncated_stderr = output::truncate_output(&stderr, MAX_OUTPUT_BYTES);
+        let truncated_stderr = output::truncate_output(stderr, MAX_OUTPUT_BYTES);
         result.push_str("STDERR:\n");
         result.push_str(&truncated_stderr);
     }
@@ -72,5 +122,35 @@ pub async fn execute(input: &serde_json::Value, cwd: &Path) -> Result<String> {
         result = format!("Command completed with exit code {exit_code}");
     }
 
-    Ok(result)
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeout_kills_the_whole_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pid_file = tmp.path().join("pid");
+        let input = serde_json::json!({
+            "command": format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+            "timeout": 500,
+        });
+        let err = execute(&input, tmp.path(), None, None).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+
+        let pid: u32 = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        // Orphans may linger as zombies if nothing reaps them
+        let state = std::fs::read_to_string(format!("/proc/{pid}/stat")).unwrap_or_default();
+        assert!(
+            state.is_empty() || state.contains(") Z "),
+            "sleep outlived the timeout: {state}"
+        );
+    }
 }
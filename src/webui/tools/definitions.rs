@@ -24,6 +24,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["file_path"]
             }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "Write".to_string(),
@@ -42,6 +43,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["file_path", "content"]
             }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "Edit".to_string(),
@@ -68,6 +70,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["file_path", "old_string", "new_string"]
             }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "Bash".to_string(),
@@ -86,6 +89,56 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["command"]
             }),
+            cache_control: None,
+        },
+        ToolDefinition {
+            name: "BashBackground".to_string(),
+            description: "Start a long-running bash command in the background, such as a dev server or file watcher, and return its id at once. Use ReadProcessOutput to see its output and KillProcess to stop it. Background processes are killed when the session ends.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The bash command to run in the background"
+                    }
+                },
+                "required": ["command"]
+            }),
+            cache_control: None,
+        },
+        ToolDefinition {
+            name: "ReadProcessOutput".to_string(),
+            description: "Read the output a background process has written since the last read, and whether it is still running.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "integer",
+                        "description": "The id BashBackground returned"
+                    },
+                    "wait_ms": {
+                        "type": "integer",
+                        "description": "Wait up to this long for new output or the process to exit (default: 0, max: 30000)"
+                    }
+                },
+                "required": ["id"]
+            }),
+            cache_control: None,
+        },
+        ToolDefinition {
+            name: "KillProcess".to_string(),
+            description: "Stop a background process and everything it started, returning its unread output.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "integer",
+                        "description": "The id BashBackground returned"
+                    }
+                },
+                "required": ["id"]
+            }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "Grep".to_string(),
@@ -117,6 +170,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["pattern"]
             }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "Glob".to_string(),
@@ -135,6 +189,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["pattern"]
             }),
+            cache_control: None,
         },
         ToolDefinition {
             name: "SessionSearch".to_string(),
@@ -153,6 +208,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": ["query"]
             }),
+            cache_control: None,
         },
         super::tool_search::tool_search_definition(),
         ToolDefinition {
@@ -168,6 +224,7 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
                 },
                 "required": []
             }),
+            cache_control: None,
         },
     ]
 }
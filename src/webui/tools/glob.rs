@@ -43,7 +43,7 @@ pub async fn execute(input: &serde_json::Value, cwd: &Path) -> Result<String> {
         }
 
         // Sort by modification time, most recent first
-        results.sort_by(|a, b| b.0.cmp(&a.0));
+        results.sort_by_key(|r| std::cmp::Reverse(r.0));
 
         Ok(results.into_iter().map(|(_, path)| path).collect())
     })
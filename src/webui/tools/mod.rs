@@ -1,34 +1,83 @@
+pub mod background;
 pub mod bash;
 pub mod definitions;
 pub mod edit;
 pub mod glob;
 pub mod grep;
 pub mod output;
+pub mod permissions;
+pub mod propose_task;
 pub mod read;
 pub mod sandbox;
 pub mod session_search;
+pub mod shell;
 pub mod tool_search;
 pub mod write;
+pub mod write_policy;
 
 use std::path::Path;
 
+pub use background::ProcessRegistry;
+pub use permissions::Permissions;
+pub use propose_task::TaskProposer;
+pub use sandbox::ProcessSandbox;
+pub use shell::PersistentShell;
+pub use write_policy::WritePolicy;
+
 /// Result of executing a tool: content string and whether it was an error.
 pub struct ToolExecutionResult {
     pub content: String,
     pub is_error: bool,
 }
 
+/// Where the shell commands of built-in tools run.
+#[derive(Clone, Copy, Default)]
+pub struct ProcessContext<'a> {
+    /// Sandbox for Bash and BashBackground commands
+    pub sandbox: Option<&'a ProcessSandbox>,
+    /// Shell shared by Bash calls; each call gets a fresh `bash -c` without one
+    pub shell: Option<&'a PersistentShell>,
+    /// Registry for background processes; those tools are unknown without one
+    pub processes: Option<&'a ProcessRegistry>,
+}
+
 /// Execute a built-in tool by name. Returns None if the tool name is not recognized.
+///
+/// Calls the `permissions` refuse, or that need approval nobody gives, and
+/// writes a `write_policy` rejects are returned as tool errors without
+/// executing the tool.
 pub async fn execute_tool(
     name: &str,
     input: &serde_json::Value,
     cwd: &Path,
+    permissions: &Permissions,
+    write_policy: Option<&dyn WritePolicy>,
+    process: ProcessContext<'_>,
 ) -> Option<ToolExecutionResult> {
+    if let Err(e) = permissions.enforce(name, input, cwd).await {
+        return Some(ToolExecutionResult {
+            content: format!("{e:#}"),
+            is_error: true,
+        });
+    }
+    if let Some(policy) = write_policy {
+        if let Err(e) = write_policy::enforce(policy, name, input, cwd) {
+            return Some(ToolExecutionResult {
+                content: format!("{e:#}"),
+                is_error: true,
+            });
+        }
+    }
+
     let result = match name {
         "Read" => read::execute(input, cwd).await,
         "Write" => write::execute(input, cwd).await,
         "Edit" => edit::execute(input, cwd).await,
-        "Bash" => bash::execute(input, cwd).await,
+        "Bash" => bash::execute(input, cwd, process.sandbox, process.shell).await,
+        background::START_TOOL | background::READ_TOOL | background::KILL_TOOL => {
+            let registry = process.processes?;
+            background::execute(registry, name, input, cwd, process.sandbox).await
+        }
         "Grep" => grep::execute(input, cwd).await,
         "Glob" => glob::execute(input, cwd).await,
         "SessionSearch" => session_search::execute_search(input, cwd).await,
@@ -0,0 +1,63 @@
+//! `ProposeTask` tool: lets a native-team worker add a follow-up task to
+//! its drone's task graph instead of doing out-of-scope work itself.
+//!
+//! The tool is only offered to loops given a `TaskProposer`; the proposer
+//! decides whether the task is accepted and which number it gets.
+
+use crate::webui::anthropic::types::ToolDefinition;
+
+use super::ToolExecutionResult;
+
+/// Accepts follow-up tasks proposed by an agent.
+pub trait TaskProposer: Send + Sync {
+    /// Queue the proposed task. Returns a confirmation for the model, or
+    /// `Err(reason)` if the proposal is rejected.
+    fn propose(&self, input: &serde_json::Value) -> Result<String, String>;
+}
+
+/// Run a `ProposeTask` call against the proposer.
+pub fn execute(proposer: &dyn TaskProposer, input: &serde_json::Value) -> ToolExecutionResult {
+    match proposer.propose(input) {
+        Ok(content) => ToolExecutionResult {
+            content,
+            is_error: false,
+        },
+        Err(content) => ToolExecutionResult {
+            content,
+            is_error: true,
+        },
+    }
+}
+
+/// Definition for the ProposeTask tool.
+pub fn propose_task_definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "ProposeTask".to_string(),
+        description: "Propose a follow-up task for another worker when you discover necessary work outside the scope of your own task (e.g. a missing migration or a broken shared helper). The task is added to the drone's plan and scheduled once its dependencies complete. Do not use it to split up your own task.".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "Short imperative title for the task"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "What needs to be done and why, with enough context for a worker that has not seen your task"
+                },
+                "depends_on": {
+                    "type": "array",
+                    "items": {"type": "integer"},
+                    "description": "Task numbers that must complete before this task starts"
+                },
+                "files": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Files the task is expected to modify"
+                }
+            },
+            "required": ["title", "description"]
+        }),
+        cache_control: None,
+    }
+}
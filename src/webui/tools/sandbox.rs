@@ -1,7 +1,10 @@
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 
+use crate::types::{SandboxConfig, SandboxMode};
+
 /// Resolve a file path relative to the session cwd, ensuring it's valid.
 /// Allows absolute paths that exist, and resolves relative paths against cwd.
 pub fn validate_path(path: &str, cwd: &Path) -> Result<PathBuf> {
@@ -34,23 +37,304 @@ pub fn validate_path(path: &str, cwd: &Path) -> Result<PathBuf> {
     Ok(canonical)
 }
 
-/// Check if a bash command is potentially dangerous and should be blocked.
-pub fn check_dangerous_command(command: &str) -> Result<()> {
-    let dangerous_patterns = [
-        "rm -rf /",
-        "rm -rf /*",
-        "mkfs.",
-        "dd if=/dev/zero",
-        ":(){ :|:& };:",
-        "> /dev/sda",
-    ];
+/// Runs drone shell commands inside bubblewrap: the file system is read-only
+/// except for the worktree, the temp dirs, the repository's git directory
+/// and any configured `writable` paths, the network can be cut off, and
+/// resource limits apply to every process started.
+///
+/// The git directory's hooks and config stay read-only: the coordinator
+/// runs git there outside the sandbox, and would run whatever they name.
+#[derive(Debug, Clone)]
+pub struct ProcessSandbox {
+    config: SandboxConfig,
+    writable: Vec<PathBuf>,
+    /// Bound read-only over the writable paths
+    protected: Vec<PathBuf>,
+}
+
+impl ProcessSandbox {
+    /// The sandbox for commands run in `root`, or `None` when the mode is off.
+    /// `git_dir` is the repository's shared git directory from
+    /// `git_common_dir`, looked up once per drone.
+    pub fn new(
+        config: &SandboxConfig,
+        root: &Path,
+        git_dir: Option<&Path>,
+    ) -> Option<ProcessSandbox> {
+        if config.mode() == SandboxMode::Off {
+            return None;
+        }
+        let home = dirs::home_dir();
+        let mut writable = vec![
+            root.to_path_buf(),
+            std::env::temp_dir(),
+            PathBuf::from("/tmp"),
+        ];
+        // Commits from a worktree write to the main repository's git dir
+        writable.extend(git_dir.map(Path::to_path_buf));
+        let mut protected = Vec::new();
+        if let Some(git_dir) = git_dir {
+            // A hooks dir created inside the sandbox would be writable
+            let _ = std::fs::create_dir_all(git_dir.join("hooks"));
+            protected.extend(
+                [git_dir.join("hooks"), git_dir.join("config")]
+                    .iter()
+                    .filter_map(|p| p.canonicalize().ok()),
+            );
+        }
+        writable.extend(config.writable.iter().filter_map(|p| {
+            match (p.strip_prefix("~/"), &home) {
+                (Some(rest), Some(home)) => Some(home.join(rest)),
+                (Some(_), None) => None,
+                (None, _) => Some(root.join(p)),
+            }
+        }));
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for path in writable {
+            // bwrap refuses to bind paths that don't exist
+            let Ok(path) = path.canonicalize() else {
+                continue;
+            };
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        Some(ProcessSandbox {
+            config: config.clone(),
+            writable: paths,
+            protected,
+        })
+    }
+
+    /// Arguments for `bwrap` to run `shell -c script` in `cwd`.
+    fn bwrap_args(&self, shell: &str, script: &str, cwd: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = [
+            "--die-with-parent",
+            "--unshare-pid",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+        ]
+        .iter()
+        .map(OsString::from)
+        .collect();
+        for path in &self.writable {
+            args.push("--bind".into());
+            args.push(path.into());
+            args.push(path.into());
+        }
+        for path in &self.protected {
+            args.push("--ro-bind".into());
+            args.push(path.into());
+            args.push(path.into());
+        }
+        if !self.config.network() {
+            args.push("--unshare-net".into());
+        }
+        args.push("--chdir".into());
+        args.push(cwd.into());
+        args.extend([shell, "-c", script].map(OsString::from));
+        args
+    }
+
+    /// A command that runs `shell -c script` in `cwd` inside the sandbox.
+    pub fn command(&self, shell: &str, script: &str, cwd: &Path) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new("bwrap");
+        cmd.args(self.bwrap_args(shell, script, cwd))
+            .current_dir(cwd);
+        let limits = ResourceLimits::from(&self.config);
+        if !limits.is_empty() {
+            // SAFETY: the closure only calls setrlimit, which is
+            // async-signal-safe, between fork and exec.
+            unsafe {
+                cmd.pre_exec(move || limits.apply().map_err(std::io::Error::from));
+            }
+        }
+        cmd
+    }
+}
 
-    let lower = command.to_lowercase();
-    for pattern in &dangerous_patterns {
-        if lower.contains(pattern) {
-            bail!("Blocked dangerous command pattern: {pattern}");
+/// `shell -c script` in `cwd`, inside `sandbox` when one is given.
+pub fn shell_command(
+    sandbox: Option<&ProcessSandbox>,
+    shell: &str,
+    script: &str,
+    cwd: &Path,
+) -> tokio::process::Command {
+    match sandbox {
+        Some(sandbox) => sandbox.command(shell, script, cwd),
+        None => {
+            let mut cmd = tokio::process::Command::new(shell);
+            cmd.arg("-c").arg(script).current_dir(cwd);
+            cmd
         }
     }
+}
 
+/// Fail early if `config` asks for a sandbox this machine can't provide.
+pub fn check_available(config: &SandboxConfig) -> Result<()> {
+    if config.mode() == SandboxMode::Off {
+        return Ok(());
+    }
+    if !cfg!(target_os = "linux") {
+        bail!("The bwrap sandbox needs Linux; set `sandbox: off` to run without it");
+    }
+    let found = std::process::Command::new("bwrap")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !found {
+        bail!("The bwrap sandbox needs bubblewrap. Install it (e.g. apt install bubblewrap) or set `sandbox: off`");
+    }
     Ok(())
 }
+
+/// The git directory shared by all worktrees of the repository at `root`.
+/// Runs `git`, so call it once rather than per sandbox.
+pub fn git_common_dir(root: &Path) -> Option<PathBuf> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--git-common-dir"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Some(if dir.is_absolute() {
+        dir
+    } else {
+        root.join(dir)
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ResourceLimits {
+    cpu_secs: Option<u64>,
+    memory_bytes: Option<u64>,
+    max_processes: Option<u64>,
+}
+
+impl From<&SandboxConfig> for ResourceLimits {
+    fn from(config: &SandboxConfig) -> Self {
+        ResourceLimits {
+            cpu_secs: config.cpu_secs,
+            memory_bytes: config.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+            max_processes: config.max_processes,
+        }
+    }
+}
+
+impl ResourceLimits {
+    fn is_empty(&self) -> bool {
+        self.cpu_secs.is_none() && self.memory_bytes.is_none() && self.max_processes.is_none()
+    }
+
+    fn apply(&self) -> nix::Result<()> {
+        use nix::sys::resource::{setrlimit, Resource};
+        if let Some(secs) = self.cpu_secs {
+            setrlimit(Resource::RLIMIT_CPU, secs, secs)?;
+        }
+        if let Some(bytes) = self.memory_bytes {
+            setrlimit(Resource::RLIMIT_AS, bytes, bytes)?;
+        }
+        if let Some(n) = self.max_processes {
+            setrlimit(Resource::RLIMIT_NPROC, n, n)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(config: SandboxConfig, root: &Path) -> ProcessSandbox {
+        ProcessSandbox::new(
+            &SandboxConfig {
+                mode: Some(SandboxMode::Bwrap),
+                ..config
+            },
+            root,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn args(sandbox: &ProcessSandbox, cwd: &Path) -> Vec<String> {
+        sandbox
+            .bwrap_args("bash", "cargo test", cwd)
+            .iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_off_mode_has_no_sandbox() {
+        assert!(ProcessSandbox::new(&SandboxConfig::default(), Path::new("/tmp"), None).is_none());
+    }
+
+    #[test]
+    fn test_bwrap_binds_only_writable_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("cache")).unwrap();
+        let sandbox = sandbox(
+            SandboxConfig {
+                network: Some(false),
+                writable: vec!["cache".to_string(), "missing".to_string()],
+                ..Default::default()
+            },
+            &root,
+        );
+        let args = args(&sandbox, &root);
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("--die-with-parent --unshare-pid --ro-bind / /"));
+        let root = root.display().to_string();
+        assert!(joined.contains(&format!("--bind {root} {root}")));
+        assert!(joined.contains(&format!("--bind {root}/cache {root}/cache")));
+        assert!(!joined.contains("missing"));
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert!(joined.ends_with(&format!("--chdir {root} bash -c cargo test")));
+    }
+
+    #[test]
+    fn test_git_hooks_and_config_stay_read_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let git_dir = root.join("repo.git");
+        std::fs::create_dir(&git_dir).unwrap();
+        std::fs::write(git_dir.join("config"), "").unwrap();
+        let sandbox = ProcessSandbox::new(
+            &SandboxConfig {
+                mode: Some(SandboxMode::Bwrap),
+                ..Default::default()
+            },
+            &root,
+            Some(&git_dir),
+        )
+        .unwrap();
+        let joined = args(&sandbox, &root).join(" ");
+
+        let git = git_dir.display().to_string();
+        let rw = joined.find(&format!("--bind {git} {git}")).unwrap();
+        // Later binds win, so the read-only ones must come after
+        for protected in ["hooks", "config"] {
+            let ro = format!("--ro-bind {git}/{protected} {git}/{protected}");
+            assert!(joined.find(&ro).is_some_and(|i| i > rw), "{joined}");
+        }
+    }
+
+    #[test]
+    fn test_network_is_kept_by_default() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sandbox = sandbox(SandboxConfig::default(), tmp.path());
+        assert!(!args(&sandbox, tmp.path()).contains(&"--unshare-net".to_string()));
+    }
+}
@@ -0,0 +1,343 @@
+//! Persistent bash sessions for the Bash tool.
+//!
+//! With a persistent shell, every Bash call of a chat session or worker runs
+//! in the same long-lived `bash` process, so `cd`, exported variables,
+//! activated virtualenvs and shell functions carry over between calls. Each
+//! command is `eval`ed with stdin from `/dev/null`, then a sentinel line is
+//! printed on stdout (with the exit code) and on stderr; everything before
+//! the sentinels is the command's output. A command that runs past its
+//! timeout takes the shell down with it, and the next call starts a new one.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+
+use crate::webui::anthropic::types::ToolDefinition;
+
+use super::background::{kill_group, track_group};
+use super::sandbox::{self, ProcessSandbox};
+
+/// Tool that restarts the persistent shell.
+pub const RESET_TOOL: &str = "BashReset";
+
+/// What a command printed, and how it exited.
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the command ended the shell itself (e.g. `exit`)
+    pub exit_code: Option<i32>,
+}
+
+/// A bash process kept alive across Bash calls, started on first use.
+pub struct PersistentShell {
+    cwd: PathBuf,
+    sandbox: Option<Arc<ProcessSandbox>>,
+    process: Mutex<Option<ShellProcess>>,
+    /// Pid of the running shell (0 when there is none), so `shutdown`
+    /// doesn't have to wait for a command to finish. Cleared by the
+    /// `ShellProcess` when it is dropped.
+    pid: Arc<AtomicU32>,
+}
+
+impl PersistentShell {
+    /// A shell that starts in `cwd`, inside `sandbox` when one is given.
+    pub fn new(cwd: &Path, sandbox: Option<Arc<ProcessSandbox>>) -> Self {
+        Self {
+            cwd: cwd.to_path_buf(),
+            sandbox,
+            process: Mutex::new(None),
+            pid: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Run `command` in the shell. If it runs past `timeout`, the shell is
+    /// killed and an error returned.
+    pub async fn run(&self, command: &str, timeout: Duration) -> Result<CommandOutput> {
+        let mut guard = self.process.lock().await;
+        let process = match guard.as_mut() {
+            Some(process) => process,
+            None => {
+                let process =
+                    ShellProcess::spawn(&self.cwd, self.sandbox.as_deref(), self.pid.clone())?;
+                guard.insert(process)
+            }
+        };
+
+        let marker = format!("__HIVE_SHELL_DONE_{}__", uuid::Uuid::new_v4().simple());
+        let script = format!(
+            "eval {} < /dev/null\nprintf '\\n{marker} %s\\n' \"$?\"\nprintf '\\n{marker}\\n' >&2\n",
+            shell_quote(command)
+        );
+        if let Err(e) = process.write(&script).await {
+            *guard = None;
+            return Err(e).context("The shell stopped accepting commands; it will be restarted");
+        }
+
+        match tokio::time::timeout(timeout, process.read_output(&marker)).await {
+            Ok(output) => {
+                if output.exit_code.is_none() {
+                    // The command ended the shell; start afresh next time
+                    *guard = None;
+                }
+                Ok(output)
+            }
+            Err(_) => {
+                *guard = None;
+                bail!(
+                    "Command timed out after {}ms. The shell was restarted, so its working \
+                     directory, variables and functions were reset",
+                    timeout.as_millis()
+                );
+            }
+        }
+    }
+
+    /// Kill the shell. The next command starts a new one in the original
+    /// working directory.
+    pub async fn reset(&self) {
+        self.process.lock().await.take();
+    }
+
+    /// Kill the shell without waiting for a running command, which returns
+    /// with whatever it printed so far. Used when the session or worker that
+    /// owns the shell goes away.
+    pub fn shutdown(&self) {
+        let pid = self.pid.swap(0, Ordering::Relaxed);
+        if pid != 0 {
+            kill_group(pid);
+        }
+    }
+}
+
+struct ShellProcess {
+    child: Child,
+    pid: u32,
+    /// The owning `PersistentShell`'s pid slot
+    shell_pid: Arc<AtomicU32>,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    stderr: BufReader<ChildStderr>,
+}
+
+impl ShellProcess {
+    fn spawn(
+        cwd: &Path,
+        sandbox: Option<&ProcessSandbox>,
+        shell_pid: Arc<AtomicU32>,
+    ) -> Result<ShellProcess> {
+        let mut child =
+            sandbox::shell_command(sandbox, "bash", "exec bash --noprofile --norc", cwd)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                // Its own process group, so commands it started die with it
+                .process_group(0)
+                .kill_on_drop(true)
+                .spawn()
+                .context("Failed to start a persistent bash shell")?;
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            bail!("Persistent bash shell has no pipes");
+        };
+        let pid = child.id().unwrap_or(0);
+        track_group(pid);
+        shell_pid.store(pid, Ordering::Relaxed);
+        Ok(ShellProcess {
+            child,
+            pid,
+            shell_pid,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr: BufReader::new(stderr),
+        })
+    }
+
+    async fn write(&mut self, script: &str) -> std::io::Result<()> {
+        self.stdin.write_all(script.as_bytes()).await?;
+        self.stdin.flush().await
+    }
+
+    /// Read both streams up to their sentinel lines. Both are read at once
+    /// so a command filling one pipe can't stall the other.
+    async fn read_output(&mut self, marker: &str) -> CommandOutput {
+        let (stdout, stderr) = tokio::join!(
+            read_until_marker(&mut self.stdout, marker),
+            read_until_marker(&mut self.stderr, marker)
+        );
+        let (stdout, exit_line) = stdout;
+        let exit_code = exit_line.map(|line| line.trim().parse().unwrap_or(-1));
+        CommandOutput {
+            stdout,
+            stderr: stderr.0,
+            exit_code,
+        }
+    }
+}
+
+impl Drop for ShellProcess {
+    fn drop(&mut self) {
+        // Only clear the slot if a newer shell hasn't taken it, so a later
+        // `shutdown` never signals a pid the system has since reused
+        let _ = self
+            .shell_pid
+            .compare_exchange(self.pid, 0, Ordering::Relaxed, Ordering::Relaxed);
+        if let Some(pid) = self.child.id() {
+            kill_group(pid);
+        }
+    }
+}
+
+/// Collect lines until one starting with `marker`. Returns the text before
+/// it and the rest of the marker line, or `None` if the stream ended first.
+async fn read_until_marker(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    marker: &str,
+) -> (String, Option<String>) {
+    let mut text = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => return (String::from_utf8_lossy(&text).into_owned(), None),
+            Ok(_) => {}
+        }
+        if let Some(rest) = line.strip_prefix(marker.as_bytes()) {
+            // The sentinel starts with a newline of its own
+            if text.last() == Some(&b'\n') {
+                text.pop();
+            }
+            let rest = String::from_utf8_lossy(rest).into_owned();
+            return (String::from_utf8_lossy(&text).into_owned(), Some(rest));
+        }
+        text.extend_from_slice(&line);
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Adjust a tool list for a persistent shell: say so in the Bash
+/// description and add the reset tool.
+pub fn add_persistent_shell_tools(tools: &mut Vec<ToolDefinition>) {
+    let Some(bash) = tools.iter_mut().find(|t| t.name == "Bash") else {
+        return;
+    };
+    bash.description = "Execute a bash command and return stdout and stderr. Commands run one after another in the same shell, so the working directory, exported variables and shell functions carry over between calls. Call BashReset to start over in a fresh shell.".to_string();
+    tools.push(ToolDefinition {
+        name: RESET_TOOL.to_string(),
+        description: "Restart the persistent bash shell, discarding its working directory, variables and functions. Use it when the shell is in a bad state.".to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+        cache_control: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[tokio::test]
+    async fn test_state_carries_over_between_commands() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("sub")).unwrap();
+        let shell = PersistentShell::new(tmp.path(), None);
+
+        shell
+            .run(
+                "cd sub && export GREETING=hi && greet() { echo \"$GREETING $1\"; }",
+                TIMEOUT,
+            )
+            .await
+            .unwrap();
+        let out = shell.run("greet there; pwd", TIMEOUT).await.unwrap();
+        let sub = tmp.path().join("sub").canonicalize().unwrap();
+        assert_eq!(out.stdout, format!("hi there\n{}\n", sub.display()));
+        assert_eq!(out.exit_code, Some(0));
+
+        // Syntax errors and failures don't desync the shell
+        let out = shell.run("echo 'unclosed", TIMEOUT).await.unwrap();
+        assert_ne!(out.exit_code, Some(0));
+        let out = shell
+            .run("echo -n no newline >&2; false", TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(
+            (out.stderr.as_str(), out.exit_code),
+            ("no newline", Some(1))
+        );
+        assert_eq!(
+            shell.run("echo $GREETING", TIMEOUT).await.unwrap().stdout,
+            "hi\n"
+        );
+
+        shell.reset().await;
+        let out = shell
+            .run("pwd; echo \"[$GREETING]\"", TIMEOUT)
+            .await
+            .unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        assert_eq!(out.stdout, format!("{}\n[]\n", root.display()));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_exit_restart_the_shell() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shell = PersistentShell::new(tmp.path(), None);
+        shell.run("export KEEP=1", TIMEOUT).await.unwrap();
+
+        let err = shell
+            .run("sleep 30", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        // A dropped shell's pid is never signalled again
+        assert_eq!(shell.pid.load(Ordering::Relaxed), 0);
+        let out = shell.run("echo \"[$KEEP]\"", TIMEOUT).await.unwrap();
+        assert_eq!(out.stdout, "[]\n");
+
+        let out = shell.run("echo bye; exit 3", TIMEOUT).await.unwrap();
+        assert_eq!((out.stdout.as_str(), out.exit_code), ("bye\n", None));
+        assert_eq!(shell.pid.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            shell.run("echo back", TIMEOUT).await.unwrap().stdout,
+            "back\n"
+        );
+        assert_ne!(shell.pid.load(Ordering::Relaxed), 0);
+        shell.reset().await;
+        assert_eq!(shell.pid.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_ends_a_running_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shell = Arc::new(PersistentShell::new(tmp.path(), None));
+        shell.run("true", TIMEOUT).await.unwrap();
+
+        let running = tokio::spawn({
+            let shell = shell.clone();
+            async move { shell.run("echo started; sleep 30", TIMEOUT).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        shell.shutdown();
+        let out = tokio::time::timeout(Duration::from_secs(5), running)
+            .await
+            .expect("command outlived the shell")
+            .unwrap()
+            .unwrap();
+        assert_eq!((out.stdout.as_str(), out.exit_code), ("started\n", None));
+    }
+}
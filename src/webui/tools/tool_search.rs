@@ -78,5 +78,6 @@ pub fn tool_search_definition() -> ToolDefinition {
             },
             "required": []
         }),
+        cache_control: None,
     }
 }
@@ -0,0 +1,130 @@
+//! Optional write restrictions for the mutating built-in tools.
+//!
+//! Callers (e.g. native-team workers) pass a `WritePolicy` to
+//! `execute_tool`; `Write`/`Edit` targets and shell redirection targets in
+//! `Bash` commands are checked against it before anything touches disk.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use super::sandbox;
+
+/// Decides whether a tool may write to a given path.
+pub trait WritePolicy: Send + Sync {
+    /// Return `Err(reason)` if `tool` must not write to `path`.
+    /// `path` is resolved (absolute) whenever possible.
+    fn check_write(&self, tool: &str, path: &Path) -> Result<(), String>;
+}
+
+/// Check the write targets of a tool call against the policy.
+pub fn enforce(
+    policy: &dyn WritePolicy,
+    name: &str,
+    input: &serde_json::Value,
+    cwd: &Path,
+) -> Result<()> {
+    let targets: Vec<String> = match name {
+        "Write" | "Edit" => input
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .map(|p| vec![p.to_string()])
+            .unwrap_or_default(),
+        "Bash" | "BashBackground" => input
+            .get("command")
+            .and_then(|v| v.as_str())
+            .map(bash_write_targets)
+            .unwrap_or_default(),
+        _ => return Ok(()),
+    };
+
+    for target in targets {
+        let resolved = sandbox::validate_path(&target, cwd)?;
+        if let Err(reason) = policy.check_write(name, &resolved) {
+            bail!("{reason}");
+        }
+    }
+    Ok(())
+}
+
+/// Best-effort extraction of the files a shell command writes to.
+///
+/// Only catches output redirections (`>`, `>>`, `2>`, `&>`) and `tee`
+/// arguments — enough to stop the common `cat > file <<EOF` pattern without
+/// trying to parse arbitrary shell.
+pub fn bash_write_targets(command: &str) -> Vec<String> {
+    let tokens: Vec<&str> = command.split_whitespace().collect();
+    let mut targets = Vec::new();
+    let mut in_tee = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if matches!(*token, "|" | "&&" | "||" | ";") {
+            in_tee = false;
+            continue;
+        }
+        if *token == "tee" {
+            in_tee = true;
+            continue;
+        }
+        if in_tee {
+            if !token.starts_with('-') {
+                targets.push(token.to_string());
+            }
+            continue;
+        }
+
+        let Some(rest) = strip_redirect(token) else {
+            continue;
+        };
+        let target = if rest.is_empty() {
+            tokens.get(i + 1).copied()
+        } else {
+            Some(rest)
+        };
+        if let Some(t) = target {
+            let t = t.trim_matches(|c| c == '"' || c == '\'');
+            // `>&2` style fd duplication is not a file write
+            if !t.starts_with('&') && !t.starts_with("/dev/") && !t.is_empty() {
+                targets.push(t.to_string());
+            }
+        }
+    }
+
+    targets
+}
+
+/// If `token` starts with an output redirection operator, return what follows it.
+fn strip_redirect(token: &str) -> Option<&str> {
+    let t = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '&');
+    t.strip_prefix(">>").or_else(|| t.strip_prefix('>'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_redirect_targets() {
+        assert_eq!(bash_write_targets("echo hi > out.txt"), vec!["out.txt"]);
+        assert_eq!(bash_write_targets("echo hi >>log.txt"), vec!["log.txt"]);
+        assert_eq!(
+            bash_write_targets("cat > src/a.rs <<'EOF'"),
+            vec!["src/a.rs"]
+        );
+    }
+
+    #[test]
+    fn test_bash_tee_targets() {
+        assert_eq!(
+            bash_write_targets("echo x | tee -a a.txt b.txt && ls"),
+            vec!["a.txt", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn test_bash_ignores_fd_and_dev_null() {
+        assert!(bash_write_targets("cargo build 2>&1 | head").is_empty());
+        assert!(bash_write_targets("ls > /dev/null 2>&1").is_empty());
+        assert!(bash_write_targets("ls -la src").is_empty());
+    }
+}
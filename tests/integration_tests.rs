@@ -49,6 +49,8 @@ fn create_test_drone(drones_dir: &Path, drone_name: &str, prd_name: &str) {
         error_count: 0,
         last_error: None,
         lead_model: None,
+        model: None,
+        max_agents: None,
         title: None,
         description: None,
         active_agents: HashMap::new(),
@@ -276,6 +278,8 @@ fn test_drone_state_transitions() {
             error_count: 0,
             last_error: None,
             lead_model: None,
+            model: None,
+            max_agents: None,
             title: None,
             description: None,
             active_agents: HashMap::new(),
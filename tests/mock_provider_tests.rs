@@ -0,0 +1,1076 @@
+//! End-to-end runs of the agentic loop, a native-team worker and the team
+//! coordinator against `Credentials::Mock` scripted transcripts.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use hive_lib::backend::native_team::approvals::{self, PendingApproval};
+use hive_lib::backend::native_team::control::{self, ControlRequest};
+use hive_lib::backend::native_team::coordinator::TeamCoordinator;
+use hive_lib::backend::native_team::events::EventEmitter;
+use hive_lib::backend::native_team::paused_workers::{self, PausedWorker};
+use hive_lib::backend::native_team::worker::{spawn_worker, WorkerConfig, WorkerError};
+use hive_lib::backend::SpawnConfig;
+use hive_lib::chat_engine::agentic::{run_agentic_loop, AgenticLoopParams};
+use hive_lib::chat_engine::session::{Effort, SessionStore};
+use hive_lib::types::{
+    BudgetLimits, DroneState, DroneStatus, RetryPolicy, StructuredTask, TaskLimits, TaskType,
+};
+use hive_lib::webui::anthropic::types::{ContentBlock, Message, MessageContent};
+use hive_lib::webui::auth::credentials::Credentials;
+use hive_lib::webui::mock;
+use hive_lib::webui::mock::script::{write_turns, Turn};
+use hive_lib::webui::tools::definitions::builtin_tool_definitions;
+
+/// Worker and coordinator runs use paths relative to the process cwd
+/// (`.hive/drones/...`) and `$HOME` (team task files), so those tests take
+/// this lock and point both at a temp dir.
+static PROCESS_ENV: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn session_store() -> SessionStore {
+    Arc::new(tokio::sync::Mutex::new(HashMap::new()))
+}
+
+fn git(cwd: &Path, args: &[&str]) -> String {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&out.stdout).to_string()
+}
+
+fn init_repo(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.email", "t@t"]);
+    git(dir, &["config", "user.name", "t"]);
+    std::fs::write(dir.join("README.md"), "repo\n").unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-qm", "init"]);
+}
+
+fn task() -> StructuredTask {
+    StructuredTask {
+        number: 1,
+        title: "Write greeting file".to_string(),
+        body: "Create hello.txt containing a greeting.".to_string(),
+        ..Default::default()
+    }
+}
+
+fn write_greeting_turns(dir: &Path) {
+    write_turns(
+        dir,
+        &[
+            Turn::tool_use(
+                "tu_1",
+                "Write",
+                serde_json::json!({"file_path": "hello.txt", "content": "hello\n"}),
+            ),
+            Turn::text("Wrote the file. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+}
+
+fn coordinator(
+    repo: &Path,
+    drone_dir: &Path,
+    transcripts: &Path,
+    tasks: Vec<StructuredTask>,
+    budget: BudgetLimits,
+) -> TeamCoordinator {
+    coordinator_with(
+        spawn_config(repo, drone_dir, tasks, budget),
+        drone_dir,
+        transcripts,
+    )
+}
+
+fn spawn_config(
+    repo: &Path,
+    drone_dir: &Path,
+    tasks: Vec<StructuredTask>,
+    budget: BudgetLimits,
+) -> SpawnConfig {
+    SpawnConfig {
+        drone_name: "mock".to_string(),
+        prd_path: repo.join("plan.md"),
+        model: "sonnet".to_string(),
+        worktree_path: repo.to_path_buf(),
+        status_file: drone_dir.join("status.json"),
+        working_dir: repo.to_path_buf(),
+        wait: true,
+        team_name: "mock-team".to_string(),
+        max_agents: 1,
+        claude_binary: "claude".to_string(),
+        environment: None,
+        structured_tasks: tasks,
+        remote_url: String::new(),
+        mode: "agent_team".to_string(),
+        project_languages: Vec::new(),
+        budget,
+        quality_gates: Vec::new(),
+        max_proposed_tasks: 5,
+        triage: false,
+        retry: Default::default(),
+        task_limits: Default::default(),
+        sandbox: Default::default(),
+        git_common_dir: None,
+        persistent_shell: false,
+    }
+}
+
+fn coordinator_with(config: SpawnConfig, drone_dir: &Path, transcripts: &Path) -> TeamCoordinator {
+    let emitter = Arc::new(EventEmitter::new(
+        drone_dir,
+        &config.status_file,
+        &config.team_name,
+    ));
+    let tasks = config.structured_tasks.clone();
+    TeamCoordinator::new(
+        config,
+        tasks,
+        Credentials::Mock {
+            transcripts: transcripts.to_path_buf(),
+        },
+        emitter,
+        Arc::new(AtomicBool::new(false)),
+        session_store(),
+    )
+}
+
+/// Minimal status.json so the emitter can record state and errors.
+fn write_status(drone_dir: &Path) {
+    std::fs::write(
+        drone_dir.join("status.json"),
+        serde_json::json!({
+            "drone": "mock", "prd": "plan.md", "branch": "hive/mock", "worktree": "",
+            "local_mode": true, "status": "in_progress", "total": 1,
+            "started": "", "updated": "", "error_count": 0
+        })
+        .to_string(),
+    )
+    .unwrap();
+}
+
+fn read_status(drone_dir: &Path) -> DroneStatus {
+    serde_json::from_str(&std::fs::read_to_string(drone_dir.join("status.json")).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_agentic_loop_executes_scripted_tool_call() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let transcripts = tmp.path().join("transcripts");
+    let work = tmp.path().join("work");
+    std::fs::create_dir_all(&work).unwrap();
+    write_greeting_turns(&transcripts);
+
+    let creds = Credentials::Mock {
+        transcripts: transcripts.clone(),
+    };
+    let (tx, _rx) = tokio::sync::broadcast::channel(256);
+    let abort = Arc::new(AtomicBool::new(false));
+    let messages = run_agentic_loop(AgenticLoopParams {
+        creds: &creds,
+        model: "mock-model",
+        messages: vec![Message {
+            role: "user".to_string(),
+            content: MessageContent::Text("Write hello.txt".to_string()),
+        }],
+        system_prompt: None,
+        tools: Some(builtin_tool_definitions()),
+        cwd: &work,
+        tx: &tx,
+        session_id: "loop",
+        abort_flag: &abort,
+        store: session_store(),
+        effort: Effort::Low,
+        max_turns: Some(5),
+        mcp_pool: None,
+        deferred_tools_active: false,
+        permissions: None,
+        write_policy: None,
+        sandbox: None,
+        shell: None,
+        processes: None,
+        prompt_caching: true,
+        usage_sink: None,
+        task_proposer: None,
+        inbox: None,
+        pause_flag: None,
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(work.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+    // user, assistant(tool_use), user(tool_result), assistant(text)
+    assert_eq!(messages.len(), 4);
+    let MessageContent::Blocks(results) = &messages[2].content else {
+        panic!("expected tool results");
+    };
+    assert!(matches!(
+        &results[0],
+        ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "tu_1"
+    ));
+
+    // The second request carries the tool result back to the model
+    let requests = mock::recorded_requests(&transcripts);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1]["messages"][2]["content"][0]["type"],
+        "tool_result"
+    );
+    // Prompt caching: last tool and the rolling conversation breakpoints
+    let tools = requests[1]["tools"].as_array().unwrap();
+    assert_eq!(tools.last().unwrap()["cache_control"]["type"], "ephemeral");
+    assert_eq!(
+        requests[1]["messages"][2]["content"][0]["cache_control"]["type"],
+        "ephemeral"
+    );
+    assert_eq!(
+        requests[1]["messages"][0]["content"][0]["cache_control"]["type"],
+        "ephemeral"
+    );
+}
+
+#[tokio::test]
+async fn test_worker_completes_scripted_task() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let task = task();
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task.worker_name()));
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+
+    let handle = spawn_worker(WorkerConfig {
+        task,
+        creds: Credentials::Mock { transcripts },
+        model: "sonnet".to_string(),
+        cwd: repo.clone(),
+        team_name: "mock-worker-team".to_string(),
+        drone_name: "mock".to_string(),
+        prd_path: repo.join("plan.md"),
+        project_languages: Vec::new(),
+        emitter: Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "mock-worker-team",
+        )),
+        session_store: session_store(),
+        global_abort: Arc::new(AtomicBool::new(false)),
+        dependency_notes: Vec::new(),
+        quality_gates: Vec::new(),
+        task_proposals: None,
+        pause_flag: Arc::new(AtomicBool::new(false)),
+        resume: None,
+        limits: Default::default(),
+        sandbox: Default::default(),
+        git_common_dir: None,
+        persistent_shell: false,
+    });
+    let result = handle.join_handle.await.unwrap().unwrap();
+
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+}
+
+#[tokio::test]
+async fn test_worker_times_out_after_turn_budget() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let task = task();
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task.worker_name()));
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+
+    // One turn: the Write runs, but the worker never gets to report completion
+    let handle = spawn_worker(WorkerConfig {
+        task,
+        creds: Credentials::Mock { transcripts },
+        model: "sonnet".to_string(),
+        cwd: repo.clone(),
+        team_name: "mock-worker-team".to_string(),
+        drone_name: "mock".to_string(),
+        prd_path: repo.join("plan.md"),
+        project_languages: Vec::new(),
+        emitter: Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "mock-worker-team",
+        )),
+        session_store: session_store(),
+        global_abort: Arc::new(AtomicBool::new(false)),
+        dependency_notes: Vec::new(),
+        quality_gates: Vec::new(),
+        task_proposals: None,
+        pause_flag: Arc::new(AtomicBool::new(false)),
+        resume: None,
+        limits: TaskLimits {
+            max_turns: Some(1),
+            ..Default::default()
+        },
+        sandbox: Default::default(),
+        git_common_dir: None,
+        persistent_shell: false,
+    });
+    let result = handle.join_handle.await.unwrap().unwrap();
+
+    assert!(!result.success);
+    assert!(
+        matches!(&result.error, Some(WorkerError::TimedOut(msg)) if msg.contains("1 agentic turns")),
+        "{:?}",
+        result.error
+    );
+    assert!(repo.join("hello.txt").exists());
+}
+
+#[tokio::test]
+async fn test_worker_retries_until_acceptance_check_passes() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let task = StructuredTask {
+        verify: vec!["grep -q hello hello.txt || (echo 'missing greeting' && false)".to_string()],
+        approval: false,
+        ..task()
+    };
+    let transcripts = tmp.path().join("transcripts");
+    let write = |id: &str, content: &str| {
+        Turn::tool_use(
+            id,
+            "Write",
+            serde_json::json!({"file_path": "hello.txt", "content": content}),
+        )
+    };
+    write_turns(
+        &transcripts.join(task.worker_name()),
+        &[
+            write("tu_1", "hi\n"),
+            Turn::text("Done. TASK_COMPLETE"),
+            write("tu_2", "hello\n"),
+            Turn::text("Fixed the greeting. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+
+    let handle = spawn_worker(WorkerConfig {
+        task,
+        creds: Credentials::Mock {
+            transcripts: transcripts.clone(),
+        },
+        model: "sonnet".to_string(),
+        cwd: repo.clone(),
+        team_name: "mock-worker-team".to_string(),
+        drone_name: "mock".to_string(),
+        prd_path: repo.join("plan.md"),
+        project_languages: Vec::new(),
+        emitter: Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "mock-worker-team",
+        )),
+        session_store: session_store(),
+        global_abort: Arc::new(AtomicBool::new(false)),
+        dependency_notes: Vec::new(),
+        quality_gates: Vec::new(),
+        task_proposals: None,
+        pause_flag: Arc::new(AtomicBool::new(false)),
+        resume: None,
+        limits: Default::default(),
+        sandbox: Default::default(),
+        git_common_dir: None,
+        persistent_shell: false,
+    });
+    let result = handle.join_handle.await.unwrap().unwrap();
+
+    assert!(result.success, "{:?}", result.error);
+    let requests = mock::recorded_requests(&transcripts);
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0]["system"]
+        .to_string()
+        .contains("## Acceptance Checks"));
+    // The failed check's output is fed into the next attempt
+    let retry = requests[2]["messages"].to_string();
+    assert!(retry.contains("missing greeting"), "{retry}");
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert_eq!(events.matches(r#""gate":"acceptance""#).count(), 2);
+}
+
+#[tokio::test]
+async fn test_paused_worker_resumes_saved_conversation() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task().worker_name()));
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    let config = |pause: bool, resume: Option<PausedWorker>| WorkerConfig {
+        task: task(),
+        creds: Credentials::Mock {
+            transcripts: transcripts.clone(),
+        },
+        model: "sonnet".to_string(),
+        cwd: repo.clone(),
+        team_name: "mock-worker-team".to_string(),
+        drone_name: "mock".to_string(),
+        prd_path: repo.join("plan.md"),
+        project_languages: Vec::new(),
+        emitter: Arc::new(EventEmitter::new(
+            &drone_dir,
+            &drone_dir.join("status.json"),
+            "mock-worker-team",
+        )),
+        session_store: session_store(),
+        global_abort: Arc::new(AtomicBool::new(false)),
+        dependency_notes: Vec::new(),
+        quality_gates: Vec::new(),
+        task_proposals: None,
+        pause_flag: Arc::new(AtomicBool::new(pause)),
+        resume,
+        limits: Default::default(),
+        sandbox: Default::default(),
+        git_common_dir: None,
+        persistent_shell: false,
+    };
+
+    // Paused before its first API call: nothing is sent, the state is saved
+    let result = spawn_worker(config(true, None))
+        .join_handle
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.paused);
+    assert!(mock::recorded_requests(&transcripts).is_empty());
+    let saved = paused_workers::take(&drone_dir, 1).unwrap();
+    assert_eq!(saved.iteration, 0);
+
+    // Resuming sends the saved history instead of a fresh task prompt
+    let text = |role: &str, text: &str| Message {
+        role: role.to_string(),
+        content: MessageContent::Text(text.to_string()),
+    };
+    let resume = PausedWorker {
+        messages: vec![
+            text("user", "Create hello.txt containing a greeting."),
+            text("assistant", "Looking at the repo first."),
+            text("user", "README.md only contains 'repo'."),
+        ],
+        ..saved
+    };
+    let result = spawn_worker(config(false, Some(resume)))
+        .join_handle
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert!(!result.paused);
+    let requests = mock::recorded_requests(&transcripts);
+    assert_eq!(requests[0]["messages"].as_array().unwrap().len(), 3);
+    assert!(requests[0]["messages"]
+        .to_string()
+        .contains("README.md only contains"));
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+}
+
+#[tokio::test]
+async fn test_coordinator_runs_plan_to_pr_phase() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let task = task();
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task.worker_name()));
+    write_turns(
+        &transcripts.join("verifier"),
+        &[
+            Turn::text("VERIFY_FAIL: hello.txt lacks a trailing exclamation mark"),
+            Turn::text("Everything checks out. VERIFY_PASS"),
+        ],
+    )
+    .unwrap();
+    write_turns(
+        &transcripts.join("pr-agent"),
+        &[Turn::text("No remote configured, skipping PR.")],
+    )
+    .unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    let coordinator = coordinator(
+        &repo,
+        &drone_dir,
+        &transcripts,
+        vec![task.clone()],
+        BudgetLimits::default(),
+    );
+    coordinator.run().await.unwrap();
+
+    // The worker ran in its own worktree and was merged back
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+    assert!(git(&repo, &["log", "--format=%s"]).contains("hive: task 1"));
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""to_phase":"pr""#));
+    // worker (2 turns) + verifier + fix attempt + PR agent
+    let requests = mock::recorded_requests(&transcripts);
+    assert_eq!(requests.len(), 5);
+    // The fix attempt is told what the verifier found
+    let fix_system = requests[3]["system"].to_string();
+    assert!(fix_system.contains("fix agent"), "{fix_system}");
+    assert!(fix_system.contains("lacks a trailing exclamation mark"));
+
+    // One priced cost record per API call, attributed to worker and task
+    let costs: Vec<serde_json::Value> = std::fs::read_to_string(drone_dir.join("cost.ndjson"))
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(costs.len(), 5);
+    let worker_name = crate::task().worker_name();
+    let worker_costs: Vec<_> = costs
+        .iter()
+        .filter(|c| c["worker"] == worker_name)
+        .collect();
+    assert_eq!(worker_costs.len(), 2);
+    assert!(worker_costs.iter().all(|c| c["task"] == "1"));
+    assert_eq!(worker_costs[0]["model"], "claude-sonnet-4-5-20250929");
+    assert!(costs.iter().all(|c| c["cost_usd"].as_f64().unwrap() > 0.0));
+    assert!(costs
+        .iter()
+        .any(|c| c["worker"] == "verifier" && c["task"].is_null()));
+}
+
+/// Poll `approvals.json` until an approval other than `seen` is pending.
+async fn next_approval(drone_dir: &Path, seen: Option<&PendingApproval>) -> PendingApproval {
+    for _ in 0..200 {
+        if let Some(approval) = approvals::load(drone_dir)
+            .into_iter()
+            .find(|a| Some(a) != seen)
+        {
+            return approval;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("no approval was requested");
+}
+
+async fn send_control(drone_dir: &Path, request: ControlRequest) {
+    let drone_dir = drone_dir.to_path_buf();
+    let response = tokio::task::spawn_blocking(move || control::send(&drone_dir, &request))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(response.ok, "{:?}", response.error);
+}
+
+#[tokio::test]
+async fn test_coordinator_waits_for_task_approval() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let task = StructuredTask {
+        approval: true,
+        ..task()
+    };
+    let transcripts = tmp.path().join("transcripts");
+    let write = |id: &str, content: &str| {
+        Turn::tool_use(
+            id,
+            "Write",
+            serde_json::json!({"file_path": "hello.txt", "content": content}),
+        )
+    };
+    write_turns(
+        &transcripts.join(task.worker_name()),
+        &[
+            write("tu_1", "hi\n"),
+            Turn::text("Done. TASK_COMPLETE"),
+            write("tu_2", "hello\n"),
+            Turn::text("Used the full greeting. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    write_turns(&transcripts.join("verifier"), &[Turn::text("VERIFY_PASS")]).unwrap();
+    write_turns(&transcripts.join("pr-agent"), &[Turn::text("No remote.")]).unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+    let coordinator = coordinator(
+        &repo,
+        &drone_dir,
+        &transcripts,
+        vec![task],
+        BudgetLimits::default(),
+    );
+    let run = tokio::spawn(coordinator.run());
+
+    // Nothing is merged until a reviewer decides
+    let first = next_approval(&drone_dir, None).await;
+    assert_eq!(first.task, Some(1));
+    assert!(first.diff.contains("+hi"), "{}", first.diff);
+    assert!(!repo.join("hello.txt").exists());
+    send_control(
+        &drone_dir,
+        ControlRequest::Reject {
+            task: Some(1),
+            comment: Some("Write the full word hello".to_string()),
+        },
+    )
+    .await;
+
+    // The worker revises its change with the comment and asks again
+    let second = next_approval(&drone_dir, Some(&first)).await;
+    assert!(second.diff.contains("+hello"), "{}", second.diff);
+    send_control(
+        &drone_dir,
+        ControlRequest::Approve {
+            task: Some(1),
+            comment: None,
+        },
+    )
+    .await;
+    run.await.unwrap().unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+    let requests = mock::recorded_requests(&transcripts);
+    let rework = requests[2]["messages"].to_string();
+    assert!(rework.contains("Write the full word hello"), "{rework}");
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""to_phase":"awaiting_approval""#));
+    assert!(events.contains(r#""to_phase":"pr""#));
+    assert!(approvals::load(&drone_dir).is_empty());
+}
+
+#[tokio::test]
+async fn test_coordinator_stops_at_drone_budget() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task().worker_name()));
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+
+    let budget = BudgetLimits {
+        drone_usd: Some(0.000_001),
+        ..Default::default()
+    };
+    coordinator(&repo, &drone_dir, &transcripts, vec![task()], budget)
+        .run()
+        .await
+        .unwrap();
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""event":"BudgetExceeded""#), "{events}");
+    assert!(!events.contains(r#""to_phase":"verify""#));
+    assert_eq!(read_status(&drone_dir).status, DroneState::BudgetExceeded);
+}
+
+#[tokio::test]
+async fn test_coordinator_fails_fast_on_broken_setup() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    write_greeting_turns(&transcripts.join(task().worker_name()));
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+
+    let setup = StructuredTask {
+        number: 0,
+        title: "Environment setup".to_string(),
+        task_type: TaskType::Setup,
+        run: vec![
+            "echo ok > setup.txt".to_string(),
+            "echo 'error: baseline build broken' && false".to_string(),
+            "touch never.txt".to_string(),
+        ],
+        ..task()
+    };
+    coordinator(
+        &repo,
+        &drone_dir,
+        &transcripts,
+        vec![setup, task()],
+        BudgetLimits::default(),
+    )
+    .run()
+    .await
+    .unwrap();
+
+    // Commands ran in order in the worktree and stopped at the failure
+    assert!(repo.join("setup.txt").exists());
+    assert!(!repo.join("never.txt").exists());
+    // No worker was dispatched
+    assert!(mock::recorded_requests(&transcripts).is_empty());
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert_eq!(events.matches(r#""event":"SetupCommand""#).count(), 2);
+    assert!(events.contains(r#""to_phase":"setup""#));
+    assert!(!events.contains(r#""to_phase":"monitor""#));
+
+    let status = read_status(&drone_dir);
+    assert_eq!(status.status, DroneState::Error);
+    let error = status.last_error.unwrap();
+    assert!(error.contains("Setup task 0"), "{error}");
+    assert!(error.contains("baseline build broken"), "{error}");
+}
+
+#[tokio::test]
+async fn test_coordinator_runs_task_proposed_by_worker() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let proposed = StructuredTask {
+        number: 2,
+        title: "Write farewell file".to_string(),
+        ..task()
+    };
+    let transcripts = tmp.path().join("transcripts");
+    write_turns(
+        &transcripts.join(task().worker_name()),
+        &[
+            Turn::tool_use(
+                "tu_1",
+                "ProposeTask",
+                serde_json::json!({
+                    "title": proposed.title,
+                    "description": "Create bye.txt as well.",
+                    "depends_on": [1]
+                }),
+            ),
+            Turn::tool_use(
+                "tu_2",
+                "Write",
+                serde_json::json!({"file_path": "hello.txt", "content": "hello\n"}),
+            ),
+            Turn::text("Wrote the file. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    write_turns(
+        &transcripts.join(proposed.worker_name()),
+        &[
+            Turn::tool_use(
+                "tu_1",
+                "Write",
+                serde_json::json!({"file_path": "bye.txt", "content": "bye\n"}),
+            ),
+            Turn::text("Done. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    write_turns(&transcripts.join("verifier"), &[Turn::text("VERIFY_PASS")]).unwrap();
+    write_turns(
+        &transcripts.join("pr-agent"),
+        &[Turn::text("No remote configured, skipping PR.")],
+    )
+    .unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    coordinator(
+        &repo,
+        &drone_dir,
+        &transcripts,
+        vec![task()],
+        BudgetLimits::default(),
+    )
+    .run()
+    .await
+    .unwrap();
+
+    // The proposed task was dispatched after its dependency and merged back
+    assert_eq!(
+        std::fs::read_to_string(repo.join("bye.txt")).unwrap(),
+        "bye\n"
+    );
+    let log = git(&repo, &["log", "--format=%s"]);
+    assert!(log.contains("hive: task 2"), "{log}");
+
+    let requests = mock::recorded_requests(&transcripts);
+    assert!(requests[0]["tools"].to_string().contains("ProposeTask"));
+    let confirmation = requests[1]["messages"].to_string();
+    assert!(confirmation.contains("Added task 2"), "{confirmation}");
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(
+        events.contains(r#""subject":"Write farewell file""#),
+        "{events}"
+    );
+    let seeded =
+        std::fs::read_to_string(tmp.path().join(".claude/tasks/mock-team/2.json")).unwrap();
+    assert!(seeded.contains("US-002: Write farewell file"), "{seeded}");
+}
+
+#[tokio::test]
+async fn test_triage_splits_task_that_failed_every_retry() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    let blocked = || Turn::text("TASK_BLOCKED: the greeting and farewell need different owners");
+    write_turns(
+        &transcripts.join(task().worker_name()),
+        &[blocked(), blocked(), blocked()],
+    )
+    .unwrap();
+    write_turns(
+        &transcripts.join(format!("{}-triage", task().worker_name())),
+        &[Turn::text(
+            "The task mixes two files.\n\nTRIAGE_DECISION\n\
+             {\"action\": \"split\", \"reason\": \"Two independent files\", \"tasks\": [\
+             {\"title\": \"Write hello\", \"description\": \"Create hello.txt\"},\
+             {\"title\": \"Write bye\", \"description\": \"Create bye.txt\"}]}",
+        )],
+    )
+    .unwrap();
+    for (title, file) in [("Write hello", "hello.txt"), ("Write bye", "bye.txt")] {
+        let part = StructuredTask {
+            title: title.to_string(),
+            ..task()
+        };
+        write_turns(
+            &transcripts.join(part.worker_name()),
+            &[
+                Turn::tool_use(
+                    "tu_1",
+                    "Write",
+                    serde_json::json!({"file_path": file, "content": "hi\n"}),
+                ),
+                Turn::text("Done. TASK_COMPLETE"),
+            ],
+        )
+        .unwrap();
+    }
+    write_turns(&transcripts.join("verifier"), &[Turn::text("VERIFY_PASS")]).unwrap();
+    write_turns(
+        &transcripts.join("pr-agent"),
+        &[Turn::text("No remote configured, skipping PR.")],
+    )
+    .unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+    let mut config = spawn_config(&repo, &drone_dir, vec![task()], BudgetLimits::default());
+    config.triage = true;
+    coordinator_with(config, &drone_dir, &transcripts)
+        .run()
+        .await
+        .unwrap();
+
+    // The split tasks ran in order and the drone went on to the PR
+    assert!(repo.join("hello.txt").exists());
+    assert!(repo.join("bye.txt").exists());
+    let log = git(&repo, &["log", "--format=%s"]);
+    assert!(log.contains("hive: task 3 — Write bye"), "{log}");
+
+    let requests = mock::recorded_requests(&transcripts);
+    let triage = requests
+        .iter()
+        .find(|r| r["system"].to_string().contains("failure triage agent"))
+        .expect("triage request");
+    let prompt = triage["system"].to_string();
+    assert!(prompt.contains("need different owners"), "{prompt}");
+    assert!(prompt.contains("Last Worker Transcript"), "{prompt}");
+
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""event":"TriageDecision""#), "{events}");
+    assert!(events.contains(r#""action":"split""#), "{events}");
+    assert!(events.contains(r#""new_tasks":["2","3"]"#), "{events}");
+    assert!(events.contains(r#""to_phase":"pr""#));
+    assert_eq!(read_status(&drone_dir).status, DroneState::Completed);
+}
+
+#[tokio::test]
+async fn test_transient_error_does_not_use_an_attempt() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    write_turns(
+        &transcripts.join(task().worker_name()),
+        &[
+            Turn::api_error(529, r#"{"type":"overloaded_error"}"#),
+            Turn::tool_use(
+                "tu_1",
+                "Write",
+                serde_json::json!({"file_path": "hello.txt", "content": "hello\n"}),
+            ),
+            Turn::text("Wrote the file. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    write_turns(&transcripts.join("verifier"), &[Turn::text("VERIFY_PASS")]).unwrap();
+    write_turns(
+        &transcripts.join("pr-agent"),
+        &[Turn::text("No remote configured, skipping PR.")],
+    )
+    .unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+    // A single attempt: only a transient error may be retried
+    let task = StructuredTask {
+        retry: RetryPolicy {
+            max_attempts: Some(1),
+            backoff_secs: Some(0),
+            ..Default::default()
+        },
+        ..task()
+    };
+    coordinator(
+        &repo,
+        &drone_dir,
+        &transcripts,
+        vec![task],
+        BudgetLimits::default(),
+    )
+    .run()
+    .await
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hello.txt")).unwrap(),
+        "hello\n"
+    );
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""error_class":"transient""#), "{events}");
+    assert_eq!(read_status(&drone_dir).status, DroneState::Completed);
+}
+
+#[tokio::test]
+async fn test_retry_escalates_model() {
+    let _env = PROCESS_ENV.lock().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo);
+    std::env::set_current_dir(&repo).unwrap();
+    std::env::set_var("HOME", tmp.path());
+
+    let transcripts = tmp.path().join("transcripts");
+    write_turns(
+        &transcripts.join(task().worker_name()),
+        &[
+            Turn::text("TASK_BLOCKED: not sure where the greeting goes"),
+            Turn::tool_use(
+                "tu_1",
+                "Write",
+                serde_json::json!({"file_path": "hello.txt", "content": "hello\n"}),
+            ),
+            Turn::text("Wrote the file. TASK_COMPLETE"),
+        ],
+    )
+    .unwrap();
+    write_turns(&transcripts.join("verifier"), &[Turn::text("VERIFY_PASS")]).unwrap();
+    write_turns(
+        &transcripts.join("pr-agent"),
+        &[Turn::text("No remote configured, skipping PR.")],
+    )
+    .unwrap();
+
+    let drone_dir = repo.join(".hive/drones/mock");
+    std::fs::create_dir_all(&drone_dir).unwrap();
+    write_status(&drone_dir);
+    let mut config = spawn_config(&repo, &drone_dir, vec![task()], BudgetLimits::default());
+    config.retry = RetryPolicy {
+        escalate_model: Some("opus".to_string()),
+        ..Default::default()
+    };
+    coordinator_with(config, &drone_dir, &transcripts)
+        .run()
+        .await
+        .unwrap();
+
+    assert!(repo.join("hello.txt").exists());
+    let requests = mock::recorded_requests(&transcripts);
+    let models: Vec<String> = requests
+        .iter()
+        .filter(|r| r["system"].to_string().contains("Write greeting file"))
+        .map(|r| r["model"].as_str().unwrap_or_default().to_string())
+        .collect();
+    assert!(models.len() >= 2, "{models:?}");
+    assert!(models[0].contains("sonnet"), "{models:?}");
+    assert!(models[1].contains("opus"), "{models:?}");
+    let events = std::fs::read_to_string(drone_dir.join("events.ndjson")).unwrap();
+    assert!(events.contains(r#""error_class":"blocked""#), "{events}");
+}
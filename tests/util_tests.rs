@@ -52,6 +52,8 @@ fn create_test_drone(
         error_count: 0,
         last_error: None,
         lead_model: None,
+        model: None,
+        max_agents: None,
         title: None,
         description: None,
         active_agents: HashMap::new(),
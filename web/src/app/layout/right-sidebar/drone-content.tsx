@@ -1,6 +1,7 @@
 import * as Collapsible from "@radix-ui/react-collapsible";
 import { Trash2 } from "lucide-react";
 import { useCallback, useEffect, useState } from "react";
+import { ApprovalPanel } from "@/domains/monitor/components/approval-panel";
 import { TaskList } from "@/domains/monitor/components/task-list";
 import { TeamBar } from "@/domains/monitor/components/team-bar";
 import type { DroneInfo } from "@/domains/monitor/types";
@@ -177,6 +178,8 @@ function DroneListItem({
             </span>
           </div>
 
+          <ApprovalPanel droneName={drone.name} approvals={drone.approvals} />
+
           {drone.tasks.length > 0 && (
             <div className="mb-2">
               <div className="text-[10px] font-medium text-muted-foreground uppercase tracking-wide mb-1">
@@ -11,6 +11,7 @@ import type {
   QueuedMessage as QueuedMessageType,
 } from "../../types";
 import { DroneStatusCard } from "../drone-status-card";
+import { PermissionPrompt } from "../permission-prompt";
 import { PromptInput } from "../prompt-input";
 import { QueuedMessage } from "../queued-message";
 import { SessionTurn } from "../session-turn";
@@ -115,6 +116,8 @@ export function ChatLayout({
   const { focusedTurnIndex } = useMessageNavigation(visibleTurns);
 
   const isCreatingSession = useAppStore((s) => s.isCreatingSession);
+  const pendingPermissions = useAppStore((s) => s.pendingPermissions);
+  const sessionId = useAppStore((s) => s.session?.id ?? null);
 
   // Show loading splash while Claude CLI is starting
   if (isCreatingSession && turns.length === 0) {
@@ -227,6 +230,10 @@ export function ChatLayout({
               />
             ),
           )}
+          {sessionId &&
+            pendingPermissions.map((p) => (
+              <PermissionPrompt key={p.requestId} sessionId={sessionId} permission={p} />
+            ))}
           {messageQueue.map((msg) => (
             <QueuedMessage key={msg.id} message={msg} onCancel={onCancelQueued ?? (() => {})} />
           ))}
@@ -0,0 +1,69 @@
+import { Check, ShieldAlert, X } from "lucide-react";
+import { useAnswerPermission } from "../mutations";
+import type { PendingPermission } from "../types";
+import "./permission-prompt.css";
+
+interface PermissionPromptProps {
+  sessionId: string;
+  permission: PendingPermission;
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+const SUMMARY_KEYS = ["command", "file_path", "path", "pattern", "url"];
+
+/** The input field a user needs to judge the call, else the raw input. */
+function summarizeInput(input: Record<string, unknown>): string {
+  for (const key of SUMMARY_KEYS) {
+    const value = input[key];
+    if (typeof value === "string" && value) return value;
+  }
+  return JSON.stringify(input, null, 2);
+}
+
+// ── Component ────────────────────────────────────────────────────────────────
+
+export function PermissionPrompt({ sessionId, permission }: PermissionPromptProps) {
+  const answer = useAnswerPermission();
+
+  const respond = (allow: boolean) =>
+    answer.mutate({ sessionId, requestId: permission.requestId, allow });
+
+  return (
+    <div data-component="permission-prompt">
+      <div data-slot="permission-prompt-header">
+        <ShieldAlert className="h-3.5 w-3.5" />
+        <span data-slot="permission-prompt-tool">{permission.tool}</span>
+        {permission.reason && (
+          <span data-slot="permission-prompt-reason">{permission.reason}</span>
+        )}
+      </div>
+      <pre data-slot="permission-prompt-input">{summarizeInput(permission.input)}</pre>
+      {answer.isError && (
+        <p data-slot="permission-prompt-error">
+          {answer.error instanceof Error ? answer.error.message : "Could not send the answer"}
+        </p>
+      )}
+      <div data-slot="permission-prompt-actions">
+        <button
+          type="button"
+          data-slot="permission-prompt-deny"
+          onClick={() => respond(false)}
+          disabled={answer.isPending}
+        >
+          <X className="h-3 w-3" />
+          Deny
+        </button>
+        <button
+          type="button"
+          data-slot="permission-prompt-allow"
+          onClick={() => respond(true)}
+          disabled={answer.isPending}
+        >
+          <Check className="h-3 w-3" />
+          Allow
+        </button>
+      </div>
+    </div>
+  );
+}
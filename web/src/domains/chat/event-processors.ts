@@ -5,6 +5,8 @@ import type {
   ChatState,
   CompactEvent,
   FinishReason,
+  PermissionRequestEvent,
+  PermissionResolvedEvent,
   ResultEvent,
   StreamEvent,
   SystemEvent,
@@ -32,6 +34,10 @@ export function processStreamEvent(state: ChatState, event: StreamEvent): ChatSt
       return processUsageEvent(base, event);
     case "compact.completed":
       return processCompactEvent(base, event);
+    case "permission.request":
+      return processPermissionRequest(base, event);
+    case "permission.resolved":
+      return processPermissionResolved(base, event);
     default:
       return base;
   }
@@ -231,6 +237,8 @@ function processResultEvent(state: ChatState, event: ResultEvent): ChatState {
     currentTurnId: null,
     isStreaming: false,
     contextUsage,
+    // Requests can't outlive the turn that made them
+    pendingPermissions: [],
     session: state.session
       ? {
           ...state.session,
@@ -284,3 +292,26 @@ function processCompactEvent(state: ChatState, event: CompactEvent): ChatState {
     },
   };
 }
+
+function processPermissionRequest(state: ChatState, event: PermissionRequestEvent): ChatState {
+  if (state.pendingPermissions.some((p) => p.requestId === event.request_id)) return state;
+  return {
+    ...state,
+    pendingPermissions: [
+      ...state.pendingPermissions,
+      {
+        requestId: event.request_id,
+        tool: event.tool,
+        input: event.input,
+        reason: event.reason,
+      },
+    ],
+  };
+}
+
+function processPermissionResolved(state: ChatState, event: PermissionResolvedEvent): ChatState {
+  return {
+    ...state,
+    pendingPermissions: state.pendingPermissions.filter((p) => p.requestId !== event.request_id),
+  };
+}
@@ -19,6 +19,12 @@ interface CreateSessionResponse {
   createdAt: string;
 }
 
+interface AnswerPermissionParams {
+  sessionId: string;
+  requestId: string;
+  allow: boolean;
+}
+
 interface RenameSessionParams {
   id: string;
   title: string;
@@ -86,3 +92,15 @@ export function useDeleteSession() {
     },
   });
 }
+
+/** Allow or refuse a tool call the session is waiting on. The stream's
+ * `permission.resolved` event removes the prompt. */
+export function useAnswerPermission() {
+  return useMutation({
+    mutationFn: ({ sessionId, requestId, allow }: AnswerPermissionParams) =>
+      apiClient.postVoid(
+        `/api/chat/sessions/${encodeURIComponent(sessionId)}/permissions/${encodeURIComponent(requestId)}`,
+        { allow },
+      ),
+  });
+}
@@ -41,6 +41,7 @@ interface SnapshotSource {
   error: ChatState["error"];
   contextUsage: ChatState["contextUsage"];
   messageQueue: ChatState["messageQueue"];
+  pendingPermissions: ChatState["pendingPermissions"];
   activeSessionId: string | null;
   promptDraft: string;
   selectedModel: string | null;
@@ -59,6 +60,7 @@ export function buildSnapshot(state: SnapshotSource): ProjectChatSnapshot {
     error: state.error,
     contextUsage: state.contextUsage,
     messageQueue: state.messageQueue,
+    pendingPermissions: state.pendingPermissions,
   };
   return {
     chatState,
@@ -103,6 +105,7 @@ function stripStreamingState(snap: ProjectChatSnapshot): ProjectChatSnapshot {
       isStale: false,
       lastEventAt: null,
       messageQueue: [],
+      pendingPermissions: [],
     },
     wasStreaming: false,
     streamingSessionId: null,
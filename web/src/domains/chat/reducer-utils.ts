@@ -12,6 +12,7 @@ export const initialChatState: ChatState = {
   error: null,
   contextUsage: null,
   messageQueue: [],
+  pendingPermissions: [],
 };
 
 // ── Part ID counter ─────────────────────────────────────────────────────────
@@ -19,6 +19,7 @@ export function chatReducer(state: ChatState, action: ChatAction): ChatState {
         isStreaming: false,
         error: null,
         messageQueue: [],
+        pendingPermissions: [],
       };
 
     case "SESSION_RESET":
@@ -19,6 +19,7 @@ function extractChatState(slice: ChatSlice): ChatState {
     error: slice.error,
     contextUsage: slice.contextUsage,
     messageQueue: slice.messageQueue,
+    pendingPermissions: slice.pendingPermissions,
   };
 }
 
@@ -101,6 +101,21 @@ export interface SessionCompletedEvent {
   type: "session.completed";
 }
 
+/** A tool call waiting for approval; answer via POST /permissions/{request_id}. */
+export interface PermissionRequestEvent {
+  type: "permission.request";
+  request_id: string;
+  tool: string;
+  input: Record<string, unknown>;
+  reason: string;
+}
+
+export interface PermissionResolvedEvent {
+  type: "permission.resolved";
+  request_id: string;
+  allowed: boolean;
+}
+
 export type StreamEvent =
   | SystemEvent
   | AssistantEvent
@@ -108,7 +123,9 @@ export type StreamEvent =
   | ResultEvent
   | UsageEvent
   | CompactEvent
-  | SessionCompletedEvent;
+  | SessionCompletedEvent
+  | PermissionRequestEvent
+  | PermissionResolvedEvent;
 
 // ── Assistant part types (rendered in UI) ───────────────────────────────────
 
@@ -207,6 +224,15 @@ export interface SlashCommand {
   source?: "project" | "user" | "tools";
 }
 
+// ── Permission prompt (tool call waiting for the user) ──────────────────────
+
+export interface PendingPermission {
+  requestId: string;
+  tool: string;
+  input: Record<string, unknown>;
+  reason: string;
+}
+
 // ── Reducer state & actions ─────────────────────────────────────────────────
 
 export interface ContextUsage {
@@ -227,6 +253,7 @@ export interface ChatState {
   error: string | null;
   contextUsage: ContextUsage | null;
   messageQueue: QueuedMessage[];
+  pendingPermissions: PendingPermission[];
 }
 
 export type ChatAction =
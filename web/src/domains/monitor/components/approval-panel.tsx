@@ -0,0 +1,151 @@
+import { Check, X } from "lucide-react";
+import { useEffect, useState } from "react";
+import type { ApprovalInfo, PendingApproval } from "@/domains/monitor/types";
+import { apiClient } from "@/shared/api/client";
+import { cn } from "@/shared/lib/utils";
+
+interface ApprovalPanelProps {
+  droneName: string;
+  approvals: ApprovalInfo[];
+}
+
+function droneUrl(droneName: string, action: string) {
+  return `/api/drones/${encodeURIComponent(droneName)}/${action}`;
+}
+
+function diffLineClass(line: string) {
+  if (line.startsWith("+++") || line.startsWith("---")) return "font-semibold";
+  if (line.startsWith("+")) return "text-success";
+  if (line.startsWith("-")) return "text-destructive";
+  if (line.startsWith("@@")) return "text-accent";
+  return "text-muted-foreground";
+}
+
+/** Work waiting on a reviewer, with its diff, notes and approve/reject actions. */
+export function ApprovalPanel({ droneName, approvals }: ApprovalPanelProps) {
+  const [details, setDetails] = useState<PendingApproval[]>([]);
+  const requestedKey = approvals.map((a) => a.requested).join(",");
+
+  // Diffs are only fetched when the set of pending approvals changes
+  useEffect(() => {
+    if (!requestedKey) return;
+    const ctrl = new AbortController();
+    apiClient
+      .get<PendingApproval[]>(droneUrl(droneName, "approvals"), ctrl.signal)
+      .then(setDetails)
+      .catch(() => {
+        /* retried when the approvals change */
+      });
+    return () => ctrl.abort();
+  }, [droneName, requestedKey]);
+
+  if (approvals.length === 0) return null;
+
+  return (
+    <div className="mb-2">
+      <div className="text-[10px] font-medium text-muted-foreground uppercase tracking-wide mb-1">
+        Awaiting approval
+      </div>
+      {approvals.map((approval) => (
+        <ApprovalItem
+          key={approval.requested}
+          droneName={droneName}
+          approval={approval}
+          diff={details.find((d) => d.requested === approval.requested)?.diff}
+        />
+      ))}
+    </div>
+  );
+}
+
+function ApprovalItem({
+  droneName,
+  approval,
+  diff,
+}: {
+  droneName: string;
+  approval: ApprovalInfo;
+  diff: string | undefined;
+}) {
+  const [comment, setComment] = useState("");
+  const [sending, setSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const label = approval.task != null ? `Task ${approval.task}: ${approval.title}` : approval.title;
+
+  const decide = async (action: "approve" | "reject") => {
+    setSending(true);
+    setError(null);
+    try {
+      await apiClient.postVoid(droneUrl(droneName, action), {
+        task: approval.task ?? undefined,
+        comment: comment.trim() || undefined,
+      });
+    } catch (e) {
+      setError(e instanceof Error ? e.message : "Request failed");
+      setSending(false);
+    }
+    // On success the approval disappears on the next SSE update
+  };
+
+  return (
+    <div className="rounded-md border border-sidebar-border p-2 mb-2">
+      <div className="text-xs font-semibold mb-1">{label}</div>
+      {approval.notes && (
+        <p className="text-[11px] text-muted-foreground whitespace-pre-wrap mb-1.5">
+          {approval.notes}
+        </p>
+      )}
+      <pre className="text-[10px] leading-snug max-h-64 overflow-auto bg-muted/40 rounded p-1.5 mb-1.5">
+        {diff === undefined
+          ? "Loading diff…"
+          : diff.split("\n").map((line, i) => (
+              // biome-ignore lint/suspicious/noArrayIndexKey: diff lines have no identity
+              <div key={i} className={diffLineClass(line)}>
+                {line || " "}
+              </div>
+            ))}
+      </pre>
+      <textarea
+        className="w-full text-xs rounded-md border border-sidebar-border bg-transparent p-1.5 mb-1.5"
+        rows={2}
+        placeholder="Comment (a rejection without one drops the work)"
+        value={comment}
+        onChange={(e) => setComment(e.target.value)}
+        onClick={(e) => e.stopPropagation()}
+      />
+      {error && <p className="text-[11px] text-destructive mb-1.5">{error}</p>}
+      <div className="flex justify-end gap-2">
+        <button
+          type="button"
+          className={cn(
+            "inline-flex items-center gap-1.5 px-3 py-1.5 rounded-md text-xs font-semibold transition-colors disabled:opacity-50",
+            "bg-destructive/10 text-destructive hover:bg-destructive/20",
+          )}
+          onClick={(e) => {
+            e.stopPropagation();
+            void decide("reject");
+          }}
+          disabled={sending}
+        >
+          <X className="h-3 w-3" />
+          Reject
+        </button>
+        <button
+          type="button"
+          className={cn(
+            "inline-flex items-center gap-1.5 px-3 py-1.5 rounded-md text-xs font-semibold transition-colors disabled:opacity-50",
+            "bg-success/10 text-success hover:bg-success/20",
+          )}
+          onClick={(e) => {
+            e.stopPropagation();
+            void decide("approve");
+          }}
+          disabled={sending}
+        >
+          <Check className="h-3 w-3" />
+          Approve
+        </button>
+      </div>
+    </div>
+  );
+}
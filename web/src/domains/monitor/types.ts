@@ -24,6 +24,20 @@ export interface DroneInfo {
   progress: [number, number];
   cost: CostInfo;
   liveness: string;
+  approvals: ApprovalInfo[];
+}
+
+/** Work parked at an approval gate; `task` is absent for the PR gate */
+export interface ApprovalInfo {
+  task?: number | null;
+  title: string;
+  notes: string;
+  requested: string;
+}
+
+/** An approval with its diff, from `/api/drones/{name}/approvals` */
+export interface PendingApproval extends ApprovalInfo {
+  diff: string;
 }
 
 export interface TaskInfo {
@@ -60,4 +74,16 @@ export interface CostInfo {
   output_tokens: number;
   cache_creation_tokens: number;
   cache_read_tokens: number;
+  by_task: CostBreakdownInfo[];
+  by_worker: CostBreakdownInfo[];
+}
+
+export interface CostBreakdownInfo {
+  name: string;
+  task?: string;
+  total_usd: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
 }
@@ -17,7 +17,8 @@ export const MOCK_DRONES: DroneInfo[] = [
     updated: "",
     elapsed: "1h 2m",
     progress: [2, 5],
-    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 1.24, input_tokens: 145234, output_tokens: 32456 },
+    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 1.24, input_tokens: 145234, output_tokens: 32456, by_task: [], by_worker: [] },
+    approvals: [],
     tasks: [
       {
         id: "1",
@@ -183,7 +184,8 @@ export const MOCK_DRONES: DroneInfo[] = [
     updated: "",
     elapsed: "2h 15m",
     progress: [4, 4],
-    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 3.87, input_tokens: 389000, output_tokens: 87000 },
+    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 3.87, input_tokens: 389000, output_tokens: 87000, by_task: [], by_worker: [] },
+    approvals: [],
     tasks: [
       {
         id: "1",
@@ -312,7 +314,8 @@ export const MOCK_DRONES: DroneInfo[] = [
     updated: "",
     elapsed: "1h 48m",
     progress: [1, 3],
-    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 0.52, input_tokens: 54000, output_tokens: 12800 },
+    cost: { cache_creation_tokens: 0, cache_read_tokens: 0, total_usd: 0.52, input_tokens: 54000, output_tokens: 12800, by_task: [], by_worker: [] },
+    approvals: [],
     tasks: [
       {
         id: "1",